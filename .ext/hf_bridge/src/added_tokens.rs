@@ -0,0 +1,247 @@
+use std::os::raw::c_char;
+
+use serde_json::{json, Value};
+use tokenizers::{AddedToken, Model, Tokenizer};
+
+#[derive(Debug)]
+pub(crate) enum AddedTokensError {
+    EmptyContent,
+    Missing(String),
+    Rebuild(String),
+    PinnedIdInVocab {
+        content: String,
+        id: u32,
+        occupant: String,
+    },
+    ContentInVocab {
+        content: String,
+        pinned: u32,
+        vocab_id: u32,
+    },
+    IdMismatch {
+        content: String,
+        expected: u32,
+        actual: Option<u32>,
+    },
+}
+
+impl AddedTokensError {
+    pub(crate) fn into_message(self) -> String {
+        match self {
+            AddedTokensError::EmptyContent => String::from("added token content must not be empty"),
+            AddedTokensError::Missing(content) => {
+                format!("token '{content}' has no id after being added")
+            }
+            AddedTokensError::PinnedIdInVocab {
+                content,
+                id,
+                occupant,
+            } => format!(
+                "cannot pin '{content}' to id {id}: the model vocabulary already maps it to '{occupant}'"
+            ),
+            AddedTokensError::ContentInVocab {
+                content,
+                pinned,
+                vocab_id,
+            } => format!(
+                "cannot pin '{content}' to id {pinned}: the model vocabulary already maps it to id {vocab_id}"
+            ),
+            AddedTokensError::Rebuild(reason) => {
+                format!("failed to rebuild tokenizer with updated added tokens: {reason}")
+            }
+            AddedTokensError::IdMismatch {
+                content,
+                expected,
+                actual: Some(actual),
+            } => format!("token '{content}' would receive id {actual} instead of {expected}"),
+            AddedTokensError::IdMismatch {
+                content, expected, ..
+            } => format!("token '{content}' would lose its id {expected}"),
+        }
+    }
+}
+
+/// Caller-side description of a token to add; a negative `pinned_id` lets the tokenizer pick the id.
+#[repr(C)]
+pub struct CAddedToken {
+    pub content: *const c_char,
+    pub special: bool,
+    pub single_word: bool,
+    pub lstrip: bool,
+    pub rstrip: bool,
+    pub normalized: bool,
+    pub pinned_id: i64,
+}
+
+/// An added token, optionally pinned to a specific id.
+#[derive(Debug)]
+pub(crate) struct TokenSpec {
+    pub(crate) token: AddedToken,
+    pub(crate) pinned_id: Option<u32>,
+}
+
+/// Adds `specs` and returns the id each one ended up with.
+///
+/// Unpinned tokens go through `Tokenizer::add_tokens`. `tokenizers` always hands out the next free
+/// id, so pinning rewrites the serialized added-token table instead: a pinned id that belongs to an
+/// existing added token renames that slot, otherwise it must be the next id in line. Content the
+/// model vocabulary already knows keeps its vocabulary id, so it can only be pinned to that id, and
+/// ids the model vocabulary uses for other content cannot be pinned at all; both are rejected
+/// before anything changes. The rebuilt tokenizer only replaces `tokenizer` once every expected id
+/// has been verified.
+pub(crate) fn add_tokens(
+    tokenizer: &mut Tokenizer,
+    specs: Vec<TokenSpec>,
+) -> Result<Vec<u32>, AddedTokensError> {
+    if specs.iter().any(|spec| spec.token.content.is_empty()) {
+        return Err(AddedTokensError::EmptyContent);
+    }
+
+    if specs.iter().all(|spec| spec.pinned_id.is_none()) {
+        let tokens: Vec<AddedToken> = specs.into_iter().map(|spec| spec.token).collect();
+        tokenizer.add_tokens(&tokens);
+        return tokens
+            .iter()
+            .map(|token| {
+                tokenizer
+                    .token_to_id(&token.content)
+                    .ok_or_else(|| AddedTokensError::Missing(token.content.clone()))
+            })
+            .collect();
+    }
+
+    let (mut document, mut entries) = serialized_entries(tokenizer)?;
+    let mut next_id = entries
+        .iter()
+        .map(|(id, _)| id + 1)
+        .max()
+        .unwrap_or(0)
+        .max(tokenizer.get_vocab_size(false) as u32);
+
+    let mut assigned = Vec::with_capacity(specs.len());
+    for spec in specs {
+        let model = tokenizer.get_model();
+        let vocab_id = model.token_to_id(&spec.token.content);
+        let id = match (spec.pinned_id, vocab_id) {
+            (Some(id), Some(vocab_id)) if id != vocab_id => {
+                return Err(AddedTokensError::ContentInVocab {
+                    content: spec.token.content,
+                    pinned: id,
+                    vocab_id,
+                })
+            }
+            (Some(id), Some(_)) => id,
+            (Some(id), None) => {
+                if let Some(occupant) = model.id_to_token(id) {
+                    return Err(AddedTokensError::PinnedIdInVocab {
+                        content: spec.token.content,
+                        id,
+                        occupant,
+                    });
+                }
+                id
+            }
+            (None, Some(vocab_id)) => vocab_id,
+            (None, None) => {
+                while entries.iter().any(|(existing, _)| *existing == next_id) {
+                    next_id += 1;
+                }
+                next_id
+            }
+        };
+
+        entries.retain(|(existing, entry)| {
+            *existing != id && entry["content"].as_str() != Some(spec.token.content.as_str())
+        });
+        entries.push((id, token_entry(id, &spec.token)));
+        assigned.push(id);
+    }
+
+    rebuild(tokenizer, &mut document, entries)?;
+    Ok(assigned)
+}
+
+/// Removes the added tokens whose content is listed and returns how many were removed. Removal is
+/// refused when it would shift the ids of the remaining added tokens.
+pub(crate) fn remove_tokens(
+    tokenizer: &mut Tokenizer,
+    contents: &[String],
+) -> Result<usize, AddedTokensError> {
+    let (mut document, mut entries) = serialized_entries(tokenizer)?;
+    let before = entries.len();
+    entries.retain(|(_, entry)| {
+        !entry["content"]
+            .as_str()
+            .is_some_and(|content| contents.iter().any(|candidate| candidate == content))
+    });
+
+    let removed = before - entries.len();
+    if removed > 0 {
+        rebuild(tokenizer, &mut document, entries)?;
+    }
+    Ok(removed)
+}
+
+fn serialized_entries(
+    tokenizer: &Tokenizer,
+) -> Result<(Value, Vec<(u32, Value)>), AddedTokensError> {
+    let document = serde_json::to_value(tokenizer)
+        .map_err(|err| AddedTokensError::Rebuild(err.to_string()))?;
+
+    let entries = document["added_tokens"]
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|entry| {
+                    let id = entry["id"].as_u64().and_then(|id| u32::try_from(id).ok())?;
+                    Some((id, entry.clone()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok((document, entries))
+}
+
+fn token_entry(id: u32, token: &AddedToken) -> Value {
+    json!({
+        "id": id,
+        "content": token.content,
+        "single_word": token.single_word,
+        "lstrip": token.lstrip,
+        "rstrip": token.rstrip,
+        "normalized": token.normalized,
+        "special": token.special,
+    })
+}
+
+/// Reloads the tokenizer with `entries` as its added tokens, replacing `tokenizer` only when every
+/// entry kept the id it was given.
+fn rebuild(
+    tokenizer: &mut Tokenizer,
+    document: &mut Value,
+    mut entries: Vec<(u32, Value)>,
+) -> Result<(), AddedTokensError> {
+    entries.sort_by_key(|(id, _)| *id);
+    document["added_tokens"] =
+        Value::Array(entries.iter().map(|(_, entry)| entry.clone()).collect());
+
+    let rebuilt: Tokenizer = serde_json::from_value(document.take())
+        .map_err(|err| AddedTokensError::Rebuild(err.to_string()))?;
+
+    for (expected, entry) in &entries {
+        let content = entry["content"].as_str().unwrap_or_default();
+        let actual = rebuilt.token_to_id(content);
+        if actual != Some(*expected) {
+            return Err(AddedTokensError::IdMismatch {
+                content: content.to_string(),
+                expected: *expected,
+                actual,
+            });
+        }
+    }
+
+    *tokenizer = rebuilt;
+    Ok(())
+}
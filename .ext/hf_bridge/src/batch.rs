@@ -0,0 +1,193 @@
+use std::sync::{Arc, Mutex, OnceLock};
+
+use ahash::AHashMap;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use tokenizers::tokenizer::EncodeInput;
+use tokenizers::utils::padding::pad_encodings;
+use tokenizers::{Encoding, Tokenizer};
+
+/// A dedicated pool for the requested thread budget could not be built.
+#[derive(Debug)]
+pub(crate) struct ThreadPoolError(String);
+
+#[derive(Debug)]
+pub(crate) enum BatchError {
+    ThreadPool(String),
+    Encode(String),
+}
+
+impl BatchError {
+    pub(crate) fn into_message(self) -> String {
+        match self {
+            BatchError::ThreadPool(reason) => {
+                format!("failed to build batch thread pool: {reason}")
+            }
+            BatchError::Encode(reason) => format!("batch encoding failed: {reason}"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum DecodeBatchError {
+    ThreadPool(String),
+    Decode(String),
+}
+
+impl DecodeBatchError {
+    pub(crate) fn into_message(self) -> String {
+        match self {
+            DecodeBatchError::ThreadPool(reason) => {
+                format!("failed to build batch thread pool: {reason}")
+            }
+            DecodeBatchError::Decode(reason) => format!("batch decoding failed: {reason}"),
+        }
+    }
+}
+
+/// Returns the dedicated pool for `threads`, building it on first use. Spawning workers costs far
+/// more than a typical batch, so pools live for the rest of the process, one per distinct count.
+fn dedicated_pool(threads: usize) -> Result<Arc<ThreadPool>, ThreadPoolError> {
+    static POOLS: OnceLock<Mutex<AHashMap<usize, Arc<ThreadPool>>>> = OnceLock::new();
+
+    let mut pools = POOLS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(pool) = pools.get(&threads) {
+        return Ok(Arc::clone(pool));
+    }
+
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .map_err(|err| ThreadPoolError(err.to_string()))?;
+    let pool = Arc::new(pool);
+    pools.insert(threads, Arc::clone(&pool));
+    Ok(pool)
+}
+
+/// Runs `job` according to the requested thread budget.
+///
+/// `0` uses the shared rayon pool (still subject to `TOKENIZERS_PARALLELISM`),
+/// `1` asks `job` to work sequentially, and any larger value runs inside a
+/// dedicated pool capped at that many threads, reused by later calls with the same budget.
+pub(crate) fn run_with_threads<T, F>(max_threads: usize, job: F) -> Result<T, ThreadPoolError>
+where
+    T: Send,
+    F: FnOnce(bool) -> T + Send,
+{
+    match max_threads {
+        0 => Ok(job(true)),
+        1 => Ok(job(false)),
+        threads => Ok(dedicated_pool(threads)?.install(|| job(true))),
+    }
+}
+
+/// Pairs each primary sequence with its optional companion as an `EncodeInput`.
+pub(crate) fn batch_inputs<'s>(
+    sequences: &'s [String],
+    pairs: Option<&'s [Option<String>]>,
+) -> Vec<EncodeInput<'s>> {
+    match pairs {
+        Some(pair_values) => sequences
+            .iter()
+            .zip(pair_values.iter())
+            .map(|(sequence, pair)| match pair {
+                Some(pair_value) => EncodeInput::from((sequence.as_str(), pair_value.as_str())),
+                None => EncodeInput::from(sequence.as_str()),
+            })
+            .collect(),
+        None => sequences
+            .iter()
+            .map(|sequence| EncodeInput::from(sequence.as_str()))
+            .collect(),
+    }
+}
+
+/// Encodes `inputs` with character offsets and applies the tokenizer's padding
+/// across the whole batch, mirroring `Tokenizer::encode_batch_char_offsets`.
+pub(crate) fn encode_batch(
+    tokenizer: &Tokenizer,
+    inputs: Vec<EncodeInput<'_>>,
+    add_special_tokens: bool,
+    max_threads: usize,
+) -> Result<Vec<Encoding>, BatchError> {
+    encode_batch_with(tokenizer, inputs, add_special_tokens, max_threads, true)
+}
+
+/// Same as [`encode_batch`] but skips offset tracking, for callers that only read ids and masks.
+pub(crate) fn encode_batch_fast(
+    tokenizer: &Tokenizer,
+    inputs: Vec<EncodeInput<'_>>,
+    add_special_tokens: bool,
+    max_threads: usize,
+) -> Result<Vec<Encoding>, BatchError> {
+    encode_batch_with(tokenizer, inputs, add_special_tokens, max_threads, false)
+}
+
+fn encode_batch_with(
+    tokenizer: &Tokenizer,
+    inputs: Vec<EncodeInput<'_>>,
+    add_special_tokens: bool,
+    max_threads: usize,
+    char_offsets: bool,
+) -> Result<Vec<Encoding>, BatchError> {
+    run_with_threads(max_threads, |parallel| {
+        if parallel {
+            return if char_offsets {
+                tokenizer.encode_batch_char_offsets(inputs, add_special_tokens)
+            } else {
+                tokenizer.encode_batch_fast(inputs, add_special_tokens)
+            };
+        }
+
+        let mut encodings = inputs
+            .into_iter()
+            .map(|input| {
+                if char_offsets {
+                    tokenizer.encode_char_offsets(input, add_special_tokens)
+                } else {
+                    tokenizer.encode_fast(input, add_special_tokens)
+                }
+            })
+            .collect::<tokenizers::Result<Vec<Encoding>>>()?;
+
+        if let Some(params) = tokenizer.get_padding() {
+            pad_encodings(&mut encodings, params)?;
+        }
+
+        Ok(encodings)
+    })
+    .map_err(|ThreadPoolError(reason)| BatchError::ThreadPool(reason))?
+    .map_err(|err| BatchError::Encode(err.to_string()))
+}
+
+/// Decodes every id sequence, mirroring `Tokenizer::decode_batch` under the same thread budget rules.
+pub(crate) fn decode_batch(
+    tokenizer: &Tokenizer,
+    sequences: &[&[u32]],
+    skip_special_tokens: bool,
+    max_threads: usize,
+) -> Result<Vec<String>, DecodeBatchError> {
+    run_with_threads(max_threads, |parallel| {
+        if parallel {
+            return tokenizer.decode_batch(sequences, skip_special_tokens);
+        }
+
+        sequences
+            .iter()
+            .map(|ids| tokenizer.decode(ids, skip_special_tokens))
+            .collect::<tokenizers::Result<Vec<String>>>()
+    })
+    .map_err(|ThreadPoolError(reason)| DecodeBatchError::ThreadPool(reason))?
+    .map_err(|err| DecodeBatchError::Decode(err.to_string()))
+}
+
+/// Number of attended tokens in `encoding`, i.e. its length without padding.
+pub(crate) fn attended_length(encoding: &Encoding) -> usize {
+    encoding
+        .get_attention_mask()
+        .iter()
+        .filter(|mask| **mask != 0)
+        .count()
+}
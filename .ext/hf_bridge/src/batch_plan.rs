@@ -0,0 +1,164 @@
+use serde::Serialize;
+use tokenizers::utils::padding::{PaddingParams, PaddingStrategy};
+use tokenizers::Encoding;
+
+const PLAN_INDEX_ERROR: &str = "batch plan index out of range";
+
+/// Row and padded-token limits for a planned batch; `0` leaves a limit unbounded.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct BatchBudget {
+    pub(crate) max_rows: usize,
+    pub(crate) max_tokens: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct PlannedBatch {
+    #[serde(rename = "indices")]
+    pub(crate) indices: Vec<usize>,
+    #[serde(rename = "padded_length")]
+    pub(crate) padded_length: usize,
+    #[serde(rename = "padding_waste")]
+    pub(crate) padding_waste: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct PlanSummary<'a> {
+    #[serde(rename = "batches")]
+    batches: &'a [PlannedBatch],
+    #[serde(rename = "padding_waste")]
+    padding_waste: f64,
+}
+
+/// Length-bucketed batches over a set of unpadded encodings, ready to be materialized as tensors.
+pub struct CBatchPlan {
+    encodings: Vec<Encoding>,
+    padding: PaddingParams,
+    batches: Vec<PlannedBatch>,
+}
+
+impl CBatchPlan {
+    /// Sorts `encodings` by length and greedily fills batches until adding the next sequence
+    /// would break `budget`. A sequence that alone exceeds the token budget gets its own batch.
+    pub(crate) fn new(
+        encodings: Vec<Encoding>,
+        padding: PaddingParams,
+        budget: BatchBudget,
+    ) -> Self {
+        let mut order: Vec<usize> = (0..encodings.len()).collect();
+        order.sort_by_key(|index| encodings[*index].len());
+
+        let mut batches = Vec::new();
+        let mut current: Vec<usize> = Vec::new();
+        for index in order {
+            let rows = current.len() + 1;
+            let padded = padded_length(&padding, encodings[index].len());
+            let over_rows = budget.max_rows > 0 && rows > budget.max_rows;
+            let over_tokens = budget.max_tokens > 0 && padded * rows > budget.max_tokens;
+
+            if !current.is_empty() && (over_rows || over_tokens) {
+                batches.push(plan_batch(
+                    &encodings,
+                    &padding,
+                    std::mem::take(&mut current),
+                ));
+            }
+            current.push(index);
+        }
+
+        if !current.is_empty() {
+            batches.push(plan_batch(&encodings, &padding, current));
+        }
+
+        Self {
+            encodings,
+            padding,
+            batches,
+        }
+    }
+
+    pub(crate) fn batch_count(&self) -> usize {
+        self.batches.len()
+    }
+
+    pub(crate) fn batch(&self, index: usize) -> Result<&PlannedBatch, &'static str> {
+        self.batches.get(index).ok_or(PLAN_INDEX_ERROR)
+    }
+
+    pub(crate) fn to_json(&self) -> serde_json::Result<String> {
+        let padded: usize = self
+            .batches
+            .iter()
+            .map(|batch| batch.padded_length * batch.indices.len())
+            .sum();
+        let real: usize = self.encodings.iter().map(Encoding::len).sum();
+
+        serde_json::to_string(&PlanSummary {
+            batches: &self.batches,
+            padding_waste: waste_ratio(padded, real),
+        })
+    }
+
+    /// Returns the rows of batch `index` padded to its planned length, in plan order.
+    pub(crate) fn padded_rows(&self, index: usize) -> Result<Vec<Encoding>, &'static str> {
+        let batch = self.batch(index)?;
+        Ok(batch
+            .indices
+            .iter()
+            .map(|row| {
+                let mut encoding = self.encodings[*row].clone();
+                encoding.pad(
+                    batch.padded_length,
+                    self.padding.pad_id,
+                    self.padding.pad_type_id,
+                    &self.padding.pad_token,
+                    self.padding.direction,
+                );
+                encoding
+            })
+            .collect())
+    }
+}
+
+/// Applies the padding strategy to a batch whose longest row is `longest` tokens.
+fn padded_length(padding: &PaddingParams, longest: usize) -> usize {
+    let mut length = match padding.strategy {
+        PaddingStrategy::Fixed(size) => size.max(longest),
+        PaddingStrategy::BatchLongest => longest,
+    };
+
+    if let Some(multiple) = padding.pad_to_multiple_of {
+        if multiple > 0 && length % multiple > 0 {
+            length += multiple - length % multiple;
+        }
+    }
+
+    length
+}
+
+fn plan_batch(
+    encodings: &[Encoding],
+    padding: &PaddingParams,
+    indices: Vec<usize>,
+) -> PlannedBatch {
+    let longest = indices
+        .iter()
+        .map(|index| encodings[*index].len())
+        .max()
+        .unwrap_or(0);
+    let padded = padded_length(padding, longest);
+    let real: usize = indices.iter().map(|index| encodings[*index].len()).sum();
+
+    PlannedBatch {
+        padding_waste: waste_ratio(padded * indices.len(), real),
+        indices,
+        padded_length: padded,
+    }
+}
+
+fn waste_ratio(padded: usize, real: usize) -> f64 {
+    if padded == 0 {
+        0.0
+    } else {
+        (padded - real) as f64 / padded as f64
+    }
+}
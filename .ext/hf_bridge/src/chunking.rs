@@ -0,0 +1,192 @@
+use std::os::raw::c_int;
+
+use serde::Serialize;
+use tokenizers::{Encoding, Tokenizer};
+
+use crate::offsets::{OffsetConverter, OffsetMode};
+
+const SENTENCE_TERMINATORS: [char; 4] = ['.', '!', '?', '\u{2026}'];
+const FULLWIDTH_TERMINATORS: [char; 3] = ['\u{3002}', '\u{FF01}', '\u{FF1F}'];
+
+#[derive(Debug)]
+pub(crate) enum ChunkingError {
+    InvalidWindow(String),
+    Encode(String),
+    Decode(String),
+}
+
+impl ChunkingError {
+    pub(crate) fn into_message(self) -> String {
+        match self {
+            ChunkingError::InvalidWindow(reason) => format!("invalid chunk window: {reason}"),
+            ChunkingError::Encode(reason) => format!("failed to encode document: {reason}"),
+            ChunkingError::Decode(reason) => format!("failed to decode chunk: {reason}"),
+        }
+    }
+}
+
+/// Where a chunk is allowed to end when it cannot take the full window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ChunkBoundary {
+    Token,
+    Whitespace,
+    Sentence,
+}
+
+impl ChunkBoundary {
+    pub(crate) fn from_raw(value: c_int) -> Result<Self, &'static str> {
+        match value {
+            0 => Ok(ChunkBoundary::Token),
+            1 => Ok(ChunkBoundary::Whitespace),
+            2 => Ok(ChunkBoundary::Sentence),
+            _ => Err("unknown chunk boundary mode"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ChunkWindow {
+    pub(crate) max_tokens: usize,
+    pub(crate) overlap: usize,
+    pub(crate) reserved_tokens: usize,
+    pub(crate) boundary: ChunkBoundary,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct TextChunk {
+    #[serde(rename = "ids")]
+    pub(crate) ids: Vec<u32>,
+    #[serde(rename = "start")]
+    pub(crate) start: u32,
+    #[serde(rename = "end")]
+    pub(crate) end: u32,
+    #[serde(rename = "text")]
+    pub(crate) text: String,
+}
+
+/// Splits `text` into overlapping windows of at most `max_tokens - reserved_tokens` tokens.
+/// `tokenizer` must not truncate or pad (see `CTokenizer::unconstrained`).
+pub(crate) fn chunk_text(
+    tokenizer: &Tokenizer,
+    text: &str,
+    window: ChunkWindow,
+    mode: OffsetMode,
+) -> Result<Vec<TextChunk>, ChunkingError> {
+    let capacity = window.max_tokens.saturating_sub(window.reserved_tokens);
+    if capacity == 0 {
+        return Err(ChunkingError::InvalidWindow(String::from(
+            "max_tokens must exceed reserved_tokens",
+        )));
+    }
+
+    if window.overlap >= capacity {
+        return Err(ChunkingError::InvalidWindow(format!(
+            "overlap ({}) must be smaller than the usable window ({capacity})",
+            window.overlap
+        )));
+    }
+
+    let encoding = tokenizer
+        .encode(text, false)
+        .map_err(|err| ChunkingError::Encode(err.to_string()))?;
+    let converter = OffsetConverter::new(text, mode);
+    let total = encoding.len();
+    let mut chunks = Vec::new();
+    let mut start = 0usize;
+
+    while start < total {
+        let mut end = (start + capacity).min(total);
+        if end < total {
+            end = boundary_end(
+                text,
+                &encoding,
+                start + window.overlap,
+                end,
+                window.boundary,
+            );
+        }
+
+        let ids = encoding.get_ids()[start..end].to_vec();
+        let offsets = encoding.get_offsets();
+        let (span_start, span_end) = converter.convert(offsets[start].0, offsets[end - 1].1);
+        let decoded = tokenizer
+            .decode(&ids, false)
+            .map_err(|err| ChunkingError::Decode(err.to_string()))?;
+
+        chunks.push(TextChunk {
+            ids,
+            start: span_start,
+            end: span_end,
+            text: decoded,
+        });
+
+        if end == total {
+            break;
+        }
+        start = end - window.overlap;
+    }
+
+    Ok(chunks)
+}
+
+/// Picks the latest token index in `(floor, end]` the boundary mode accepts, falling back to a
+/// whitespace break for sentences and to a hard cut at `end` when nothing qualifies.
+fn boundary_end(
+    text: &str,
+    encoding: &Encoding,
+    floor: usize,
+    end: usize,
+    boundary: ChunkBoundary,
+) -> usize {
+    let last_whitespace = || {
+        (floor + 1..=end)
+            .rev()
+            .find(|index| is_whitespace_break(text, encoding, *index))
+    };
+
+    match boundary {
+        ChunkBoundary::Token => end,
+        ChunkBoundary::Whitespace => last_whitespace().unwrap_or(end),
+        ChunkBoundary::Sentence => (floor + 1..=end)
+            .rev()
+            .find(|index| is_sentence_break(text, encoding, *index))
+            .or_else(last_whitespace)
+            .unwrap_or(end),
+    }
+}
+
+/// True when whitespace separates token `index - 1` from token `index`, wherever the tokenizer
+/// placed it (between spans, trailing the previous token, or leading the next one).
+fn is_whitespace_break(text: &str, encoding: &Encoding, index: usize) -> bool {
+    let offsets = encoding.get_offsets();
+    let (previous_start, previous_end) = offsets[index - 1];
+    let (next_start, next_end) = offsets[index];
+    let previous_tail = text
+        .get(previous_start..previous_end)
+        .and_then(|slice| slice.chars().next_back());
+    let between = if next_start > previous_end {
+        text.get(previous_end..next_start).unwrap_or("")
+    } else {
+        ""
+    };
+    let next_head = text
+        .get(next_start..next_end)
+        .and_then(|slice| slice.chars().next());
+
+    previous_tail.is_some_and(char::is_whitespace)
+        || between.chars().any(char::is_whitespace)
+        || next_head.is_some_and(char::is_whitespace)
+}
+
+fn is_sentence_break(text: &str, encoding: &Encoding, index: usize) -> bool {
+    let (_, previous_end) = encoding.get_offsets()[index - 1];
+    let Some(last) = text
+        .get(..previous_end)
+        .and_then(|prefix| prefix.trim_end().chars().next_back())
+    else {
+        return false;
+    };
+
+    FULLWIDTH_TERMINATORS.contains(&last)
+        || (SENTENCE_TERMINATORS.contains(&last) && is_whitespace_break(text, encoding, index))
+}
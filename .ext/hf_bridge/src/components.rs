@@ -0,0 +1,82 @@
+use serde::Serialize;
+use tokenizers::{
+    DecoderWrapper, NormalizerWrapper, PostProcessorWrapper, PreTokenizerWrapper, Tokenizer,
+};
+
+/// Pipeline stage addressed by the component setters and getters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Component {
+    Normalizer,
+    PreTokenizer,
+    PostProcessor,
+    Decoder,
+}
+
+#[derive(Debug)]
+pub(crate) enum ComponentError {
+    Parse(String),
+    Serialize(String),
+}
+
+impl ComponentError {
+    pub(crate) fn into_message(self) -> String {
+        match self {
+            ComponentError::Parse(reason) => format!("failed to parse component: {reason}"),
+            ComponentError::Serialize(reason) => {
+                format!("failed to serialize component: {reason}")
+            }
+        }
+    }
+}
+
+fn parse<T: serde::de::DeserializeOwned>(json: Option<&str>) -> Result<Option<T>, ComponentError> {
+    json.map(serde_json::from_str)
+        .transpose()
+        .map_err(|err| ComponentError::Parse(err.to_string()))
+}
+
+fn serialize<T: Serialize>(component: Option<&T>) -> Result<String, ComponentError> {
+    serde_json::to_string(&component).map_err(|err| ComponentError::Serialize(err.to_string()))
+}
+
+/// Replaces `component` with the one described by `json`, in tokenizer.json format, or removes it
+/// when `json` is `None`. The tokenizer is left untouched when parsing fails.
+pub(crate) fn set_component(
+    tokenizer: &mut Tokenizer,
+    component: Component,
+    json: Option<&str>,
+) -> Result<(), ComponentError> {
+    match component {
+        Component::Normalizer => {
+            let normalizer = parse::<NormalizerWrapper>(json)?;
+            tokenizer.with_normalizer(normalizer);
+            // Added tokens marked `normalized` are matched against normalized text, so their
+            // matcher has to be rebuilt for the new normalizer.
+            tokenizer.add_tokens(&[]);
+        }
+        Component::PreTokenizer => {
+            tokenizer.with_pre_tokenizer(parse::<PreTokenizerWrapper>(json)?);
+        }
+        Component::PostProcessor => {
+            tokenizer.with_post_processor(parse::<PostProcessorWrapper>(json)?);
+        }
+        Component::Decoder => {
+            tokenizer.with_decoder(parse::<DecoderWrapper>(json)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Serializes `component` in tokenizer.json format, or `null` when it is not set.
+pub(crate) fn get_component(
+    tokenizer: &Tokenizer,
+    component: Component,
+) -> Result<String, ComponentError> {
+    match component {
+        Component::Normalizer => serialize(tokenizer.get_normalizer()),
+        Component::PreTokenizer => serialize(tokenizer.get_pre_tokenizer()),
+        Component::PostProcessor => serialize(tokenizer.get_post_processor()),
+        Component::Decoder => serialize(tokenizer.get_decoder()),
+    }
+}
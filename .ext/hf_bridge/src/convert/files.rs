@@ -0,0 +1,76 @@
+use std::fs;
+use std::path::Path;
+
+use serde_json::{Map, Value};
+
+use super::ConvertError;
+
+fn read_text(path: &Path) -> Result<String, ConvertError> {
+    fs::read_to_string(path)
+        .map_err(|err| ConvertError::Io(format!("failed to read {}: {err}", path.display())))
+}
+
+pub(crate) fn read_bytes(path: &Path) -> Result<Vec<u8>, ConvertError> {
+    fs::read(path)
+        .map_err(|err| ConvertError::Io(format!("failed to read {}: {err}", path.display())))
+}
+
+/// Reads a WordPiece `vocab.txt`: one token per line, ids by line number. Only the line break is
+/// stripped and a repeated token keeps its last id, as in `transformers`' `load_vocab`.
+pub(crate) fn read_vocab_txt(path: &Path) -> Result<Map<String, Value>, ConvertError> {
+    let content = read_text(path)?;
+    let mut vocab = Map::new();
+    for (id, line) in content.lines().enumerate() {
+        vocab.insert(line.to_string(), Value::from(id));
+    }
+
+    if vocab.is_empty() {
+        return Err(ConvertError::Format(format!(
+            "{} contains no tokens",
+            path.display()
+        )));
+    }
+    Ok(vocab)
+}
+
+/// Reads a BPE `vocab.json` mapping tokens to ids.
+pub(crate) fn read_vocab_json(path: &Path) -> Result<Map<String, Value>, ConvertError> {
+    let content = read_text(path)?;
+    let vocab: Map<String, Value> = serde_json::from_str(&content).map_err(|err| {
+        ConvertError::Format(format!("{} is not a token map: {err}", path.display()))
+    })?;
+
+    if let Some((token, _)) = vocab.iter().find(|(_, id)| id.as_u64().is_none()) {
+        return Err(ConvertError::Format(format!(
+            "{} maps '{token}' to a non-integer id",
+            path.display()
+        )));
+    }
+    Ok(vocab)
+}
+
+/// Reads a BPE `merges.txt`, skipping the `#version` header and blank lines.
+pub(crate) fn read_merges_txt(path: &Path) -> Result<Vec<(String, String)>, ConvertError> {
+    let content = read_text(path)?;
+    let mut merges = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        if line.is_empty() || (index == 0 && line.starts_with("#version")) {
+            continue;
+        }
+
+        let mut parts = line.split(' ');
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(left), Some(right), None) if !left.is_empty() && !right.is_empty() => {
+                merges.push((left.to_string(), right.to_string()));
+            }
+            _ => {
+                return Err(ConvertError::Format(format!(
+                    "{} line {} is not a merge pair",
+                    path.display(),
+                    index + 1
+                )));
+            }
+        }
+    }
+    Ok(merges)
+}
@@ -0,0 +1,703 @@
+use std::os::raw::c_int;
+use std::path::Path;
+use std::str::FromStr;
+
+use serde_json::{json, Map, Value};
+use tokenizers::normalizers::precompiled::Precompiled;
+use tokenizers::Tokenizer;
+
+pub(crate) mod files;
+pub(crate) mod sentencepiece;
+
+use sentencepiece::{extract_merges, PieceType, SentencePieceModel, SpmModelType};
+
+const SPIECE_UNDERLINE: &str = "\u{2581}";
+
+#[derive(Debug)]
+pub(crate) enum ConvertError {
+    Io(String),
+    Format(String),
+    UnknownFamily(String),
+    Build(String),
+}
+
+impl ConvertError {
+    pub(crate) fn into_message(self) -> String {
+        match self {
+            ConvertError::Io(reason) => reason,
+            ConvertError::Format(reason) => format!("invalid legacy tokenizer files: {reason}"),
+            ConvertError::UnknownFamily(reason) => {
+                format!("cannot determine tokenizer family: {reason}")
+            }
+            ConvertError::Build(reason) => format!("failed to build converted tokenizer: {reason}"),
+        }
+    }
+}
+
+/// Slow tokenizer families with a `transformers` slow-to-fast converter counterpart.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum LegacyFamily {
+    Bert,
+    Gpt2,
+    Roberta,
+    Llama,
+    T5,
+    XlmRoberta,
+}
+
+impl LegacyFamily {
+    /// Maps the FFI family code; `-1` asks for detection and yields `None`.
+    pub(crate) fn from_raw(value: c_int) -> Result<Option<Self>, &'static str> {
+        match value {
+            -1 => Ok(None),
+            0 => Ok(Some(LegacyFamily::Bert)),
+            1 => Ok(Some(LegacyFamily::Gpt2)),
+            2 => Ok(Some(LegacyFamily::Roberta)),
+            3 => Ok(Some(LegacyFamily::Llama)),
+            4 => Ok(Some(LegacyFamily::T5)),
+            5 => Ok(Some(LegacyFamily::XlmRoberta)),
+            _ => Err("unknown legacy tokenizer family"),
+        }
+    }
+
+    /// Maps a `tokenizer_class` from tokenizer_config.json, with or without the `Fast` suffix.
+    pub(crate) fn from_class(name: &str) -> Option<Self> {
+        match name.strip_suffix("Fast").unwrap_or(name) {
+            "BertTokenizer" | "DistilBertTokenizer" => Some(LegacyFamily::Bert),
+            "GPT2Tokenizer" => Some(LegacyFamily::Gpt2),
+            "RobertaTokenizer" => Some(LegacyFamily::Roberta),
+            "LlamaTokenizer" => Some(LegacyFamily::Llama),
+            "T5Tokenizer" => Some(LegacyFamily::T5),
+            "XLMRobertaTokenizer" => Some(LegacyFamily::XlmRoberta),
+            _ => None,
+        }
+    }
+}
+
+/// Settings read from tokenizer_config.json that change how the slow tokenizer behaves.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ConvertHints {
+    pub(crate) tokenizer_class: Option<String>,
+    pub(crate) do_lower_case: Option<bool>,
+    pub(crate) strip_accents: Option<bool>,
+    pub(crate) tokenize_chinese_chars: Option<bool>,
+    pub(crate) add_prefix_space: Option<bool>,
+    pub(crate) add_bos_token: Option<bool>,
+    pub(crate) add_eos_token: Option<bool>,
+    pub(crate) legacy: Option<bool>,
+    pub(crate) extra_ids: Option<usize>,
+    pub(crate) unk_token: Option<String>,
+    pub(crate) bos_token: Option<String>,
+    pub(crate) eos_token: Option<String>,
+    pub(crate) pad_token: Option<String>,
+    pub(crate) cls_token: Option<String>,
+    pub(crate) sep_token: Option<String>,
+    pub(crate) mask_token: Option<String>,
+    pub(crate) additional_special_tokens: Vec<String>,
+}
+
+/// Special tokens may be stored as plain strings or as serialized `AddedToken` objects.
+fn token_content(value: &Value) -> Option<String> {
+    match value {
+        Value::String(content) => Some(content.clone()),
+        Value::Object(fields) => fields
+            .get("content")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        _ => None,
+    }
+}
+
+impl ConvertHints {
+    pub(crate) fn from_config(json: Option<&str>) -> Result<Self, ConvertError> {
+        let Some(json) = json else {
+            return Ok(ConvertHints::default());
+        };
+
+        let config: Map<String, Value> = serde_json::from_str(json).map_err(|err| {
+            ConvertError::Format(format!("tokenizer_config.json is not an object: {err}"))
+        })?;
+        let flag = |key: &str| config.get(key).and_then(Value::as_bool);
+        let token = |key: &str| config.get(key).and_then(token_content);
+
+        Ok(ConvertHints {
+            tokenizer_class: config
+                .get("tokenizer_class")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            do_lower_case: flag("do_lower_case"),
+            strip_accents: flag("strip_accents"),
+            tokenize_chinese_chars: flag("tokenize_chinese_chars"),
+            add_prefix_space: flag("add_prefix_space"),
+            add_bos_token: flag("add_bos_token"),
+            add_eos_token: flag("add_eos_token"),
+            legacy: flag("legacy"),
+            extra_ids: config
+                .get("extra_ids")
+                .and_then(Value::as_u64)
+                .map(|count| count as usize),
+            unk_token: token("unk_token"),
+            bos_token: token("bos_token"),
+            eos_token: token("eos_token"),
+            pad_token: token("pad_token"),
+            cls_token: token("cls_token"),
+            sep_token: token("sep_token"),
+            mask_token: token("mask_token"),
+            additional_special_tokens: config
+                .get("additional_special_tokens")
+                .and_then(Value::as_array)
+                .map(|entries| entries.iter().filter_map(token_content).collect())
+                .unwrap_or_default(),
+        })
+    }
+}
+
+fn pick(hint: &Option<String>, default: &str) -> String {
+    hint.clone().unwrap_or_else(|| default.to_string())
+}
+
+/// Builds a tokenizer equivalent to the `transformers` fast conversion of a slow tokenizer.
+///
+/// `primary` is `vocab.txt` (BERT), `vocab.json` (GPT-2, RoBERTa) or a SentencePiece `.model`
+/// (Llama, T5, XLM-R); `secondary` is the `merges.txt` the byte-level BPE families need. Without
+/// an explicit `family` the `tokenizer_class` hint decides, then the shape of the files.
+pub(crate) fn convert(
+    family: Option<LegacyFamily>,
+    primary: &Path,
+    secondary: Option<&Path>,
+    hints: &ConvertHints,
+) -> Result<Tokenizer, ConvertError> {
+    let family = match family {
+        Some(family) => family,
+        None => detect_family(primary, secondary, hints)?,
+    };
+
+    let document = match family {
+        LegacyFamily::Bert => convert_bert(primary, hints)?,
+        LegacyFamily::Gpt2 | LegacyFamily::Roberta => {
+            let merges = secondary.ok_or_else(|| {
+                ConvertError::Format(String::from(
+                    "byte-level BPE conversion requires merges.txt",
+                ))
+            })?;
+            convert_byte_level_bpe(family, primary, merges, hints)?
+        }
+        LegacyFamily::Llama | LegacyFamily::T5 | LegacyFamily::XlmRoberta => {
+            let proto = SentencePieceModel::parse(&files::read_bytes(primary)?)?;
+            match family {
+                LegacyFamily::Llama => convert_llama(&proto, hints)?,
+                _ => convert_unigram(family, &proto, hints)?,
+            }
+        }
+    };
+
+    Tokenizer::from_str(&document.to_string()).map_err(|err| ConvertError::Build(err.to_string()))
+}
+
+fn detect_family(
+    primary: &Path,
+    secondary: Option<&Path>,
+    hints: &ConvertHints,
+) -> Result<LegacyFamily, ConvertError> {
+    if let Some(class) = hints.tokenizer_class.as_deref() {
+        return LegacyFamily::from_class(class).ok_or_else(|| {
+            ConvertError::UnknownFamily(format!("unsupported tokenizer_class '{class}'"))
+        });
+    }
+
+    match primary.extension().and_then(|extension| extension.to_str()) {
+        Some("txt") => Ok(LegacyFamily::Bert),
+        Some("json") if secondary.is_some() => Ok(LegacyFamily::Gpt2),
+        _ => Err(ConvertError::UnknownFamily(format!(
+            "{} needs an explicit family or a tokenizer_class hint",
+            primary.display()
+        ))),
+    }
+}
+
+/// Collects the pieces of a tokenizer.json document before it is parsed.
+struct Document {
+    vocab: Map<String, Value>,
+    added_tokens: Vec<Value>,
+}
+
+impl Document {
+    fn new(vocab: Map<String, Value>) -> Self {
+        Document {
+            vocab,
+            added_tokens: Vec::new(),
+        }
+    }
+
+    fn id(&self, token: &str) -> Option<u64> {
+        self.vocab.get(token).and_then(Value::as_u64)
+    }
+
+    fn require_id(&self, token: &str) -> Result<u64, ConvertError> {
+        self.id(token).ok_or_else(|| {
+            ConvertError::Format(format!("special token '{token}' is missing from the vocab"))
+        })
+    }
+
+    /// Registers `token` as an added token when the vocab knows it; unknown tokens are skipped, as
+    /// `transformers` only carries over special tokens that exist in the slow vocab.
+    fn add(&mut self, token: &str, special: bool, lstrip: bool) {
+        let Some(id) = self.id(token) else {
+            return;
+        };
+        if self
+            .added_tokens
+            .iter()
+            .any(|entry| entry["content"] == token)
+        {
+            return;
+        }
+
+        self.added_tokens.push(json!({
+            "id": id,
+            "content": token,
+            "single_word": false,
+            "lstrip": lstrip,
+            "rstrip": false,
+            "normalized": false,
+            "special": special,
+        }));
+    }
+
+    fn add_special(&mut self, tokens: &[&str], hints: &ConvertHints) {
+        for token in tokens {
+            self.add(token, true, false);
+        }
+        for token in &hints.additional_special_tokens {
+            self.add(token, true, false);
+        }
+    }
+
+    /// `pieces` is a template such as `[("<s>", 0), ("$A", 0), ("</s>", 0)]`, where `$A` and `$B`
+    /// stand for the input sequences.
+    fn template(
+        &self,
+        single: &[(&str, u32)],
+        pair: &[(&str, u32)],
+    ) -> Result<Value, ConvertError> {
+        let mut special_tokens = Map::new();
+        let mut items = |pieces: &[(&str, u32)]| -> Result<Vec<Value>, ConvertError> {
+            pieces
+                .iter()
+                .map(|(piece, type_id)| match piece.strip_prefix('$') {
+                    Some(sequence) => Ok(json!({"Sequence": {"id": sequence, "type_id": type_id}})),
+                    None => {
+                        let id = self.require_id(piece)?;
+                        special_tokens.insert(
+                            piece.to_string(),
+                            json!({"id": piece, "ids": [id], "tokens": [piece]}),
+                        );
+                        Ok(json!({"SpecialToken": {"id": piece, "type_id": type_id}}))
+                    }
+                })
+                .collect()
+        };
+
+        let single = items(single)?;
+        let pair = items(pair)?;
+        Ok(json!({
+            "type": "TemplateProcessing",
+            "single": single,
+            "pair": pair,
+            "special_tokens": special_tokens,
+        }))
+    }
+
+    fn finish(
+        mut self,
+        model: Value,
+        normalizer: Value,
+        pre_tokenizer: Value,
+        post_processor: Value,
+        decoder: Value,
+    ) -> Value {
+        self.added_tokens
+            .sort_by_key(|entry| entry["id"].as_u64().unwrap_or(u64::MAX));
+        json!({
+            "version": "1.0",
+            "truncation": null,
+            "padding": null,
+            "added_tokens": self.added_tokens,
+            "normalizer": normalizer,
+            "pre_tokenizer": pre_tokenizer,
+            "post_processor": post_processor,
+            "decoder": decoder,
+            "model": model,
+        })
+    }
+}
+
+/// `BertConverter`: WordPiece with the BERT normalizer and `[CLS] A [SEP] B [SEP]` template.
+fn convert_bert(vocab_path: &Path, hints: &ConvertHints) -> Result<Value, ConvertError> {
+    let mut document = Document::new(files::read_vocab_txt(vocab_path)?);
+    let unk = pick(&hints.unk_token, "[UNK]");
+    let cls = pick(&hints.cls_token, "[CLS]");
+    let sep = pick(&hints.sep_token, "[SEP]");
+    let pad = pick(&hints.pad_token, "[PAD]");
+    let mask = pick(&hints.mask_token, "[MASK]");
+    document.add_special(&[&pad, &unk, &cls, &sep, &mask], hints);
+
+    let post_processor = document.template(
+        &[(&cls, 0), ("$A", 0), (&sep, 0)],
+        &[(&cls, 0), ("$A", 0), (&sep, 0), ("$B", 1), (&sep, 1)],
+    )?;
+    let model = json!({
+        "type": "WordPiece",
+        "unk_token": unk,
+        "continuing_subword_prefix": "##",
+        "max_input_chars_per_word": 100,
+        "vocab": document.vocab,
+    });
+
+    Ok(document.finish(
+        model,
+        json!({
+            "type": "BertNormalizer",
+            "clean_text": true,
+            "handle_chinese_chars": hints.tokenize_chinese_chars.unwrap_or(true),
+            "strip_accents": hints.strip_accents,
+            "lowercase": hints.do_lower_case.unwrap_or(true),
+        }),
+        json!({"type": "BertPreTokenizer"}),
+        post_processor,
+        json!({"type": "WordPiece", "prefix": "##", "cleanup": true}),
+    ))
+}
+
+/// `GPT2Converter` and `RobertaConverter`: byte-level BPE that differ only in their special tokens
+/// and post-processor.
+fn convert_byte_level_bpe(
+    family: LegacyFamily,
+    vocab_path: &Path,
+    merges_path: &Path,
+    hints: &ConvertHints,
+) -> Result<Value, ConvertError> {
+    let mut document = Document::new(files::read_vocab_json(vocab_path)?);
+    let merges = files::read_merges_txt(merges_path)?;
+    let add_prefix_space = hints.add_prefix_space.unwrap_or(false);
+
+    let post_processor = if family == LegacyFamily::Roberta {
+        let bos = pick(&hints.bos_token, "<s>");
+        let eos = pick(&hints.eos_token, "</s>");
+        let cls = pick(&hints.cls_token, "<s>");
+        let sep = pick(&hints.sep_token, "</s>");
+        let unk = pick(&hints.unk_token, "<unk>");
+        let pad = pick(&hints.pad_token, "<pad>");
+        let mask = pick(&hints.mask_token, "<mask>");
+        document.add_special(&[&bos, &pad, &eos, &unk], hints);
+        document.add(&mask, true, true);
+        json!({
+            "type": "RobertaProcessing",
+            "sep": [sep, document.require_id(&sep)?],
+            "cls": [cls, document.require_id(&cls)?],
+            "trim_offsets": true,
+            "add_prefix_space": add_prefix_space,
+        })
+    } else {
+        let bos = pick(&hints.bos_token, "<|endoftext|>");
+        let eos = pick(&hints.eos_token, "<|endoftext|>");
+        let unk = pick(&hints.unk_token, "<|endoftext|>");
+        document.add_special(&[&bos, &eos, &unk], hints);
+        if hints.add_bos_token.unwrap_or(false) {
+            document.template(&[(&bos, 0), ("$A", 0)], &[(&bos, 0), ("$A", 0), ("$B", 1)])?
+        } else {
+            json!({
+                "type": "ByteLevel",
+                "add_prefix_space": true,
+                "trim_offsets": false,
+                "use_regex": true,
+            })
+        }
+    };
+
+    let model = json!({
+        "type": "BPE",
+        "dropout": null,
+        "unk_token": null,
+        "continuing_subword_prefix": "",
+        "end_of_word_suffix": "",
+        "fuse_unk": false,
+        "byte_fallback": false,
+        "ignore_merges": false,
+        "vocab": document.vocab,
+        "merges": merges,
+    });
+
+    Ok(document.finish(
+        model,
+        Value::Null,
+        json!({
+            "type": "ByteLevel",
+            "add_prefix_space": add_prefix_space,
+            "trim_offsets": true,
+            "use_regex": true,
+        }),
+        post_processor,
+        json!({
+            "type": "ByteLevel",
+            "add_prefix_space": true,
+            "trim_offsets": true,
+            "use_regex": true,
+        }),
+    ))
+}
+
+/// Registers the SentencePiece control and unknown pieces as special added tokens and the
+/// user-defined pieces as plain ones, as `SpmConverter` does.
+fn add_spm_pieces(document: &mut Document, proto: &SentencePieceModel) {
+    for piece in &proto.pieces {
+        match piece.kind {
+            PieceType::Control | PieceType::Unknown => document.add(&piece.piece, true, false),
+            PieceType::UserDefined => document.add(&piece.piece, false, false),
+            _ => {}
+        }
+    }
+}
+
+fn prepend_scheme(add_prefix_space: bool, hints: &ConvertHints) -> &'static str {
+    match (add_prefix_space, hints.legacy.unwrap_or(true)) {
+        (false, _) => "never",
+        (true, true) => "always",
+        (true, false) => "first",
+    }
+}
+
+/// `LlamaConverter`: byte-fallback BPE whose merges are recovered from the piece vocabulary.
+fn convert_llama(proto: &SentencePieceModel, hints: &ConvertHints) -> Result<Value, ConvertError> {
+    if proto.model_type != SpmModelType::Bpe {
+        return Err(ConvertError::Format(String::from(
+            "Llama conversion requires a BPE sentencepiece model",
+        )));
+    }
+
+    let vocab: Map<String, Value> = proto
+        .pieces
+        .iter()
+        .enumerate()
+        .map(|(id, piece)| (piece.piece.clone(), Value::from(id)))
+        .collect();
+    let unk = proto
+        .pieces
+        .get(proto.unk_id)
+        .map(|piece| piece.piece.clone())
+        .unwrap_or_else(|| pick(&hints.unk_token, "<unk>"));
+
+    let mut document = Document::new(vocab);
+    let bos = pick(&hints.bos_token, "<s>");
+    let eos = pick(&hints.eos_token, "</s>");
+    document.add_special(&[&unk, &bos, &eos], hints);
+    add_spm_pieces(&mut document, proto);
+
+    let add_bos = hints.add_bos_token.unwrap_or(true);
+    let add_eos = hints.add_eos_token.unwrap_or(false);
+    let sequence = |name: &'static str, type_id: u32| {
+        let mut pieces = Vec::new();
+        if add_bos {
+            pieces.push((bos.as_str(), type_id));
+        }
+        pieces.push((name, type_id));
+        if add_eos {
+            pieces.push((eos.as_str(), type_id));
+        }
+        pieces
+    };
+    let single = sequence("$A", 0);
+    let pair = [single.clone(), sequence("$B", 1)].concat();
+    let post_processor = document.template(&single, &pair)?;
+
+    let add_prefix_space = hints.add_prefix_space.unwrap_or(true);
+    let legacy = hints.legacy.unwrap_or(true);
+    let (normalizer, pre_tokenizer) = if legacy {
+        let mut normalizers = Vec::new();
+        if add_prefix_space {
+            normalizers.push(json!({"type": "Prepend", "prepend": SPIECE_UNDERLINE}));
+        }
+        normalizers.push(json!({
+            "type": "Replace",
+            "pattern": {"String": " "},
+            "content": SPIECE_UNDERLINE,
+        }));
+        (
+            json!({"type": "Sequence", "normalizers": normalizers}),
+            Value::Null,
+        )
+    } else {
+        (
+            Value::Null,
+            json!({
+                "type": "Metaspace",
+                "replacement": SPIECE_UNDERLINE,
+                "prepend_scheme": prepend_scheme(add_prefix_space, hints),
+                "split": false,
+            }),
+        )
+    };
+
+    let mut decoders = vec![
+        json!({"type": "Replace", "pattern": {"String": SPIECE_UNDERLINE}, "content": " "}),
+        json!({"type": "ByteFallback"}),
+        json!({"type": "Fuse"}),
+    ];
+    if add_prefix_space {
+        decoders.push(json!({"type": "Strip", "content": " ", "start": 1, "stop": 0}));
+    }
+
+    let model = json!({
+        "type": "BPE",
+        "dropout": null,
+        "unk_token": unk,
+        "continuing_subword_prefix": null,
+        "end_of_word_suffix": null,
+        "fuse_unk": true,
+        "byte_fallback": true,
+        "ignore_merges": false,
+        "vocab": document.vocab,
+        "merges": extract_merges(&proto.pieces),
+    });
+
+    Ok(document.finish(
+        model,
+        normalizer,
+        pre_tokenizer,
+        post_processor,
+        json!({"type": "Sequence", "decoders": decoders}),
+    ))
+}
+
+/// `T5Converter` and `XLMRobertaConverter`: Unigram models with the shared `SpmConverter`
+/// normalizer and Metaspace pre-tokenization.
+fn convert_unigram(
+    family: LegacyFamily,
+    proto: &SentencePieceModel,
+    hints: &ConvertHints,
+) -> Result<Value, ConvertError> {
+    if proto.model_type != SpmModelType::Unigram {
+        return Err(ConvertError::Format(String::from(
+            "T5 and XLM-R conversion require a Unigram sentencepiece model",
+        )));
+    }
+
+    let mut scores: Vec<(String, f64)> = Vec::with_capacity(proto.pieces.len() + 1);
+    let unk_id = if family == LegacyFamily::XlmRoberta {
+        // fairseq reserves the first four ids and shifts the sentencepiece pieces by one.
+        scores.extend(
+            ["<s>", "<pad>", "</s>", "<unk>"]
+                .iter()
+                .map(|token| (token.to_string(), 0.0)),
+        );
+        scores.extend(
+            proto
+                .pieces
+                .iter()
+                .skip(3)
+                .map(|piece| (piece.piece.clone(), f64::from(piece.score))),
+        );
+        scores.push((String::from("<mask>"), 0.0));
+        3
+    } else {
+        scores.extend(
+            proto
+                .pieces
+                .iter()
+                .map(|piece| (piece.piece.clone(), f64::from(piece.score))),
+        );
+        let extra_ids = hints.extra_ids.unwrap_or(100);
+        scores.extend(
+            (0..extra_ids)
+                .rev()
+                .map(|index| (format!("<extra_id_{index}>"), 0.0)),
+        );
+        proto.unk_id
+    };
+
+    let mut vocab = Map::new();
+    for (id, (token, _)) in scores.iter().enumerate() {
+        vocab.entry(token.clone()).or_insert(Value::from(id));
+    }
+    let mut document = Document::new(vocab);
+
+    let post_processor = if family == LegacyFamily::XlmRoberta {
+        let bos = pick(&hints.bos_token, "<s>");
+        let eos = pick(&hints.eos_token, "</s>");
+        let cls = pick(&hints.cls_token, "<s>");
+        let sep = pick(&hints.sep_token, "</s>");
+        let unk = pick(&hints.unk_token, "<unk>");
+        let pad = pick(&hints.pad_token, "<pad>");
+        let mask = pick(&hints.mask_token, "<mask>");
+        document.add_special(&[&bos, &pad, &eos, &unk], hints);
+        document.add(&mask, true, true);
+        document.template(
+            &[(&cls, 0), ("$A", 0), (&sep, 0)],
+            &[
+                (&cls, 0),
+                ("$A", 0),
+                (&sep, 0),
+                (&sep, 0),
+                ("$B", 0),
+                (&sep, 0),
+            ],
+        )?
+    } else {
+        let eos = pick(&hints.eos_token, "</s>");
+        let unk = pick(&hints.unk_token, "<unk>");
+        let pad = pick(&hints.pad_token, "<pad>");
+        let extra: Vec<String> = scores
+            .iter()
+            .filter(|(token, _)| token.starts_with("<extra_id_"))
+            .map(|(token, _)| token.clone())
+            .collect();
+        document.add_special(&[&pad, &eos, &unk], hints);
+        for token in &extra {
+            document.add(token, true, false);
+        }
+        document.template(
+            &[("$A", 0), (&eos, 0)],
+            &[("$A", 0), (&eos, 0), ("$B", 0), (&eos, 0)],
+        )?
+    };
+    add_spm_pieces(&mut document, proto);
+
+    let mut normalizers = Vec::new();
+    if !proto.precompiled_charsmap.is_empty() {
+        let precompiled = Precompiled::from(&proto.precompiled_charsmap)
+            .map_err(|err| ConvertError::Format(format!("invalid precompiled charsmap: {err}")))?;
+        normalizers.push(
+            serde_json::to_value(&precompiled)
+                .map_err(|err| ConvertError::Build(err.to_string()))?,
+        );
+    }
+    normalizers.push(json!({"type": "Strip", "strip_left": false, "strip_right": true}));
+    normalizers.push(json!({
+        "type": "Replace",
+        "pattern": {"Regex": " {2,}"},
+        "content": SPIECE_UNDERLINE,
+    }));
+
+    let add_prefix_space = hints.add_prefix_space.unwrap_or(proto.add_dummy_prefix);
+    let metaspace = json!({
+        "type": "Metaspace",
+        "replacement": SPIECE_UNDERLINE,
+        "prepend_scheme": prepend_scheme(add_prefix_space, hints),
+        "split": true,
+    });
+
+    let model = json!({
+        "type": "Unigram",
+        "unk_id": unk_id,
+        "vocab": scores,
+        "byte_fallback": proto.byte_fallback,
+    });
+
+    Ok(document.finish(
+        model,
+        json!({"type": "Sequence", "normalizers": normalizers}),
+        metaspace.clone(),
+        post_processor,
+        metaspace,
+    ))
+}
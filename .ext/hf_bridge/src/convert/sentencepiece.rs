@@ -0,0 +1,241 @@
+use super::ConvertError;
+
+/// `SentencePiece.Type` from sentencepiece_model.proto.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum PieceType {
+    Normal,
+    Unknown,
+    Control,
+    UserDefined,
+    Unused,
+    Byte,
+}
+
+impl PieceType {
+    fn from_proto(value: u64) -> Self {
+        match value {
+            2 => PieceType::Unknown,
+            3 => PieceType::Control,
+            4 => PieceType::UserDefined,
+            5 => PieceType::Unused,
+            6 => PieceType::Byte,
+            _ => PieceType::Normal,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Piece {
+    pub(crate) piece: String,
+    pub(crate) score: f32,
+    pub(crate) kind: PieceType,
+}
+
+/// `TrainerSpec.ModelType`; only the two types `transformers` converts are kept apart.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum SpmModelType {
+    Unigram,
+    Bpe,
+    Other(u64),
+}
+
+/// The parts of a SentencePiece `ModelProto` the converters read.
+#[derive(Clone, Debug)]
+pub(crate) struct SentencePieceModel {
+    pub(crate) pieces: Vec<Piece>,
+    pub(crate) model_type: SpmModelType,
+    pub(crate) unk_id: usize,
+    pub(crate) byte_fallback: bool,
+    pub(crate) add_dummy_prefix: bool,
+    pub(crate) precompiled_charsmap: Vec<u8>,
+}
+
+impl SentencePieceModel {
+    /// Decodes a serialized `ModelProto`, ignoring every field the converters do not need.
+    pub(crate) fn parse(bytes: &[u8]) -> Result<Self, ConvertError> {
+        let mut model = SentencePieceModel {
+            pieces: Vec::new(),
+            model_type: SpmModelType::Unigram,
+            unk_id: 0,
+            byte_fallback: false,
+            add_dummy_prefix: true,
+            precompiled_charsmap: Vec::new(),
+        };
+
+        let mut reader = WireReader::new(bytes);
+        while let Some((field, wire_type)) = reader.key()? {
+            match (field, wire_type) {
+                (1, LENGTH_DELIMITED) => model.pieces.push(parse_piece(reader.bytes()?)?),
+                (2, LENGTH_DELIMITED) => parse_trainer_spec(reader.bytes()?, &mut model)?,
+                (3, LENGTH_DELIMITED) => parse_normalizer_spec(reader.bytes()?, &mut model)?,
+                _ => reader.skip(wire_type)?,
+            }
+        }
+
+        if model.pieces.is_empty() {
+            return Err(ConvertError::Format(String::from(
+                "sentencepiece model contains no pieces",
+            )));
+        }
+        Ok(model)
+    }
+}
+
+const VARINT: u8 = 0;
+const FIXED64: u8 = 1;
+const LENGTH_DELIMITED: u8 = 2;
+const FIXED32: u8 = 5;
+
+fn parse_piece(bytes: &[u8]) -> Result<Piece, ConvertError> {
+    let mut piece = Piece {
+        piece: String::new(),
+        score: 0.0,
+        kind: PieceType::Normal,
+    };
+
+    let mut reader = WireReader::new(bytes);
+    while let Some((field, wire_type)) = reader.key()? {
+        match (field, wire_type) {
+            (1, LENGTH_DELIMITED) => {
+                piece.piece = String::from_utf8(reader.bytes()?.to_vec()).map_err(|_| {
+                    ConvertError::Format(String::from("sentencepiece piece is not UTF-8"))
+                })?;
+            }
+            (2, FIXED32) => piece.score = f32::from_bits(reader.fixed32()?),
+            (3, VARINT) => piece.kind = PieceType::from_proto(reader.varint()?),
+            _ => reader.skip(wire_type)?,
+        }
+    }
+    Ok(piece)
+}
+
+fn parse_trainer_spec(bytes: &[u8], model: &mut SentencePieceModel) -> Result<(), ConvertError> {
+    let mut reader = WireReader::new(bytes);
+    while let Some((field, wire_type)) = reader.key()? {
+        match (field, wire_type) {
+            (3, VARINT) => {
+                model.model_type = match reader.varint()? {
+                    1 => SpmModelType::Unigram,
+                    2 => SpmModelType::Bpe,
+                    other => SpmModelType::Other(other),
+                };
+            }
+            (35, VARINT) => model.byte_fallback = reader.varint()? != 0,
+            (40, VARINT) => model.unk_id = reader.varint()? as usize,
+            _ => reader.skip(wire_type)?,
+        }
+    }
+    Ok(())
+}
+
+fn parse_normalizer_spec(bytes: &[u8], model: &mut SentencePieceModel) -> Result<(), ConvertError> {
+    let mut reader = WireReader::new(bytes);
+    while let Some((field, wire_type)) = reader.key()? {
+        match (field, wire_type) {
+            (2, LENGTH_DELIMITED) => model.precompiled_charsmap = reader.bytes()?.to_vec(),
+            (3, VARINT) => model.add_dummy_prefix = reader.varint()? != 0,
+            _ => reader.skip(wire_type)?,
+        }
+    }
+    Ok(())
+}
+
+/// Minimal protobuf wire-format reader, enough to walk `ModelProto` without generated code.
+struct WireReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> WireReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        WireReader { bytes, position: 0 }
+    }
+
+    fn truncated() -> ConvertError {
+        ConvertError::Format(String::from("sentencepiece model is truncated"))
+    }
+
+    fn key(&mut self) -> Result<Option<(u64, u8)>, ConvertError> {
+        if self.position >= self.bytes.len() {
+            return Ok(None);
+        }
+        let key = self.varint()?;
+        Ok(Some((key >> 3, (key & 0x7) as u8)))
+    }
+
+    fn varint(&mut self) -> Result<u64, ConvertError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = *self.bytes.get(self.position).ok_or_else(Self::truncated)?;
+            self.position += 1;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(ConvertError::Format(String::from(
+            "sentencepiece model contains an overlong varint",
+        )))
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8], ConvertError> {
+        let end = self
+            .position
+            .checked_add(length)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(Self::truncated)?;
+        let slice = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(slice)
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8], ConvertError> {
+        let length = self.varint()? as usize;
+        self.take(length)
+    }
+
+    fn fixed32(&mut self) -> Result<u32, ConvertError> {
+        let raw = self.take(4)?;
+        Ok(u32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]))
+    }
+
+    fn skip(&mut self, wire_type: u8) -> Result<(), ConvertError> {
+        match wire_type {
+            VARINT => self.varint().map(|_| ()),
+            FIXED64 => self.take(8).map(|_| ()),
+            LENGTH_DELIMITED => self.bytes().map(|_| ()),
+            FIXED32 => self.take(4).map(|_| ()),
+            other => Err(ConvertError::Format(format!(
+                "sentencepiece model uses unsupported wire type {other}"
+            ))),
+        }
+    }
+}
+
+/// Rebuilds BPE merges from a SentencePiece vocabulary the way `transformers`'
+/// `SentencePieceExtractor` does: every split of a piece into two known pieces is a merge, ranked
+/// by the id of the merged piece and then by the ids of its halves.
+pub(crate) fn extract_merges(pieces: &[Piece]) -> Vec<(String, String)> {
+    let ids: std::collections::HashMap<&str, usize> = pieces
+        .iter()
+        .enumerate()
+        .map(|(id, piece)| (piece.piece.as_str(), id))
+        .collect();
+
+    let mut merges = Vec::new();
+    for (merged_id, piece) in pieces.iter().enumerate() {
+        let text = piece.piece.as_str();
+        for (split, _) in text.char_indices().skip(1) {
+            let (left, right) = text.split_at(split);
+            if let (Some(left_id), Some(right_id)) = (ids.get(left), ids.get(right)) {
+                merges.push((merged_id, *left_id, *right_id, left, right));
+            }
+        }
+    }
+
+    merges.sort_by_key(|(merged, left, right, _, _)| (*merged, *left, *right));
+    merges
+        .into_iter()
+        .map(|(_, _, _, left, right)| (left.to_string(), right.to_string()))
+        .collect()
+}
@@ -0,0 +1,60 @@
+use tokenizers::Tokenizer;
+
+use crate::decode_stream::{common_prefix_length, CDecodeStream};
+
+/// Decodes `ids` exactly as `Tokenizer::decode` does and attributes a byte span of the result to
+/// every id. The ids are stepped through a decode stream, whose prefix technique decodes only a
+/// short window per id, so each id's span is the text it completes; ids that add nothing (skipped
+/// specials, leading bytes of a split character) get empty spans.
+///
+/// The streamed text is checked against a single full decode. Decoders that rewrite text already
+/// emitted (fusing and replacing across tokens, cleanup rules) can make the stream disagree or fail
+/// with an invalid prefix. Spans are then kept only up to the text both agree on, and every later
+/// id is attributed by decoding `ids[..=i]` and measuring how far it matches the full text. That
+/// fallback decodes once per remaining id, so it is quadratic in their number.
+pub(crate) fn decode_with_spans(
+    tokenizer: &Tokenizer,
+    ids: &[u32],
+    skip_special_tokens: bool,
+) -> tokenizers::Result<(String, Vec<(usize, usize)>)> {
+    let text = tokenizer.decode(ids, skip_special_tokens)?;
+
+    let mut stream = CDecodeStream::new(skip_special_tokens);
+    let mut streamed = String::with_capacity(text.len());
+    let mut spans = Vec::with_capacity(ids.len());
+    let mut complete = true;
+    for id in ids {
+        let start = streamed.len();
+        match stream.step(tokenizer, std::slice::from_ref(id)) {
+            Ok(chunk) => streamed.push_str(chunk.as_deref().unwrap_or_default()),
+            Err(_) => {
+                complete = false;
+                break;
+            }
+        }
+        spans.push((start, streamed.len()));
+    }
+    if complete {
+        match stream.flush(tokenizer) {
+            Ok(rest) => streamed.push_str(&rest),
+            Err(_) => complete = false,
+        }
+    }
+
+    if !complete || streamed != text {
+        let agreed = common_prefix_length(&streamed, &text);
+        spans.truncate(spans.partition_point(|span| span.1 <= agreed));
+        let mut end = spans.last().map_or(0, |span| span.1);
+        for index in spans.len()..ids.len() {
+            let prefix = tokenizer.decode(&ids[..=index], skip_special_tokens)?;
+            let start = end;
+            end = end.max(common_prefix_length(&prefix, &text));
+            spans.push((start, end));
+        }
+    }
+    if let Some(last) = spans.last_mut() {
+        last.1 = text.len();
+    }
+
+    Ok((text, spans))
+}
@@ -0,0 +1,77 @@
+use tokenizers::Tokenizer;
+
+/// Incremental detokenizer state mirroring `tokenizers::DecodeStream`, kept apart from the tokenizer
+/// so a handle can outlive any single borrow and be stepped against the tokenizer passed per call.
+pub struct CDecodeStream {
+    skip_special_tokens: bool,
+    ids: Vec<u32>,
+    prefix: String,
+    prefix_index: usize,
+}
+
+impl CDecodeStream {
+    pub(crate) fn new(skip_special_tokens: bool) -> Self {
+        Self {
+            skip_special_tokens,
+            ids: Vec::new(),
+            prefix: String::new(),
+            prefix_index: 0,
+        }
+    }
+
+    /// Pushes `ids` and returns the newly completed text, or `None` while the tail still decodes to
+    /// an incomplete character.
+    pub(crate) fn step(
+        &mut self,
+        tokenizer: &Tokenizer,
+        ids: &[u32],
+    ) -> Result<Option<String>, String> {
+        if ids.is_empty() {
+            return Ok(None);
+        }
+
+        tokenizers::step_decode_stream(
+            tokenizer,
+            ids.to_vec(),
+            self.skip_special_tokens,
+            &mut self.ids,
+            &mut self.prefix,
+            &mut self.prefix_index,
+        )
+        .map_err(|err| err.to_string())
+    }
+
+    /// Whether ids were stepped in that have not produced text yet, such as the leading bytes of a
+    /// split character.
+    pub(crate) fn has_pending(&self) -> bool {
+        self.ids.len() > self.prefix_index
+    }
+
+    /// Returns whatever text is still held back, incomplete characters included, and resets the stream.
+    /// When the decoder rewrote text already returned (so the window no longer starts with it), the
+    /// part after the longest common prefix is returned rather than dropping the pending text.
+    pub(crate) fn flush(&mut self, tokenizer: &Tokenizer) -> Result<String, String> {
+        let decoded = tokenizer
+            .decode(&self.ids, self.skip_special_tokens)
+            .map_err(|err| err.to_string())?;
+        let pending = decoded[common_prefix_length(&self.prefix, &decoded)..].to_string();
+
+        self.reset();
+        Ok(pending)
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.ids.clear();
+        self.prefix.clear();
+        self.prefix_index = 0;
+    }
+}
+
+/// Length in bytes of the longest common prefix of `left` and `right`, ending on a character boundary.
+pub(crate) fn common_prefix_length(left: &str, right: &str) -> usize {
+    left.chars()
+        .zip(right.chars())
+        .take_while(|(a, b)| a == b)
+        .map(|(ch, _)| ch.len_utf8())
+        .sum()
+}
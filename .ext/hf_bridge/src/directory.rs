@@ -0,0 +1,567 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tokenizers::processors::sequence::Sequence;
+use tokenizers::processors::template::TemplateProcessing;
+use tokenizers::processors::PostProcessorWrapper;
+use tokenizers::utils::padding::{PaddingDirection, PaddingParams};
+use tokenizers::utils::truncation::{TruncationDirection, TruncationParams};
+use tokenizers::{AddedToken, Tokenizer};
+
+use crate::added_tokens::{add_tokens, TokenSpec};
+use crate::convert::{convert, ConvertHints};
+
+pub(crate) const TOKENIZER_FILE: &str = "tokenizer.json";
+const TOKENIZER_CONFIG_FILE: &str = "tokenizer_config.json";
+const SPECIAL_TOKENS_MAP_FILE: &str = "special_tokens_map.json";
+const ADDED_TOKENS_FILE: &str = "added_tokens.json";
+pub(crate) const CHAT_TEMPLATE_FILE: &str = "chat_template.jinja";
+
+/// Legacy vocabulary files `convert` accepts, in the order they are tried, each with the companion
+/// file it needs.
+const LEGACY_SOURCES: [(&str, Option<&str>); 5] = [
+    ("tokenizer.model", None),
+    ("spiece.model", None),
+    ("sentencepiece.bpe.model", None),
+    ("vocab.json", Some("merges.txt")),
+    ("vocab.txt", None),
+];
+
+/// Special-token attributes in the order `transformers` lists them.
+const SPECIAL_TOKEN_KEYS: [&str; 7] = [
+    "bos_token",
+    "eos_token",
+    "unk_token",
+    "sep_token",
+    "pad_token",
+    "cls_token",
+    "mask_token",
+];
+
+/// `transformers` stores `int(1e30)` as `model_max_length` when the model sets no limit.
+const UNBOUNDED_MAX_LENGTH: u64 = 1 << 53;
+
+#[derive(Debug)]
+pub(crate) enum DirectoryError {
+    NotFound(String),
+    Io(String),
+    Parse { file: String, reason: String },
+    Load(String),
+    Apply(String),
+}
+
+impl DirectoryError {
+    pub(crate) fn into_message(self) -> String {
+        match self {
+            DirectoryError::NotFound(reason) => reason,
+            DirectoryError::Io(reason) => reason,
+            DirectoryError::Parse { file, reason } => format!("failed to parse {file}: {reason}"),
+            DirectoryError::Load(reason) => format!("failed to load tokenizer: {reason}"),
+            DirectoryError::Apply(reason) => {
+                format!("failed to apply tokenizer configuration: {reason}")
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct ResolvedToken {
+    #[serde(rename = "content")]
+    pub(crate) content: String,
+    #[serde(rename = "id")]
+    pub(crate) id: Option<u32>,
+}
+
+/// Everything `tokenizers_load_directory` resolved besides the tokenizer itself.
+#[derive(Debug, Serialize)]
+pub(crate) struct DirectoryMetadata {
+    #[serde(rename = "directory")]
+    pub(crate) directory: String,
+    #[serde(rename = "files")]
+    pub(crate) files: Vec<String>,
+    #[serde(rename = "converted")]
+    pub(crate) converted: bool,
+    #[serde(rename = "tokenizer_class")]
+    pub(crate) tokenizer_class: Option<String>,
+    #[serde(rename = "model_max_length")]
+    pub(crate) model_max_length: Option<u64>,
+    #[serde(rename = "padding_side")]
+    pub(crate) padding_side: String,
+    #[serde(rename = "truncation_side")]
+    pub(crate) truncation_side: String,
+    #[serde(rename = "add_bos_token")]
+    pub(crate) add_bos_token: Option<bool>,
+    #[serde(rename = "add_eos_token")]
+    pub(crate) add_eos_token: Option<bool>,
+    #[serde(rename = "clean_up_tokenization_spaces")]
+    pub(crate) clean_up_tokenization_spaces: Option<bool>,
+    #[serde(rename = "special_tokens")]
+    pub(crate) special_tokens: Map<String, Value>,
+    #[serde(rename = "additional_special_tokens")]
+    pub(crate) additional_special_tokens: Vec<ResolvedToken>,
+    #[serde(rename = "added_tokens")]
+    pub(crate) added_tokens: Vec<ResolvedToken>,
+    #[serde(rename = "chat_template")]
+    pub(crate) chat_template: Option<Value>,
+}
+
+pub(crate) struct LoadedDirectory {
+    pub(crate) tokenizer: Tokenizer,
+    pub(crate) metadata: DirectoryMetadata,
+}
+
+fn read_optional(directory: &Path, name: &str) -> Result<Option<String>, DirectoryError> {
+    let path = directory.join(name);
+    if !path.is_file() {
+        return Ok(None);
+    }
+
+    fs::read_to_string(&path)
+        .map(|content| Some(content).filter(|text| !text.trim().is_empty()))
+        .map_err(|err| DirectoryError::Io(format!("failed to read {}: {err}", path.display())))
+}
+
+fn read_object(directory: &Path, name: &str) -> Result<Option<Map<String, Value>>, DirectoryError> {
+    read_optional(directory, name)?
+        .map(|content| {
+            serde_json::from_str(&content).map_err(|err| DirectoryError::Parse {
+                file: name.to_string(),
+                reason: err.to_string(),
+            })
+        })
+        .transpose()
+}
+
+/// Special tokens may be stored as plain strings or as serialized `AddedToken` objects.
+fn parse_token(value: &Value) -> Option<AddedToken> {
+    match value {
+        Value::String(content) => Some(AddedToken::from(content.clone(), true)),
+        Value::Object(fields) => {
+            let content = fields.get("content")?.as_str()?;
+            let flag = |key: &str, default: bool| {
+                fields.get(key).and_then(Value::as_bool).unwrap_or(default)
+            };
+            Some(
+                AddedToken::from(content.to_string(), true)
+                    .single_word(flag("single_word", false))
+                    .lstrip(flag("lstrip", false))
+                    .rstrip(flag("rstrip", false))
+                    .normalized(flag("normalized", false)),
+            )
+        }
+        _ => None,
+    }
+}
+
+/// Resolves `padding_side` or `truncation_side`, falling back to the direction tokenizer.json
+/// already configures and then to `right`.
+fn side(
+    config: &Map<String, Value>,
+    key: &str,
+    configured_left: Option<bool>,
+) -> Result<String, DirectoryError> {
+    match config.get(key).and_then(Value::as_str) {
+        None if configured_left == Some(true) => Ok(String::from("left")),
+        None => Ok(String::from("right")),
+        Some(value @ ("left" | "right")) => Ok(value.to_string()),
+        Some(other) => Err(DirectoryError::Apply(format!("unknown {key} '{other}'"))),
+    }
+}
+
+/// Loads `tokenizer.json` from `location` and applies the sibling configuration files the way
+/// `transformers`' `AutoTokenizer.from_pretrained` does.
+///
+/// `special_tokens_map.json` entries override `tokenizer_config.json`, `added_tokens.json` tokens
+/// keep their listed ids, and every special token ends up registered as a special added token;
+/// tokens tokenizer.json already declares keep their flags.
+/// Without `tokenizer.json` the tokenizer is converted from the legacy vocabulary files instead.
+/// With `apply_defaults`, `add_bos_token`/`add_eos_token` rebuild the post-processor, the pad
+/// token and `padding_side` enable padding, and `model_max_length` and `truncation_side` enable
+/// truncation, unless tokenizer.json already configures them.
+pub(crate) fn load_directory(
+    location: &Path,
+    apply_defaults: bool,
+) -> Result<LoadedDirectory, DirectoryError> {
+    let (directory, tokenizer_path) = if location.is_file() {
+        let parent = location.parent().unwrap_or(Path::new(".")).to_path_buf();
+        (parent, Some(location.to_path_buf()))
+    } else if location.is_dir() {
+        let candidate = location.join(TOKENIZER_FILE);
+        let tokenizer_path = candidate.is_file().then_some(candidate);
+        (location.to_path_buf(), tokenizer_path)
+    } else {
+        return Err(DirectoryError::NotFound(format!(
+            "tokenizer location {} does not exist",
+            location.display()
+        )));
+    };
+
+    let mut files = Vec::new();
+    let config = read_object(&directory, TOKENIZER_CONFIG_FILE)?;
+    if config.is_some() {
+        files.push(TOKENIZER_CONFIG_FILE.to_string());
+    }
+    let mut config = config.unwrap_or_default();
+
+    let (mut tokenizer, converted) = match tokenizer_path {
+        Some(path) => {
+            let tokenizer =
+                Tokenizer::from_file(&path).map_err(|err| DirectoryError::Load(err.to_string()))?;
+            files.push(path.file_name().map_or_else(
+                || TOKENIZER_FILE.to_string(),
+                |name| name.to_string_lossy().into_owned(),
+            ));
+            (tokenizer, false)
+        }
+        None => (convert_legacy(&directory, &config, &mut files)?, true),
+    };
+
+    if let Some(special_map) = read_object(&directory, SPECIAL_TOKENS_MAP_FILE)? {
+        files.push(SPECIAL_TOKENS_MAP_FILE.to_string());
+        config.extend(special_map);
+    }
+
+    let mut added_tokens = Vec::new();
+    if let Some(entries) = read_object(&directory, ADDED_TOKENS_FILE)? {
+        files.push(ADDED_TOKENS_FILE.to_string());
+        let mut specs = Vec::new();
+        for (content, id) in entries {
+            let id = id
+                .as_u64()
+                .and_then(|id| u32::try_from(id).ok())
+                .ok_or_else(|| DirectoryError::Parse {
+                    file: ADDED_TOKENS_FILE.to_string(),
+                    reason: format!("'{content}' has no valid id"),
+                })?;
+            if tokenizer.token_to_id(&content) != Some(id) {
+                specs.push(TokenSpec {
+                    token: AddedToken::from(content.clone(), false),
+                    pinned_id: Some(id),
+                });
+            }
+            added_tokens.push(content);
+        }
+        specs.sort_by_key(|spec| spec.pinned_id);
+        if !specs.is_empty() {
+            add_tokens(&mut tokenizer, specs)
+                .map_err(|err| DirectoryError::Apply(err.into_message()))?;
+        }
+    }
+
+    let mut special = Vec::new();
+    for key in SPECIAL_TOKEN_KEYS {
+        if let Some(token) = config.get(key).and_then(parse_token) {
+            special.push((key, token));
+        }
+    }
+    let additional: Vec<AddedToken> = config
+        .get("additional_special_tokens")
+        .and_then(Value::as_array)
+        .map(|entries| entries.iter().filter_map(parse_token).collect())
+        .unwrap_or_default();
+    // Tokens tokenizer.json already declares keep their flags (`lstrip` on `<mask>` and the
+    // like); a plain string in special_tokens_map.json only marks them special.
+    let existing = tokenizer.get_added_tokens_decoder();
+    let to_register: Vec<AddedToken> = special
+        .iter()
+        .map(|(_, token)| token)
+        .chain(additional.iter())
+        .filter_map(|token| {
+            match existing
+                .values()
+                .find(|added| added.content == token.content)
+            {
+                Some(added) if added.special => None,
+                Some(added) => Some(AddedToken {
+                    special: true,
+                    ..added.clone()
+                }),
+                None => Some(token.clone()),
+            }
+        })
+        .collect();
+    tokenizer.add_special_tokens(&to_register);
+
+    let resolve = |tokenizer: &Tokenizer, content: &str| ResolvedToken {
+        content: content.to_string(),
+        id: tokenizer.token_to_id(content),
+    };
+    let flag = |key: &str| config.get(key).and_then(Value::as_bool);
+    let model_max_length = config
+        .get("model_max_length")
+        .and_then(Value::as_u64)
+        .filter(|length| *length < UNBOUNDED_MAX_LENGTH);
+
+    let chat_template = match read_optional(&directory, CHAT_TEMPLATE_FILE)? {
+        Some(template) => {
+            files.push(CHAT_TEMPLATE_FILE.to_string());
+            Some(Value::String(template))
+        }
+        None => config.get("chat_template").cloned(),
+    };
+
+    let metadata = DirectoryMetadata {
+        directory: directory.to_string_lossy().into_owned(),
+        files,
+        converted,
+        tokenizer_class: config
+            .get("tokenizer_class")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        model_max_length,
+        padding_side: side(
+            &config,
+            "padding_side",
+            tokenizer
+                .get_padding()
+                .map(|params| matches!(params.direction, PaddingDirection::Left)),
+        )?,
+        truncation_side: side(
+            &config,
+            "truncation_side",
+            tokenizer
+                .get_truncation()
+                .map(|params| matches!(params.direction, TruncationDirection::Left)),
+        )?,
+        add_bos_token: flag("add_bos_token"),
+        add_eos_token: flag("add_eos_token"),
+        clean_up_tokenization_spaces: flag("clean_up_tokenization_spaces"),
+        special_tokens: special
+            .iter()
+            .map(|(key, token)| {
+                let resolved = resolve(&tokenizer, &token.content);
+                (
+                    key.to_string(),
+                    serde_json::to_value(resolved).unwrap_or_default(),
+                )
+            })
+            .collect(),
+        additional_special_tokens: additional
+            .iter()
+            .map(|token| resolve(&tokenizer, &token.content))
+            .collect(),
+        added_tokens: added_tokens
+            .iter()
+            .map(|content| resolve(&tokenizer, content))
+            .collect(),
+        chat_template,
+    };
+
+    if apply_defaults {
+        apply_post_processor(&mut tokenizer, &metadata)?;
+        apply_padding(&mut tokenizer, &metadata);
+        apply_truncation(&mut tokenizer, &metadata)?;
+    }
+
+    Ok(LoadedDirectory {
+        tokenizer,
+        metadata,
+    })
+}
+
+/// Returns the first legacy vocabulary file in `directory` whose companion is present too. When
+/// there is none, the error lists the companions missing next to vocabulary files that were found,
+/// and is empty when no vocabulary file exists at all.
+pub(crate) fn find_legacy_source(
+    directory: &Path,
+) -> Result<(PathBuf, Option<PathBuf>), Vec<String>> {
+    let existing = |name: &str| {
+        let path = directory.join(name);
+        path.is_file().then_some(path)
+    };
+
+    let mut missing = Vec::new();
+    for (primary, companion) in LEGACY_SOURCES {
+        let Some(primary) = existing(primary) else {
+            continue;
+        };
+        match companion {
+            None => return Ok((primary, None)),
+            Some(companion) => match existing(companion) {
+                Some(path) => return Ok((primary, Some(path))),
+                None => missing.push(companion.to_string()),
+            },
+        }
+    }
+    Err(missing)
+}
+
+fn convert_legacy(
+    directory: &Path,
+    config: &Map<String, Value>,
+    files: &mut Vec<String>,
+) -> Result<Tokenizer, DirectoryError> {
+    let (primary, secondary) = find_legacy_source(directory).map_err(|missing| {
+        let mut message = format!(
+            "{} contains no {TOKENIZER_FILE} or legacy vocabulary files",
+            directory.display()
+        );
+        if !missing.is_empty() {
+            message.push_str(&format!(" (missing {})", missing.join(", ")));
+        }
+        DirectoryError::NotFound(message)
+    })?;
+
+    let config_json = Value::Object(config.clone()).to_string();
+    let hints = ConvertHints::from_config(Some(&config_json))
+        .map_err(|err| DirectoryError::Load(err.into_message()))?;
+    let tokenizer = convert(None, &primary, secondary.as_deref(), &hints)
+        .map_err(|err| DirectoryError::Load(err.into_message()))?;
+
+    for path in std::iter::once(&primary).chain(secondary.as_ref()) {
+        if let Some(name) = path.file_name() {
+            files.push(name.to_string_lossy().into_owned());
+        }
+    }
+    Ok(tokenizer)
+}
+
+fn special_token(metadata: &DirectoryMetadata, key: &str) -> Option<(String, Option<u32>)> {
+    let entry = metadata.special_tokens.get(key)?;
+    let content = entry["content"].as_str()?.to_string();
+    let id = entry["id"].as_u64().and_then(|id| u32::try_from(id).ok());
+    Some((content, id))
+}
+
+/// Tokenizer classes whose `update_post_processor` rebuilds the template from `add_bos_token`
+/// and `add_eos_token` even when both are false.
+const TEMPLATE_REBUILDING_CLASSES: [&str; 4] = [
+    "LlamaTokenizer",
+    "LlamaTokenizerFast",
+    "GemmaTokenizer",
+    "GemmaTokenizerFast",
+];
+
+/// Mirrors `LlamaTokenizerFast.update_post_processor`: BOS and EOS wrap each sequence as the
+/// `add_bos_token` and `add_eos_token` flags say. Other classes only get the template when a flag
+/// asks for a token, and nothing changes when neither flag is configured. A ByteLevel step of the
+/// existing post-processor keeps running ahead of the template.
+fn apply_post_processor(
+    tokenizer: &mut Tokenizer,
+    metadata: &DirectoryMetadata,
+) -> Result<(), DirectoryError> {
+    if metadata.add_bos_token.is_none() && metadata.add_eos_token.is_none() {
+        return Ok(());
+    }
+    let rebuilds = metadata
+        .tokenizer_class
+        .as_deref()
+        .is_some_and(|class| TEMPLATE_REBUILDING_CLASSES.contains(&class));
+    if !rebuilds && metadata.add_bos_token != Some(true) && metadata.add_eos_token != Some(true) {
+        return Ok(());
+    }
+
+    let mut special_tokens = Vec::new();
+    let mut marker = |enabled: Option<bool>, key: &str| -> Result<Option<String>, DirectoryError> {
+        if enabled != Some(true) {
+            return Ok(None);
+        }
+        match special_token(metadata, key) {
+            Some((content, Some(id))) => {
+                special_tokens.push((content.clone(), id));
+                Ok(Some(content))
+            }
+            _ => Err(DirectoryError::Apply(format!(
+                "add_{key} is set but {key} is not in the vocabulary"
+            ))),
+        }
+    };
+    let bos = marker(metadata.add_bos_token, "bos_token")?;
+    let eos = marker(metadata.add_eos_token, "eos_token")?;
+
+    let sequence = |name: &str, type_id: u32| {
+        let mut pieces = Vec::new();
+        if let Some(bos) = &bos {
+            pieces.push(format!("{bos}:{type_id}"));
+        }
+        pieces.push(format!("${name}:{type_id}"));
+        if let Some(eos) = &eos {
+            pieces.push(format!("{eos}:{type_id}"));
+        }
+        pieces.join(" ")
+    };
+    let single = sequence("A", 0);
+    let pair = format!("{single} {}", sequence("B", 1));
+
+    special_tokens.dedup();
+    let processor = TemplateProcessing::builder()
+        .try_single(single)
+        .and_then(|builder| builder.try_pair(pair))
+        .map_err(DirectoryError::Apply)?
+        .special_tokens(special_tokens)
+        .build()
+        .map_err(|err| DirectoryError::Apply(err.to_string()))?;
+
+    let mut steps: Vec<PostProcessorWrapper> = match tokenizer.get_post_processor() {
+        Some(PostProcessorWrapper::ByteLevel(byte_level)) => {
+            vec![PostProcessorWrapper::ByteLevel(*byte_level)]
+        }
+        Some(PostProcessorWrapper::Sequence(sequence)) => sequence
+            .as_ref()
+            .iter()
+            .filter(|step| matches!(step, PostProcessorWrapper::ByteLevel(_)))
+            .cloned()
+            .collect(),
+        _ => Vec::new(),
+    };
+    if steps.is_empty() {
+        tokenizer.with_post_processor(Some(processor));
+    } else {
+        steps.push(PostProcessorWrapper::Template(processor));
+        tokenizer.with_post_processor(Some(Sequence::new(steps)));
+    }
+    Ok(())
+}
+
+fn apply_padding(tokenizer: &mut Tokenizer, metadata: &DirectoryMetadata) {
+    let direction = if metadata.padding_side == "left" {
+        PaddingDirection::Left
+    } else {
+        PaddingDirection::Right
+    };
+
+    if let Some(params) = tokenizer.get_padding_mut() {
+        params.direction = direction;
+        return;
+    }
+
+    if let Some((pad_token, Some(pad_id))) = special_token(metadata, "pad_token") {
+        tokenizer.with_padding(Some(PaddingParams {
+            direction,
+            pad_id,
+            pad_token,
+            ..PaddingParams::default()
+        }));
+    }
+}
+
+fn apply_truncation(
+    tokenizer: &mut Tokenizer,
+    metadata: &DirectoryMetadata,
+) -> Result<(), DirectoryError> {
+    let direction = if metadata.truncation_side == "left" {
+        TruncationDirection::Left
+    } else {
+        TruncationDirection::Right
+    };
+
+    let params = match (tokenizer.get_truncation(), metadata.model_max_length) {
+        (Some(existing), _) => TruncationParams {
+            direction,
+            ..existing.clone()
+        },
+        (None, Some(max_length)) => TruncationParams {
+            direction,
+            max_length: max_length as usize,
+            ..TruncationParams::default()
+        },
+        (None, None) => return Ok(()),
+    };
+
+    tokenizer
+        .with_truncation(Some(params))
+        .map(|_| ())
+        .map_err(|err| DirectoryError::Apply(err.to_string()))
+}
@@ -1,5 +1,11 @@
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use ahash::AHashMap;
 use tokenizers::Encoding;
 
+use crate::offsets::OffsetConverter;
+
 const NUMERIC_LENGTH_ERROR: &str =
     "tokenizers_encoding_copy_numeric received insufficient destination length";
 const DESTINATION_NULL_ERROR: &str =
@@ -73,10 +79,152 @@ impl CEncoding {
         }
     }
 
+    /// Runs an upstream merge, pad or truncate over `parts` and keeps every token's sequence id.
+    ///
+    /// Upstream tracks sequences as ranges keyed by sequence id: merging lets the last part's
+    /// ranges overwrite the earlier ones, and truncating or padding a handle without ranges marks
+    /// every token, specials and padding included, as sequence 0. The operation is therefore
+    /// replayed on copies whose attention mask carries `sequence id + 1`; tokens move the same way
+    /// in both runs, and padding fills the mask with 0, i.e. no sequence.
+    pub(crate) fn apply(
+        parts: &[&CEncoding],
+        operation: impl Fn(Vec<Encoding>) -> Encoding,
+    ) -> CEncoding {
+        let result = operation(
+            parts
+                .iter()
+                .map(|part| part.build_encoding(false))
+                .collect(),
+        );
+        let shadow = operation(parts.iter().map(|part| part.build_encoding(true)).collect());
+
+        let mut managed = Self::from_encoding(result);
+        managed.restore_sequence_ids(&shadow);
+        managed
+    }
+
+    /// Rebuilds a `tokenizers::Encoding`, recovering sequence ranges from the per-token sequence ids;
+    /// with `sequence_mask` the attention mask carries the sequence ids as described on [`Self::apply`].
+    fn build_encoding(&self, sequence_mask: bool) -> Encoding {
+        let mut ranges: BTreeMap<usize, Range<usize>> = BTreeMap::new();
+        for (index, sequence) in self.sequence_ids.iter().enumerate() {
+            if let Some(sequence) = sequence {
+                ranges
+                    .entry(*sequence)
+                    .and_modify(|range| range.end = index + 1)
+                    .or_insert(index..index + 1);
+            }
+        }
+
+        let attention_mask = if sequence_mask {
+            self.sequence_ids
+                .iter()
+                .map(|sequence| sequence.map_or(0, |sequence| sequence as u32 + 1))
+                .collect()
+        } else {
+            self.attention_mask.clone()
+        };
+
+        Encoding::new(
+            self.ids.clone(),
+            self.type_ids.clone(),
+            self.tokens.clone(),
+            self.word_ids.clone(),
+            self.offsets
+                .iter()
+                .map(|(start, end)| (*start as usize, *end as usize))
+                .collect(),
+            self.special_tokens_mask.clone(),
+            attention_mask,
+            self.overflowing
+                .iter()
+                .map(|overflow| overflow.build_encoding(sequence_mask))
+                .collect(),
+            ranges.into_iter().collect::<AHashMap<_, _>>(),
+        )
+    }
+
+    fn restore_sequence_ids(&mut self, shadow: &Encoding) {
+        self.sequence_ids = shadow
+            .get_attention_mask()
+            .iter()
+            .map(|mask| mask.checked_sub(1).map(|sequence| sequence as usize))
+            .collect();
+        for (overflow, shadow) in self.overflowing.iter_mut().zip(shadow.get_overflowing()) {
+            overflow.restore_sequence_ids(shadow);
+        }
+    }
+
+    /// Builds an encoding from byte offsets, re-expressing them through the converter of the
+    /// sequence each token belongs to. Tokens without a sequence keep their offsets.
+    pub(crate) fn from_encoding_with_offsets(
+        encoding: Encoding,
+        converters: &[OffsetConverter<'_>],
+    ) -> Self {
+        let mut managed = Self::from_encoding(encoding);
+        managed.convert_offsets(converters);
+        managed
+    }
+
+    fn convert_offsets(&mut self, converters: &[OffsetConverter<'_>]) {
+        for (offset, sequence) in self.offsets.iter_mut().zip(self.sequence_ids.iter()) {
+            if let Some(converter) = sequence.and_then(|index| converters.get(index)) {
+                *offset = converter.convert(offset.0 as usize, offset.1 as usize);
+            }
+        }
+
+        for overflow in self.overflowing.iter_mut() {
+            overflow.convert_offsets(converters);
+        }
+    }
+
     pub(crate) fn len(&self) -> usize {
         self.ids.len()
     }
 
+    pub(crate) fn token_to_sequence(&self, token: usize) -> Option<usize> {
+        self.sequence_ids.get(token).copied().flatten()
+    }
+
+    pub(crate) fn token_to_chars(&self, token: usize) -> Option<(usize, (u32, u32))> {
+        Some((self.token_to_sequence(token)?, *self.offsets.get(token)?))
+    }
+
+    pub(crate) fn token_to_word(&self, token: usize) -> Option<(usize, u32)> {
+        Some((
+            self.token_to_sequence(token)?,
+            self.word_ids.get(token).copied().flatten()?,
+        ))
+    }
+
+    /// Returns the `[start, end)` token range of `word` within `sequence`.
+    pub(crate) fn word_to_tokens(&self, word: u32, sequence: usize) -> Option<(usize, usize)> {
+        let mut matching = (0..self.len()).filter(|index| {
+            self.sequence_ids[*index] == Some(sequence) && self.word_ids[*index] == Some(word)
+        });
+        let start = matching.next()?;
+        let end = matching.next_back().unwrap_or(start) + 1;
+        Some((start, end))
+    }
+
+    pub(crate) fn word_to_chars(&self, word: u32, sequence: usize) -> Option<(u32, u32)> {
+        let (start, end) = self.word_to_tokens(word, sequence)?;
+        Some((self.offsets[start].0, self.offsets[end - 1].1))
+    }
+
+    /// Returns the first token of `sequence` whose offsets contain `position`.
+    pub(crate) fn char_to_token(&self, position: u32, sequence: usize) -> Option<usize> {
+        (0..self.len()).find(|index| {
+            let (start, end) = self.offsets[*index];
+            self.sequence_ids[*index] == Some(sequence) && position >= start && position < end
+        })
+    }
+
+    pub(crate) fn char_to_word(&self, position: u32, sequence: usize) -> Option<u32> {
+        let token = self.char_to_token(position, sequence)?;
+        self.token_to_word(token).map(|(_, word)| word)
+    }
+
     pub(crate) fn numeric_len_and_validate(
         &self,
         destination_length: usize,
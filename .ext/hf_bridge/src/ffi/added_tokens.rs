@@ -0,0 +1,133 @@
+use std::os::raw::{c_char, c_int};
+
+use tokenizers::AddedToken;
+
+use crate::added_tokens::{add_tokens, remove_tokens, CAddedToken, TokenSpec};
+use crate::error::{clear_error, store_error};
+use crate::tokenizer::CTokenizer;
+
+use super::utils::{copy_slice, read_required_utf8, read_utf8_array, set_status};
+
+/// Adds `count` tokens with their full `AddedToken` options and writes the id each one received
+/// into `ids`. A non-negative `pinned_id` either renames the added token already holding that id
+/// or must be the next free id; otherwise the call fails with status `5` and nothing changes.
+/// Content already in the model vocabulary keeps its vocabulary id, and ids the model vocabulary
+/// assigns to other content (e.g. BERT's `[unusedN]`) cannot be pinned; both fail with status `5`.
+///
+/// # Safety
+/// `tokenizer` must be a valid pointer, `tokens` must hold `count` entries with UTF-8 `content`, and `ids` must be null or have room for `count` ids.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_add_tokens(
+    tokenizer: *mut CTokenizer,
+    tokens: *const CAddedToken,
+    count: usize,
+    ids: *mut u32,
+    status: *mut c_int,
+) -> c_int {
+    let Some(tokenizer) = (unsafe { tokenizer.as_mut() }) else {
+        store_error("tokenizers_add_tokens received null tokenizer");
+        set_status(status, 1);
+        return 0;
+    };
+
+    if count > 0 && tokens.is_null() {
+        store_error("tokenizers_add_tokens received null tokens pointer");
+        set_status(status, 2);
+        return 0;
+    }
+
+    let entries = if count == 0 {
+        &[][..]
+    } else {
+        unsafe { std::slice::from_raw_parts(tokens, count) }
+    };
+
+    let mut specs = Vec::with_capacity(count);
+    for entry in entries {
+        let content = match read_required_utf8(entry.content) {
+            Ok(value) => value,
+            Err(message) => {
+                store_error(message);
+                set_status(status, 3);
+                return 0;
+            }
+        };
+
+        let pinned_id = if entry.pinned_id < 0 {
+            None
+        } else {
+            match u32::try_from(entry.pinned_id) {
+                Ok(id) => Some(id),
+                Err(_) => {
+                    store_error("tokenizers_add_tokens received pinned id out of range");
+                    set_status(status, 4);
+                    return 0;
+                }
+            }
+        };
+
+        specs.push(TokenSpec {
+            token: AddedToken::from(content, entry.special)
+                .single_word(entry.single_word)
+                .lstrip(entry.lstrip)
+                .rstrip(entry.rstrip)
+                .normalized(entry.normalized),
+            pinned_id,
+        });
+    }
+
+    match add_tokens(tokenizer.inner_mut(), specs) {
+        Ok(assigned) => {
+            copy_slice(&assigned, ids, count);
+            clear_error();
+            set_status(status, 0);
+            assigned.len() as c_int
+        }
+        Err(err) => {
+            store_error(&format!("tokenizers_add_tokens: {}", err.into_message()));
+            set_status(status, 5);
+            0
+        }
+    }
+}
+
+/// Removes the added tokens with the given contents and returns how many were removed. Fails with
+/// status `3` when removal would shift the ids of the remaining added tokens.
+///
+/// # Safety
+/// `tokenizer` must be a valid pointer and `tokens` must hold `count` UTF-8 pointers.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_remove_tokens(
+    tokenizer: *mut CTokenizer,
+    tokens: *const *const c_char,
+    count: usize,
+    status: *mut c_int,
+) -> c_int {
+    let Some(tokenizer) = (unsafe { tokenizer.as_mut() }) else {
+        store_error("tokenizers_remove_tokens received null tokenizer");
+        set_status(status, 1);
+        return 0;
+    };
+
+    let contents = match read_utf8_array(tokens, count) {
+        Ok(values) => values,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 2);
+            return 0;
+        }
+    };
+
+    match remove_tokens(tokenizer.inner_mut(), &contents) {
+        Ok(removed) => {
+            clear_error();
+            set_status(status, 0);
+            removed as c_int
+        }
+        Err(err) => {
+            store_error(&format!("tokenizers_remove_tokens: {}", err.into_message()));
+            set_status(status, 3);
+            0
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+
+use crate::batch::{batch_inputs, encode_batch_fast, BatchError};
+use crate::batch_plan::{BatchBudget, CBatchPlan};
+use crate::error::{clear_error, store_error};
+use crate::tensor::{fill_tensors, CBatchTensorDest, TensorElementType};
+use crate::tokenizer::CTokenizer;
+
+use super::utils::{read_optional_utf8_array, read_utf8_array, set_length, set_status};
+
+/// Encodes a batch once and groups it into length-bucketed batches limited by `max_rows` and/or
+/// `max_tokens` padded tokens per batch (`0` disables a limit). Padded lengths follow the tokenizer's
+/// padding settings, including `pad_to_multiple_of`.
+///
+/// # Safety
+/// `sequences` must hold `count` UTF-8 pointers and `pairs` must be null or hold `count` nullable UTF-8 pointers; the caller frees the plan with `tokenizers_batch_plan_free`.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_plan_batches(
+    tokenizer: *const CTokenizer,
+    sequences: *const *const c_char,
+    pairs: *const *const c_char,
+    count: usize,
+    add_special_tokens: bool,
+    max_rows: usize,
+    max_tokens: usize,
+    max_threads: usize,
+    status: *mut c_int,
+) -> *mut CBatchPlan {
+    let Some(tokenizer) = (unsafe { tokenizer.as_ref() }) else {
+        store_error("tokenizers_plan_batches received null tokenizer");
+        set_status(status, 1);
+        return ptr::null_mut();
+    };
+
+    let primary = match read_utf8_array(sequences, count) {
+        Ok(values) => values,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 2);
+            return ptr::null_mut();
+        }
+    };
+
+    let pair_texts = match read_optional_utf8_array(pairs, count) {
+        Ok(values) => values,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 3);
+            return ptr::null_mut();
+        }
+    };
+
+    let inputs = batch_inputs(&primary, pair_texts.as_deref());
+    let encodings = match encode_batch_fast(
+        tokenizer.unpadded(),
+        inputs,
+        add_special_tokens,
+        max_threads,
+    ) {
+        Ok(values) => values,
+        Err(err) => {
+            let code = match err {
+                BatchError::ThreadPool(_) => 4,
+                BatchError::Encode(_) => 5,
+            };
+            store_error(&format!("tokenizers_plan_batches: {}", err.into_message()));
+            set_status(status, code);
+            return ptr::null_mut();
+        }
+    };
+
+    let padding = tokenizer.inner().get_padding().cloned().unwrap_or_default();
+    let budget = BatchBudget {
+        max_rows,
+        max_tokens,
+    };
+
+    clear_error();
+    set_status(status, 0);
+    Box::into_raw(Box::new(CBatchPlan::new(encodings, padding, budget)))
+}
+
+/// # Safety
+/// `plan` must be null or a pointer returned by `tokenizers_plan_batches`.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_batch_plan_count(plan: *const CBatchPlan) -> usize {
+    match unsafe { plan.as_ref() } {
+        Some(plan) => plan.batch_count(),
+        None => 0,
+    }
+}
+
+/// Returns the plan as JSON: each batch's input `indices`, `padded_length` and `padding_waste`, plus the overall waste.
+///
+/// # Safety
+/// `plan` must be a pointer returned by `tokenizers_plan_batches`; the caller frees the string with `tokenizers_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_batch_plan_to_json(
+    plan: *const CBatchPlan,
+    status: *mut c_int,
+) -> *mut c_char {
+    let Some(plan) = (unsafe { plan.as_ref() }) else {
+        store_error("tokenizers_batch_plan_to_json received null plan");
+        set_status(status, 1);
+        return ptr::null_mut();
+    };
+
+    let json = match plan.to_json() {
+        Ok(value) => value,
+        Err(err) => {
+            store_error(&format!(
+                "tokenizers_batch_plan_to_json failed to serialize plan: {err}"
+            ));
+            set_status(status, 2);
+            return ptr::null_mut();
+        }
+    };
+
+    match CString::new(json) {
+        Ok(value) => {
+            clear_error();
+            set_status(status, 0);
+            value.into_raw()
+        }
+        Err(_) => {
+            store_error("tokenizers_batch_plan_to_json produced interior null byte");
+            set_status(status, 3);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Writes planned batch `batch_index` into row-major tensors padded to its planned length, rows in plan order.
+///
+/// # Safety
+/// `plan` must be a pointer returned by `tokenizers_plan_batches` and every non-null buffer in `destination` must hold `capacity` elements of the chosen type.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_batch_plan_materialize(
+    plan: *const CBatchPlan,
+    batch_index: usize,
+    element_type: c_int,
+    destination: *const CBatchTensorDest,
+    sequence_length: *mut usize,
+    status: *mut c_int,
+) -> c_int {
+    let Some(plan) = (unsafe { plan.as_ref() }) else {
+        store_error("tokenizers_batch_plan_materialize received null plan");
+        set_status(status, 1);
+        return 0;
+    };
+
+    let Some(destination) = (unsafe { destination.as_ref() }) else {
+        store_error("tokenizers_batch_plan_materialize received null destination");
+        set_status(status, 2);
+        return 0;
+    };
+
+    let element_type =
+        match TensorElementType::from_raw(element_type, "tokenizers_batch_plan_materialize") {
+            Ok(value) => value,
+            Err(message) => {
+                store_error(&message);
+                set_status(status, 3);
+                return 0;
+            }
+        };
+
+    let (padded_length, rows) = match plan
+        .batch(batch_index)
+        .and_then(|batch| Ok((batch.padded_length, plan.padded_rows(batch_index)?)))
+    {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 4);
+            return 0;
+        }
+    };
+
+    set_length(sequence_length, padded_length);
+
+    match fill_tensors(
+        &rows,
+        padded_length,
+        element_type,
+        destination,
+        "tokenizers_batch_plan_materialize",
+    ) {
+        Ok(count) => {
+            clear_error();
+            set_status(status, 0);
+            count as c_int
+        }
+        Err(message) => {
+            store_error(&message);
+            set_status(status, 5);
+            0
+        }
+    }
+}
+
+/// # Safety
+/// `plan` must be null or a pointer returned by `tokenizers_plan_batches` and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_batch_plan_free(plan: *mut CBatchPlan) {
+    if plan.is_null() {
+        return;
+    }
+
+    unsafe {
+        drop(Box::from_raw(plan));
+    }
+}
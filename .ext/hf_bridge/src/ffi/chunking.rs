@@ -0,0 +1,103 @@
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+
+use crate::chunking::{chunk_text, ChunkBoundary, ChunkWindow, ChunkingError};
+use crate::error::{clear_error, store_error};
+use crate::offsets::OffsetMode;
+use crate::tokenizer::CTokenizer;
+
+use super::utils::{read_required_utf8, set_status};
+
+/// Splits `text` into overlapping token windows and returns them as a JSON array of
+/// `{ "ids", "start", "end", "text" }` objects. `boundary` is `0` for any token, `1` for whitespace,
+/// or `2` for sentence ends; spans use `offset_mode` units. The tokenizer's own truncation and padding are ignored.
+///
+/// # Safety
+/// `tokenizer` must be valid, `text` must be a null-terminated UTF-8 string, and `status` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_chunk_text(
+    tokenizer: *const CTokenizer,
+    text: *const c_char,
+    max_tokens: usize,
+    overlap: usize,
+    reserved_tokens: usize,
+    boundary: c_int,
+    offset_mode: c_int,
+    status: *mut c_int,
+) -> *mut c_char {
+    let Some(tokenizer) = (unsafe { tokenizer.as_ref() }) else {
+        store_error("tokenizers_chunk_text received null tokenizer");
+        set_status(status, 1);
+        return ptr::null_mut();
+    };
+
+    let document = match read_required_utf8(text) {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 2);
+            return ptr::null_mut();
+        }
+    };
+
+    let boundary = match ChunkBoundary::from_raw(boundary) {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(&format!("tokenizers_chunk_text: {message}"));
+            set_status(status, 3);
+            return ptr::null_mut();
+        }
+    };
+
+    let mode = match OffsetMode::from_raw(offset_mode) {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(&format!("tokenizers_chunk_text: {message}"));
+            set_status(status, 4);
+            return ptr::null_mut();
+        }
+    };
+
+    let window = ChunkWindow {
+        max_tokens,
+        overlap,
+        reserved_tokens,
+        boundary,
+    };
+
+    let chunks = match chunk_text(tokenizer.unconstrained(), &document, window, mode) {
+        Ok(values) => values,
+        Err(err) => {
+            let code = match err {
+                ChunkingError::InvalidWindow(_) => 5,
+                ChunkingError::Encode(_) | ChunkingError::Decode(_) => 6,
+            };
+            store_error(&format!("tokenizers_chunk_text: {}", err.into_message()));
+            set_status(status, code);
+            return ptr::null_mut();
+        }
+    };
+
+    match serde_json::to_string(&chunks) {
+        Ok(json) => match CString::new(json) {
+            Ok(value) => {
+                clear_error();
+                set_status(status, 0);
+                value.into_raw()
+            }
+            Err(_) => {
+                store_error("tokenizers_chunk_text failed to allocate CString");
+                set_status(status, 7);
+                ptr::null_mut()
+            }
+        },
+        Err(err) => {
+            store_error(&format!(
+                "tokenizers_chunk_text failed to serialize chunks: {err}"
+            ));
+            set_status(status, 8);
+            ptr::null_mut()
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+
+use crate::components::{get_component, set_component, Component};
+use crate::error::{clear_error, store_error};
+use crate::tokenizer::CTokenizer;
+
+use super::utils::{read_optional_utf8, set_status};
+
+fn set_from_json(
+    tokenizer: *mut CTokenizer,
+    component: Component,
+    json: *const c_char,
+    context: &str,
+    status: *mut c_int,
+) -> c_int {
+    let Some(tokenizer) = (unsafe { tokenizer.as_mut() }) else {
+        store_error(&format!("{context} received null tokenizer"));
+        set_status(status, 1);
+        return 0;
+    };
+
+    let payload = match read_optional_utf8(json) {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 2);
+            return 0;
+        }
+    };
+
+    match set_component(tokenizer.inner_mut(), component, payload.as_deref()) {
+        Ok(()) => {
+            clear_error();
+            set_status(status, 0);
+            1
+        }
+        Err(err) => {
+            store_error(&format!("{context}: {}", err.into_message()));
+            set_status(status, 3);
+            0
+        }
+    }
+}
+
+fn get_as_json(
+    tokenizer: *const CTokenizer,
+    component: Component,
+    context: &str,
+    status: *mut c_int,
+) -> *mut c_char {
+    let Some(tokenizer) = (unsafe { tokenizer.as_ref() }) else {
+        store_error(&format!("{context} received null tokenizer"));
+        set_status(status, 1);
+        return ptr::null_mut();
+    };
+
+    let json = match get_component(tokenizer.inner(), component) {
+        Ok(value) => value,
+        Err(err) => {
+            store_error(&format!("{context}: {}", err.into_message()));
+            set_status(status, 2);
+            return ptr::null_mut();
+        }
+    };
+
+    match CString::new(json) {
+        Ok(value) => {
+            clear_error();
+            set_status(status, 0);
+            value.into_raw()
+        }
+        Err(_) => {
+            store_error(&format!("{context} failed to allocate CString"));
+            set_status(status, 3);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Replaces the normalizer with a tokenizer.json `normalizer` fragment; null removes it.
+///
+/// # Safety
+/// `tokenizer` must be a valid pointer and `json` null or a null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_set_normalizer(
+    tokenizer: *mut CTokenizer,
+    json: *const c_char,
+    status: *mut c_int,
+) -> c_int {
+    set_from_json(
+        tokenizer,
+        Component::Normalizer,
+        json,
+        "tokenizers_set_normalizer",
+        status,
+    )
+}
+
+/// Returns the normalizer as a tokenizer.json fragment, or `null` when none is set.
+///
+/// # Safety
+/// `tokenizer` must be a valid pointer; the caller frees the result with `tokenizers_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_get_normalizer(
+    tokenizer: *const CTokenizer,
+    status: *mut c_int,
+) -> *mut c_char {
+    get_as_json(
+        tokenizer,
+        Component::Normalizer,
+        "tokenizers_get_normalizer",
+        status,
+    )
+}
+
+/// Replaces the pre-tokenizer with a tokenizer.json `pre_tokenizer` fragment; null removes it.
+///
+/// # Safety
+/// `tokenizer` must be a valid pointer and `json` null or a null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_set_pre_tokenizer(
+    tokenizer: *mut CTokenizer,
+    json: *const c_char,
+    status: *mut c_int,
+) -> c_int {
+    set_from_json(
+        tokenizer,
+        Component::PreTokenizer,
+        json,
+        "tokenizers_set_pre_tokenizer",
+        status,
+    )
+}
+
+/// Returns the pre-tokenizer as a tokenizer.json fragment, or `null` when none is set.
+///
+/// # Safety
+/// `tokenizer` must be a valid pointer; the caller frees the result with `tokenizers_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_get_pre_tokenizer(
+    tokenizer: *const CTokenizer,
+    status: *mut c_int,
+) -> *mut c_char {
+    get_as_json(
+        tokenizer,
+        Component::PreTokenizer,
+        "tokenizers_get_pre_tokenizer",
+        status,
+    )
+}
+
+/// Replaces the post-processor with a tokenizer.json `post_processor` fragment; null removes it.
+///
+/// # Safety
+/// `tokenizer` must be a valid pointer and `json` null or a null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_set_post_processor(
+    tokenizer: *mut CTokenizer,
+    json: *const c_char,
+    status: *mut c_int,
+) -> c_int {
+    set_from_json(
+        tokenizer,
+        Component::PostProcessor,
+        json,
+        "tokenizers_set_post_processor",
+        status,
+    )
+}
+
+/// Returns the post-processor as a tokenizer.json fragment, or `null` when none is set.
+///
+/// # Safety
+/// `tokenizer` must be a valid pointer; the caller frees the result with `tokenizers_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_get_post_processor(
+    tokenizer: *const CTokenizer,
+    status: *mut c_int,
+) -> *mut c_char {
+    get_as_json(
+        tokenizer,
+        Component::PostProcessor,
+        "tokenizers_get_post_processor",
+        status,
+    )
+}
+
+/// Replaces the decoder with a tokenizer.json `decoder` fragment; null removes it.
+///
+/// # Safety
+/// `tokenizer` must be a valid pointer and `json` null or a null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_set_decoder(
+    tokenizer: *mut CTokenizer,
+    json: *const c_char,
+    status: *mut c_int,
+) -> c_int {
+    set_from_json(
+        tokenizer,
+        Component::Decoder,
+        json,
+        "tokenizers_set_decoder",
+        status,
+    )
+}
+
+/// Returns the decoder as a tokenizer.json fragment, or `null` when none is set.
+///
+/// # Safety
+/// `tokenizer` must be a valid pointer; the caller frees the result with `tokenizers_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_get_decoder(
+    tokenizer: *const CTokenizer,
+    status: *mut c_int,
+) -> *mut c_char {
+    get_as_json(
+        tokenizer,
+        Component::Decoder,
+        "tokenizers_get_decoder",
+        status,
+    )
+}
@@ -0,0 +1,88 @@
+use std::os::raw::{c_char, c_int};
+use std::path::PathBuf;
+use std::ptr;
+
+use crate::convert::{convert, ConvertError, ConvertHints, LegacyFamily};
+use crate::error::{clear_error, store_error};
+use crate::tokenizer::CTokenizer;
+
+use super::utils::{read_optional_utf8, read_required_utf8, set_status};
+
+/// Builds a tokenizer from slow-tokenizer files the way `transformers` converts them to a fast
+/// tokenizer; the result can be exported with `tokenizers_get_config`.
+///
+/// `family` is -1 (detect from `tokenizer_config` or the files), 0 BERT, 1 GPT-2, 2 RoBERTa,
+/// 3 Llama, 4 T5 or 5 XLM-R. `vocab_path` is vocab.txt, vocab.json or the SentencePiece .model,
+/// `merges_path` the merges.txt of the byte-level BPE families, and `tokenizer_config` the
+/// optional tokenizer_config.json contents.
+///
+/// # Safety
+/// `vocab_path` must be a null-terminated UTF-8 string, `merges_path` and `tokenizer_config` must be
+/// null or UTF-8, and `status` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_from_legacy_files(
+    family: c_int,
+    vocab_path: *const c_char,
+    merges_path: *const c_char,
+    tokenizer_config: *const c_char,
+    status: *mut c_int,
+) -> *mut CTokenizer {
+    let family = match LegacyFamily::from_raw(family) {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 1);
+            return ptr::null_mut();
+        }
+    };
+
+    let paths = read_required_utf8(vocab_path)
+        .and_then(|primary| Ok((primary, read_optional_utf8(merges_path)?)));
+    let (primary, secondary) = match paths {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 2);
+            return ptr::null_mut();
+        }
+    };
+
+    let hints = match read_optional_utf8(tokenizer_config) {
+        Ok(config) => ConvertHints::from_config(config.as_deref()),
+        Err(message) => {
+            store_error(message);
+            set_status(status, 3);
+            return ptr::null_mut();
+        }
+    };
+
+    let converted = hints.and_then(|hints| {
+        convert(
+            family,
+            &PathBuf::from(primary),
+            secondary.map(PathBuf::from).as_deref(),
+            &hints,
+        )
+    });
+
+    match converted {
+        Ok(tokenizer) => {
+            clear_error();
+            set_status(status, 0);
+            Box::into_raw(Box::new(CTokenizer::new(tokenizer)))
+        }
+        Err(err) => {
+            let code = match err {
+                ConvertError::Io(_) => 4,
+                ConvertError::Format(_) | ConvertError::UnknownFamily(_) => 5,
+                ConvertError::Build(_) => 6,
+            };
+            store_error(&format!(
+                "tokenizers_from_legacy_files failed: {}",
+                err.into_message()
+            ));
+            set_status(status, code);
+            ptr::null_mut()
+        }
+    }
+}
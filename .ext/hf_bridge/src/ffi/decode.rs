@@ -1,11 +1,33 @@
 use std::ffi::CString;
-use std::os::raw::{c_char, c_int};
+use std::os::raw::{c_char, c_int, c_void};
 use std::ptr;
 
+use crate::batch::{decode_batch, DecodeBatchError};
+use crate::decode_spans::decode_with_spans;
+use crate::encoding::CEncodingOffset;
 use crate::error::{clear_error, store_error};
+use crate::offsets::{OffsetConverter, OffsetMode};
 use crate::tokenizer::CTokenizer;
 
-use super::utils::set_status;
+use super::utils::{set_length, set_status};
+
+/// Splits a flat id buffer into `lengths`-sized sequences, or `None` when the lengths overrun it.
+fn split_sequences<'a>(tokens: &'a [u32], lengths: &[usize]) -> Option<Vec<&'a [u32]>> {
+    let mut borrowed = Vec::with_capacity(lengths.len());
+    let mut offset = 0usize;
+
+    for &length in lengths {
+        let end = offset.saturating_add(length);
+        if end > tokens.len() {
+            return None;
+        }
+
+        borrowed.push(&tokens[offset..end]);
+        offset = end;
+    }
+
+    Some(borrowed)
+}
 
 /// # Safety
 /// `tokenizer`, `ids`, and `status` must be valid pointers; `ids` must reference at least `length` elements when `length > 0`.
@@ -56,6 +78,81 @@ pub unsafe extern "C" fn tokenizers_decode(
     }
 }
 
+/// Decodes like `tokenizers_decode` and writes the span each id produced in the result into `spans`,
+/// in `offset_mode` units (`0` bytes, `1` chars, `2` UTF-16 code units). Ids that produce no text,
+/// such as skipped special tokens, receive empty spans.
+///
+/// # Safety
+/// `tokenizer` and `status` must be valid pointers and `ids` and `spans` must each reference at least `length` elements when `length > 0`.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_decode_with_spans(
+    tokenizer: *const CTokenizer,
+    ids: *const u32,
+    length: usize,
+    skip_special_tokens: bool,
+    offset_mode: c_int,
+    spans: *mut CEncodingOffset,
+    status: *mut c_int,
+) -> *mut c_char {
+    let Some(tokenizer) = (unsafe { tokenizer.as_ref() }) else {
+        store_error("tokenizers_decode_with_spans received null tokenizer");
+        set_status(status, 1);
+        return ptr::null_mut();
+    };
+
+    if length > 0 && (ids.is_null() || spans.is_null()) {
+        store_error("tokenizers_decode_with_spans received null buffer");
+        set_status(status, 2);
+        return ptr::null_mut();
+    }
+
+    let mode = match OffsetMode::from_raw(offset_mode) {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 3);
+            return ptr::null_mut();
+        }
+    };
+
+    let tokens = if length == 0 {
+        &[][..]
+    } else {
+        unsafe { std::slice::from_raw_parts(ids, length) }
+    };
+
+    let (text, byte_spans) = match decode_with_spans(tokenizer.inner(), tokens, skip_special_tokens)
+    {
+        Ok(value) => value,
+        Err(err) => {
+            store_error(&format!("tokenizers_decode_with_spans failed: {err}"));
+            set_status(status, 4);
+            return ptr::null_mut();
+        }
+    };
+
+    let converter = OffsetConverter::new(&text, mode);
+    for (index, (start, end)) in byte_spans.into_iter().enumerate() {
+        let (start, end) = converter.convert(start, end);
+        unsafe {
+            *spans.add(index) = CEncodingOffset { start, end };
+        }
+    }
+
+    match CString::new(text) {
+        Ok(value) => {
+            clear_error();
+            set_status(status, 0);
+            value.into_raw()
+        }
+        Err(_) => {
+            store_error("tokenizers_decode_with_spans failed to allocate CString");
+            set_status(status, 5);
+            ptr::null_mut()
+        }
+    }
+}
+
 /// # Safety
 /// All pointer arguments must be valid; `tokens` must contain `total_length` elements and the `lengths` slice must describe `count` sequences.
 #[no_mangle]
@@ -100,25 +197,11 @@ pub unsafe extern "C" fn tokenizers_decode_batch_flat(
         unsafe { std::slice::from_raw_parts(tokens, total_length) }
     };
 
-    let mut borrowed = Vec::with_capacity(count);
-    let mut offset = 0usize;
-
-    for &length in lengths_slice.iter() {
-        if length == 0 {
-            borrowed.push(&tokens_slice[offset..offset]);
-            continue;
-        }
-
-        let end = offset.saturating_add(length);
-        if end > tokens_slice.len() {
-            store_error("tokenizers_decode_batch_flat detected inconsistent lengths");
-            set_status(status, 4);
-            return 0;
-        }
-
-        borrowed.push(&tokens_slice[offset..end]);
-        offset = end;
-    }
+    let Some(borrowed) = split_sequences(tokens_slice, lengths_slice) else {
+        store_error("tokenizers_decode_batch_flat detected inconsistent lengths");
+        set_status(status, 4);
+        return 0;
+    };
 
     match tokenizer
         .inner()
@@ -164,3 +247,151 @@ pub unsafe extern "C" fn tokenizers_decode_batch_flat(
         }
     }
 }
+
+/// Decodes a flat id buffer into one caller-owned text buffer, `0` as UTF-8 bytes or `1` as UTF-16
+/// code units, without terminators. Sequence `i` occupies `offsets[i]..offsets[i + 1]`, in units.
+/// Pass a null `buffer` to only receive the `required` size; a non-null buffer smaller than that
+/// fails with status `9` and still reports `required`. Sequences decode in parallel under `max_threads`.
+///
+/// # Safety
+/// `tokens` must contain `total_length` ids, `lengths` must describe `count` sequences, `buffer` must be null or hold `capacity` units, and `offsets` must hold `count + 1` entries when `buffer` is non-null.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_decode_batch_into(
+    tokenizer: *const CTokenizer,
+    tokens: *const u32,
+    total_length: usize,
+    lengths: *const usize,
+    count: usize,
+    skip_special_tokens: bool,
+    max_threads: usize,
+    text_encoding: c_int,
+    buffer: *mut c_void,
+    capacity: usize,
+    offsets: *mut usize,
+    required: *mut usize,
+    status: *mut c_int,
+) -> c_int {
+    let Some(tokenizer) = (unsafe { tokenizer.as_ref() }) else {
+        store_error("tokenizers_decode_batch_into received null tokenizer");
+        set_status(status, 1);
+        return 0;
+    };
+
+    if count > 0 && lengths.is_null() {
+        store_error("tokenizers_decode_batch_into received null lengths pointer");
+        set_status(status, 2);
+        return 0;
+    }
+
+    if total_length > 0 && tokens.is_null() {
+        store_error("tokenizers_decode_batch_into received null tokens pointer");
+        set_status(status, 3);
+        return 0;
+    }
+
+    let utf16 = match text_encoding {
+        0 => false,
+        1 => true,
+        other => {
+            store_error(&format!(
+                "tokenizers_decode_batch_into unknown text encoding: {other}"
+            ));
+            set_status(status, 4);
+            return 0;
+        }
+    };
+
+    let lengths_slice = if count == 0 {
+        &[][..]
+    } else {
+        unsafe { std::slice::from_raw_parts(lengths, count) }
+    };
+
+    let tokens_slice = if total_length == 0 {
+        &[][..]
+    } else {
+        unsafe { std::slice::from_raw_parts(tokens, total_length) }
+    };
+
+    let Some(borrowed) = split_sequences(tokens_slice, lengths_slice) else {
+        store_error("tokenizers_decode_batch_into detected inconsistent lengths");
+        set_status(status, 5);
+        return 0;
+    };
+
+    let texts = match decode_batch(
+        tokenizer.inner(),
+        &borrowed,
+        skip_special_tokens,
+        max_threads,
+    ) {
+        Ok(values) => values,
+        Err(err) => {
+            let code = match err {
+                DecodeBatchError::ThreadPool(_) => 6,
+                DecodeBatchError::Decode(_) => 7,
+            };
+            store_error(&format!(
+                "tokenizers_decode_batch_into: {}",
+                err.into_message()
+            ));
+            set_status(status, code);
+            return 0;
+        }
+    };
+
+    let mut boundaries = Vec::with_capacity(count + 1);
+    boundaries.push(0usize);
+    let mut total = 0usize;
+    for text in &texts {
+        total += if utf16 {
+            text.encode_utf16().count()
+        } else {
+            text.len()
+        };
+        boundaries.push(total);
+    }
+
+    set_length(required, total);
+
+    if buffer.is_null() {
+        clear_error();
+        set_status(status, 0);
+        return 0;
+    }
+
+    if offsets.is_null() {
+        store_error("tokenizers_decode_batch_into received null offsets pointer");
+        set_status(status, 8);
+        return 0;
+    }
+
+    if capacity < total {
+        store_error("tokenizers_decode_batch_into buffer is too small");
+        set_status(status, 9);
+        return 0;
+    }
+
+    if utf16 {
+        let destination = unsafe { std::slice::from_raw_parts_mut(buffer as *mut u16, total) };
+        for (slot, unit) in destination
+            .iter_mut()
+            .zip(texts.iter().flat_map(|text| text.encode_utf16()))
+        {
+            *slot = unit;
+        }
+    } else {
+        let destination = unsafe { std::slice::from_raw_parts_mut(buffer as *mut u8, total) };
+        let mut position = 0usize;
+        for text in &texts {
+            destination[position..position + text.len()].copy_from_slice(text.as_bytes());
+            position += text.len();
+        }
+    }
+
+    unsafe { std::ptr::copy_nonoverlapping(boundaries.as_ptr(), offsets, boundaries.len()) };
+
+    clear_error();
+    set_status(status, 0);
+    count as c_int
+}
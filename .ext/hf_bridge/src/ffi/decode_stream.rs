@@ -0,0 +1,142 @@
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+
+use crate::decode_stream::CDecodeStream;
+use crate::error::{clear_error, store_error};
+use crate::tokenizer::CTokenizer;
+
+use super::utils::set_status;
+
+fn into_c_string(value: String, context: &str, status: *mut c_int, code: c_int) -> *mut c_char {
+    match CString::new(value) {
+        Ok(value) => {
+            clear_error();
+            set_status(status, 0);
+            value.into_raw()
+        }
+        Err(_) => {
+            store_error(&format!("{context} produced interior null byte"));
+            set_status(status, code);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Creates an incremental detokenizer; free it with `tokenizers_decode_stream_free`.
+///
+/// # Safety
+/// The returned pointer must only be released through `tokenizers_decode_stream_free`.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_decode_stream_new(
+    skip_special_tokens: bool,
+) -> *mut CDecodeStream {
+    Box::into_raw(Box::new(CDecodeStream::new(skip_special_tokens)))
+}
+
+/// Pushes `length` ids into the stream and returns only the newly completed text. The result is an
+/// empty string while the stream holds back an incomplete character or prefix-space artifact.
+///
+/// # Safety
+/// `stream` and `tokenizer` must be valid pointers, `ids` must reference `length` elements when `length > 0`, and the caller frees the result with `tokenizers_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_decode_stream_step(
+    stream: *mut CDecodeStream,
+    tokenizer: *const CTokenizer,
+    ids: *const u32,
+    length: usize,
+    status: *mut c_int,
+) -> *mut c_char {
+    let Some(stream) = (unsafe { stream.as_mut() }) else {
+        store_error("tokenizers_decode_stream_step received null stream");
+        set_status(status, 1);
+        return ptr::null_mut();
+    };
+
+    let Some(tokenizer) = (unsafe { tokenizer.as_ref() }) else {
+        store_error("tokenizers_decode_stream_step received null tokenizer");
+        set_status(status, 2);
+        return ptr::null_mut();
+    };
+
+    if length > 0 && ids.is_null() {
+        store_error("tokenizers_decode_stream_step received null ids pointer");
+        set_status(status, 3);
+        return ptr::null_mut();
+    }
+
+    let ids = if length == 0 {
+        &[][..]
+    } else {
+        unsafe { std::slice::from_raw_parts(ids, length) }
+    };
+
+    match stream.step(tokenizer.inner(), ids) {
+        Ok(text) => into_c_string(
+            text.unwrap_or_default(),
+            "tokenizers_decode_stream_step",
+            status,
+            4,
+        ),
+        Err(err) => {
+            store_error(&format!("tokenizers_decode_stream_step failed: {err}"));
+            set_status(status, 5);
+            ptr::null_mut()
+        }
+    }
+}
+/// Returns the text still held back by the stream, incomplete characters included, and resets it.
+/// Text the decoder rewrote after it was returned is reported from the first changed character.
+/// Returns the text still held back by the stream, incomplete characters included, and resets it.
+///
+/// # Safety
+/// `stream` and `tokenizer` must be valid pointers; the caller frees the result with `tokenizers_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_decode_stream_flush(
+    stream: *mut CDecodeStream,
+    tokenizer: *const CTokenizer,
+    status: *mut c_int,
+) -> *mut c_char {
+    let Some(stream) = (unsafe { stream.as_mut() }) else {
+        store_error("tokenizers_decode_stream_flush received null stream");
+        set_status(status, 1);
+        return ptr::null_mut();
+    };
+
+    let Some(tokenizer) = (unsafe { tokenizer.as_ref() }) else {
+        store_error("tokenizers_decode_stream_flush received null tokenizer");
+        set_status(status, 2);
+        return ptr::null_mut();
+    };
+
+    match stream.flush(tokenizer.inner()) {
+        Ok(text) => into_c_string(text, "tokenizers_decode_stream_flush", status, 3),
+        Err(err) => {
+            store_error(&format!("tokenizers_decode_stream_flush failed: {err}"));
+            set_status(status, 4);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// # Safety
+/// `stream` must be null or a pointer returned by `tokenizers_decode_stream_new`.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_decode_stream_reset(stream: *mut CDecodeStream) {
+    if let Some(stream) = unsafe { stream.as_mut() } {
+        stream.reset();
+    }
+}
+
+/// # Safety
+/// `stream` must be null or a pointer returned by `tokenizers_decode_stream_new` and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_decode_stream_free(stream: *mut CDecodeStream) {
+    if stream.is_null() {
+        return;
+    }
+
+    unsafe {
+        drop(Box::from_raw(stream));
+    }
+}
@@ -0,0 +1,87 @@
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int};
+use std::path::Path;
+use std::ptr;
+
+use crate::directory::{load_directory, DirectoryError, DirectoryMetadata};
+use crate::error::{clear_error, store_error};
+use crate::tokenizer::CTokenizer;
+
+use super::utils::{read_required_utf8, set_status};
+
+/// Loads a tokenizer from a model directory (or a tokenizer.json inside one) and applies
+/// tokenizer_config.json, special_tokens_map.json, added_tokens.json and chat_template.jinja.
+///
+/// With `apply_defaults` the BOS/EOS flags, pad token, padding side and model_max_length also
+/// configure the post-processor, padding and truncation. When `metadata` is non-null it receives
+/// the resolved settings as JSON, to be released with `tokenizers_free_string`.
+///
+/// # Safety
+/// `path` must be a null-terminated UTF-8 string, and `metadata` and `status` must be null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_load_directory(
+    path: *const c_char,
+    apply_defaults: bool,
+    metadata: *mut *mut c_char,
+    status: *mut c_int,
+) -> *mut CTokenizer {
+    if !metadata.is_null() {
+        unsafe { *metadata = ptr::null_mut() };
+    }
+
+    let location = match read_required_utf8(path) {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 1);
+            return ptr::null_mut();
+        }
+    };
+
+    let loaded = match load_directory(Path::new(&location), apply_defaults) {
+        Ok(value) => value,
+        Err(err) => {
+            let code = match err {
+                DirectoryError::NotFound(_) => 2,
+                DirectoryError::Io(_) => 3,
+                DirectoryError::Parse { .. } => 4,
+                DirectoryError::Load(_) => 5,
+                DirectoryError::Apply(_) => 6,
+            };
+            store_error(&format!(
+                "tokenizers_load_directory failed: {}",
+                err.into_message()
+            ));
+            set_status(status, code);
+            return ptr::null_mut();
+        }
+    };
+
+    if !write_metadata(&loaded.metadata, metadata) {
+        store_error("tokenizers_load_directory failed to serialize metadata");
+        set_status(status, 7);
+        return ptr::null_mut();
+    }
+
+    clear_error();
+    set_status(status, 0);
+    Box::into_raw(Box::new(CTokenizer::new(loaded.tokenizer)))
+}
+
+/// Stores `metadata` as JSON into `target` when it is non-null; returns `false` if that fails.
+pub(crate) fn write_metadata(metadata: &DirectoryMetadata, target: *mut *mut c_char) -> bool {
+    if target.is_null() {
+        return true;
+    }
+
+    let json = serde_json::to_string(metadata)
+        .ok()
+        .and_then(|json| CString::new(json).ok());
+    match json {
+        Some(json) => {
+            unsafe { *target = json.into_raw() };
+            true
+        }
+        None => false,
+    }
+}
@@ -4,11 +4,16 @@ use std::ptr;
 
 use tokenizers::tokenizer::EncodeInput;
 
+use crate::batch::{batch_inputs, encode_batch, BatchError};
 use crate::encoding::{CEncoding, CEncodingNumericDest};
 use crate::error::{clear_error, store_error};
+use crate::offsets::{OffsetConverter, OffsetMode};
 use crate::tokenizer::CTokenizer;
 
-use super::utils::{copy_slice, read_optional_utf8, read_required_utf8, set_length, set_status};
+use super::utils::{
+    copy_slice, read_optional_utf16, read_optional_utf8, read_optional_utf8_array,
+    read_required_utf16, read_required_utf8, read_utf8_array, set_length, set_status,
+};
 
 /// # Safety
 /// `tokenizer`, `sequence`, `pair`, `length`, and `status` must be valid pointers, and string inputs must be UTF-8 encoded.
@@ -45,14 +50,14 @@ pub unsafe extern "C" fn tokenizers_encode(
         }
     };
 
-    let tokenizer = unsafe { &mut *tokenizer };
+    let tokenizer = unsafe { &*tokenizer };
     let input = match pair_text.as_ref() {
         Some(pair_value) => EncodeInput::from((primary.as_str(), pair_value.as_str())),
         None => EncodeInput::from(primary.as_str()),
     };
 
     match tokenizer
-        .inner_mut()
+        .inner()
         .encode_char_offsets(input, add_special_tokens)
     {
         Ok(encoding) => {
@@ -70,6 +75,315 @@ pub unsafe extern "C" fn tokenizers_encode(
     }
 }
 
+fn encode_with_offset_mode(
+    tokenizer: &CTokenizer,
+    primary: &str,
+    pair: Option<&str>,
+    add_special_tokens: bool,
+    mode: OffsetMode,
+) -> tokenizers::Result<CEncoding> {
+    let input = match pair {
+        Some(pair_value) => EncodeInput::from((primary, pair_value)),
+        None => EncodeInput::from(primary),
+    };
+
+    let encoding = tokenizer.inner().encode(input, add_special_tokens)?;
+    let mut converters = vec![OffsetConverter::new(primary, mode)];
+    if let Some(pair_value) = pair {
+        converters.push(OffsetConverter::new(pair_value, mode));
+    }
+
+    Ok(CEncoding::from_encoding_with_offsets(encoding, &converters))
+}
+
+/// Encodes UTF-8 input and reports offsets in `offset_mode` units: `0` bytes, `1` chars, or `2` UTF-16 code units.
+///
+/// # Safety
+/// `tokenizer`, `sequence`, `pair`, `length`, and `status` must be valid pointers, and string inputs must be UTF-8 encoded.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_encode_with_offsets(
+    tokenizer: *const CTokenizer,
+    sequence: *const c_char,
+    pair: *const c_char,
+    add_special_tokens: bool,
+    offset_mode: c_int,
+    length: *mut usize,
+    status: *mut c_int,
+) -> *mut CEncoding {
+    let Some(tokenizer) = (unsafe { tokenizer.as_ref() }) else {
+        store_error("tokenizers_encode_with_offsets received null tokenizer");
+        set_status(status, 1);
+        return ptr::null_mut();
+    };
+
+    let mode = match OffsetMode::from_raw(offset_mode) {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(&format!("tokenizers_encode_with_offsets: {message}"));
+            set_status(status, 2);
+            return ptr::null_mut();
+        }
+    };
+
+    let primary = match read_required_utf8(sequence) {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 3);
+            return ptr::null_mut();
+        }
+    };
+
+    let pair_text = match read_optional_utf8(pair) {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 4);
+            return ptr::null_mut();
+        }
+    };
+
+    match encode_with_offset_mode(
+        tokenizer,
+        &primary,
+        pair_text.as_deref(),
+        add_special_tokens,
+        mode,
+    ) {
+        Ok(managed) => {
+            set_length(length, managed.len());
+            clear_error();
+            set_status(status, 0);
+            Box::into_raw(Box::new(managed))
+        }
+        Err(err) => {
+            store_error(&format!("tokenizers_encode_with_offsets failed: {err}"));
+            set_status(status, 5);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Encodes UTF-16 input (as held by .NET strings) and reports offsets in `offset_mode` units.
+///
+/// # Safety
+/// `sequence` must reference `sequence_length` UTF-16 code units, `pair` must be null or reference `pair_length` code units, and `length` and `status` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_encode_utf16(
+    tokenizer: *const CTokenizer,
+    sequence: *const u16,
+    sequence_length: usize,
+    pair: *const u16,
+    pair_length: usize,
+    add_special_tokens: bool,
+    offset_mode: c_int,
+    length: *mut usize,
+    status: *mut c_int,
+) -> *mut CEncoding {
+    let Some(tokenizer) = (unsafe { tokenizer.as_ref() }) else {
+        store_error("tokenizers_encode_utf16 received null tokenizer");
+        set_status(status, 1);
+        return ptr::null_mut();
+    };
+
+    let mode = match OffsetMode::from_raw(offset_mode) {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(&format!("tokenizers_encode_utf16: {message}"));
+            set_status(status, 2);
+            return ptr::null_mut();
+        }
+    };
+
+    let primary = match read_required_utf16(sequence, sequence_length) {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 3);
+            return ptr::null_mut();
+        }
+    };
+
+    let pair_text = match read_optional_utf16(pair, pair_length) {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 4);
+            return ptr::null_mut();
+        }
+    };
+
+    match encode_with_offset_mode(
+        tokenizer,
+        &primary,
+        pair_text.as_deref(),
+        add_special_tokens,
+        mode,
+    ) {
+        Ok(managed) => {
+            set_length(length, managed.len());
+            clear_error();
+            set_status(status, 0);
+            Box::into_raw(Box::new(managed))
+        }
+        Err(err) => {
+            store_error(&format!("tokenizers_encode_utf16 failed: {err}"));
+            set_status(status, 5);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Encodes already-split words (`is_split_into_words`), so `word_ids` index back into `words` and `pair_words`.
+///
+/// # Safety
+/// `words` must hold `word_count` UTF-8 pointers, `pair_words` must be null or hold `pair_word_count` UTF-8 pointers, and `length` and `status` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_encode_pretokenized(
+    tokenizer: *const CTokenizer,
+    words: *const *const c_char,
+    word_count: usize,
+    pair_words: *const *const c_char,
+    pair_word_count: usize,
+    add_special_tokens: bool,
+    length: *mut usize,
+    status: *mut c_int,
+) -> *mut CEncoding {
+    let Some(tokenizer) = (unsafe { tokenizer.as_ref() }) else {
+        store_error("tokenizers_encode_pretokenized received null tokenizer");
+        set_status(status, 1);
+        return ptr::null_mut();
+    };
+
+    let primary = match read_utf8_array(words, word_count) {
+        Ok(values) => values,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 2);
+            return ptr::null_mut();
+        }
+    };
+
+    let pair_values = if pair_words.is_null() {
+        None
+    } else {
+        match read_utf8_array(pair_words, pair_word_count) {
+            Ok(values) => Some(values),
+            Err(message) => {
+                store_error(message);
+                set_status(status, 3);
+                return ptr::null_mut();
+            }
+        }
+    };
+
+    let primary_words: Vec<&str> = primary.iter().map(String::as_str).collect();
+    let input = match pair_values.as_ref() {
+        Some(values) => {
+            let pair_words: Vec<&str> = values.iter().map(String::as_str).collect();
+            EncodeInput::from((primary_words, pair_words))
+        }
+        None => EncodeInput::from(primary_words),
+    };
+
+    match tokenizer
+        .inner()
+        .encode_char_offsets(input, add_special_tokens)
+    {
+        Ok(encoding) => {
+            let managed = CEncoding::from_encoding(encoding);
+            set_length(length, managed.len());
+            clear_error();
+            set_status(status, 0);
+            Box::into_raw(Box::new(managed))
+        }
+        Err(err) => {
+            store_error(&format!("tokenizers_encode_pretokenized failed: {err}"));
+            set_status(status, 4);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Encodes `count` sequences in one call; `max_threads` is `0` for the shared pool, `1` for sequential, or the size of a dedicated pool kept for later calls.
+///
+/// # Safety
+/// `sequences` must hold `count` UTF-8 pointers, `pairs` must be null or hold `count` nullable UTF-8 pointers, and `output` and `lengths` (when non-null) must have room for `count` elements.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_encode_batch(
+    tokenizer: *const CTokenizer,
+    sequences: *const *const c_char,
+    pairs: *const *const c_char,
+    count: usize,
+    add_special_tokens: bool,
+    max_threads: usize,
+    output: *mut *mut CEncoding,
+    lengths: *mut usize,
+    status: *mut c_int,
+) -> c_int {
+    let Some(tokenizer) = (unsafe { tokenizer.as_ref() }) else {
+        store_error("tokenizers_encode_batch received null tokenizer");
+        set_status(status, 1);
+        return 0;
+    };
+
+    if count > 0 && output.is_null() {
+        store_error("tokenizers_encode_batch received null output buffer");
+        set_status(status, 2);
+        return 0;
+    }
+
+    let primary = match read_utf8_array(sequences, count) {
+        Ok(values) => values,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 3);
+            return 0;
+        }
+    };
+
+    let pair_texts = match read_optional_utf8_array(pairs, count) {
+        Ok(values) => values,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 4);
+            return 0;
+        }
+    };
+
+    let inputs = batch_inputs(&primary, pair_texts.as_deref());
+
+    let encodings = match encode_batch(tokenizer.inner(), inputs, add_special_tokens, max_threads) {
+        Ok(values) => values,
+        Err(err) => {
+            let code = match err {
+                BatchError::ThreadPool(_) => 5,
+                BatchError::Encode(_) => 6,
+            };
+            store_error(&format!("tokenizers_encode_batch: {}", err.into_message()));
+            set_status(status, code);
+            return 0;
+        }
+    };
+
+    for (index, encoding) in encodings.into_iter().enumerate() {
+        let managed = CEncoding::from_encoding(encoding);
+        if !lengths.is_null() {
+            unsafe {
+                *lengths.add(index) = managed.len();
+            }
+        }
+
+        unsafe {
+            *output.add(index) = Box::into_raw(Box::new(managed));
+        }
+    }
+
+    clear_error();
+    set_status(status, 0);
+    count as c_int
+}
+
 /// # Safety
 /// `encoding` must be null or a pointer previously returned by this module and not freed by the caller.
 #[no_mangle]
@@ -0,0 +1,162 @@
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+
+use tokenizers::utils::padding::PaddingDirection;
+use tokenizers::utils::truncation::TruncationDirection;
+use tokenizers::Encoding;
+
+use crate::encoding::CEncoding;
+use crate::error::{clear_error, store_error};
+
+use super::utils::{read_optional_utf8, set_length, set_status};
+
+fn into_handle(managed: CEncoding, length: *mut usize, status: *mut c_int) -> *mut CEncoding {
+    set_length(length, managed.len());
+    clear_error();
+    set_status(status, 0);
+    Box::into_raw(Box::new(managed))
+}
+
+/// Concatenates `count` encodings into a new handle, as `Encoding::merge` does; with `growing_offsets`
+/// each encoding's offsets continue from the end of the previous one.
+///
+/// # Safety
+/// `encodings` must hold `count` valid encoding handles and `length` and `status` must be writable; the inputs are left untouched.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_encoding_merge(
+    encodings: *const *const CEncoding,
+    count: usize,
+    growing_offsets: bool,
+    length: *mut usize,
+    status: *mut c_int,
+) -> *mut CEncoding {
+    if count > 0 && encodings.is_null() {
+        store_error("tokenizers_encoding_merge received null encodings array");
+        set_status(status, 1);
+        return ptr::null_mut();
+    }
+
+    let handles = if count == 0 {
+        &[][..]
+    } else {
+        unsafe { std::slice::from_raw_parts(encodings, count) }
+    };
+
+    let mut parts = Vec::with_capacity(count);
+    for handle in handles {
+        match unsafe { handle.as_ref() } {
+            Some(encoding) => parts.push(encoding),
+            None => {
+                store_error("tokenizers_encoding_merge received null encoding");
+                set_status(status, 2);
+                return ptr::null_mut();
+            }
+        }
+    }
+
+    let merged = CEncoding::apply(&parts, |parts| Encoding::merge(parts, growing_offsets));
+    into_handle(merged, length, status)
+}
+
+/// Pads a copy of `encoding` (and its overflowing entries) to `target_length`.
+///
+/// # Safety
+/// `encoding` must be a valid handle, `pad_token` must be null or UTF-8, and `length` and `status` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_encoding_pad(
+    encoding: *const CEncoding,
+    target_length: usize,
+    pad_id: u32,
+    pad_type_id: u32,
+    pad_token: *const c_char,
+    direction: c_int,
+    length: *mut usize,
+    status: *mut c_int,
+) -> *mut CEncoding {
+    let Some(encoding) = (unsafe { encoding.as_ref() }) else {
+        store_error("tokenizers_encoding_pad received null encoding");
+        set_status(status, 1);
+        return ptr::null_mut();
+    };
+
+    let direction = match direction {
+        0 => PaddingDirection::Left,
+        1 => PaddingDirection::Right,
+        other => {
+            store_error(&format!(
+                "tokenizers_encoding_pad unknown direction: {other}"
+            ));
+            set_status(status, 2);
+            return ptr::null_mut();
+        }
+    };
+
+    let pad_token_value = match read_optional_utf8(pad_token) {
+        Ok(Some(value)) => value,
+        Ok(None) => String::from("[PAD]"),
+        Err(message) => {
+            store_error(message);
+            set_status(status, 3);
+            return ptr::null_mut();
+        }
+    };
+
+    let padded = CEncoding::apply(&[encoding], |parts| {
+        let mut padded = parts.into_iter().next().unwrap_or_default();
+        padded.pad(
+            target_length,
+            pad_id,
+            pad_type_id,
+            &pad_token_value,
+            direction,
+        );
+        padded
+    });
+    into_handle(padded, length, status)
+}
+
+/// Truncates a copy of `encoding` to `max_length`, moving the remainder into overflowing entries
+/// that overlap by `stride` tokens.
+///
+/// # Safety
+/// `encoding` must be a valid handle and `length` and `status` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_encoding_truncate(
+    encoding: *const CEncoding,
+    max_length: usize,
+    stride: usize,
+    direction: c_int,
+    length: *mut usize,
+    status: *mut c_int,
+) -> *mut CEncoding {
+    let Some(encoding) = (unsafe { encoding.as_ref() }) else {
+        store_error("tokenizers_encoding_truncate received null encoding");
+        set_status(status, 1);
+        return ptr::null_mut();
+    };
+
+    let direction = match direction {
+        0 => TruncationDirection::Left,
+        1 => TruncationDirection::Right,
+        other => {
+            store_error(&format!(
+                "tokenizers_encoding_truncate unknown direction: {other}"
+            ));
+            set_status(status, 2);
+            return ptr::null_mut();
+        }
+    };
+
+    if max_length > 0 && max_length < encoding.len() && stride >= max_length {
+        store_error("tokenizers_encoding_truncate requires stride to be smaller than max_length");
+        set_status(status, 3);
+        return ptr::null_mut();
+    }
+
+    let truncated = CEncoding::apply(&[encoding], |parts| {
+        let mut truncated = parts.into_iter().next().unwrap_or_default();
+        truncated.truncate(max_length, stride, direction);
+        truncated
+    });
+    into_handle(truncated, length, status)
+}
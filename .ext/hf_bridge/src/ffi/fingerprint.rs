@@ -0,0 +1,54 @@
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+
+use crate::error::{clear_error, store_error};
+use crate::fingerprint::fingerprint;
+use crate::tokenizer::CTokenizer;
+
+use super::utils::set_status;
+
+/// Returns `{version, tokenizer, components, configuration}`: SHA-256 digests of the tokenizer's
+/// canonical model, normalizer, pre-tokenizer, post-processor, decoder and added tokens, both
+/// combined and per component, plus a separate digest of the padding and truncation settings.
+/// Equal `tokenizer` digests mean the same text encodes to the same ids.
+///
+/// # Safety
+/// `tokenizer` must be a valid pointer and `status` must be null or writable; the caller frees the
+/// result with `tokenizers_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_fingerprint(
+    tokenizer: *const CTokenizer,
+    status: *mut c_int,
+) -> *mut c_char {
+    let Some(tokenizer) = (unsafe { tokenizer.as_ref() }) else {
+        store_error("tokenizers_fingerprint received null tokenizer");
+        set_status(status, 1);
+        return ptr::null_mut();
+    };
+
+    let fingerprint = match fingerprint(tokenizer.inner()) {
+        Ok(value) => value,
+        Err(err) => {
+            store_error(&format!(
+                "tokenizers_fingerprint failed: {}",
+                err.into_message()
+            ));
+            set_status(status, 2);
+            return ptr::null_mut();
+        }
+    };
+
+    match serde_json::to_string(&fingerprint).map(CString::new) {
+        Ok(Ok(json)) => {
+            clear_error();
+            set_status(status, 0);
+            json.into_raw()
+        }
+        _ => {
+            store_error("tokenizers_fingerprint failed to serialize fingerprint");
+            set_status(status, 3);
+            ptr::null_mut()
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use std::os::raw::{c_char, c_int};
+use std::path::PathBuf;
+use std::ptr;
+
+use crate::error::{clear_error, store_error};
+use crate::hub_cache::{load_from_cache, HubCache, HubCacheError};
+use crate::tokenizer::CTokenizer;
+
+use super::directory::write_metadata;
+use super::utils::{read_optional_utf8, read_required_utf8, set_status};
+
+/// Loads a tokenizer and its configuration files from the local Hugging Face hub cache without
+/// touching the network, as `tokenizers_load_directory` does for the resolved snapshot.
+///
+/// `revision` may be a branch, tag, `refs/pr/N` or commit hash and defaults to `main`; one with
+/// empty, `.` or `..` components fails with status `2`. A snapshot without tokenizer.json or a
+/// complete set of legacy vocabulary files fails with status `4`, naming the absent file.
+/// `cache_dir` overrides the cache root otherwise taken from `HF_HUB_CACHE` or `HF_HOME`. When
+/// `metadata` is non-null it receives the resolved settings as JSON, to be released with
+/// `tokenizers_free_string`.
+///
+/// # Safety
+/// `identifier` must be a null-terminated UTF-8 string, `revision` and `cache_dir` must be null or
+/// UTF-8, and `metadata` and `status` must be null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_from_cache(
+    identifier: *const c_char,
+    revision: *const c_char,
+    cache_dir: *const c_char,
+    apply_defaults: bool,
+    metadata: *mut *mut c_char,
+    status: *mut c_int,
+) -> *mut CTokenizer {
+    if !metadata.is_null() {
+        unsafe { *metadata = ptr::null_mut() };
+    }
+
+    let name = match read_required_utf8(identifier) {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 1);
+            return ptr::null_mut();
+        }
+    };
+
+    let options = read_optional_utf8(revision)
+        .and_then(|revision| Ok((revision, read_optional_utf8(cache_dir)?)));
+    let (revision, cache_dir) = match options {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 2);
+            return ptr::null_mut();
+        }
+    };
+
+    let cache = cache_dir
+        .map(|root| HubCache::new(PathBuf::from(root)))
+        .unwrap_or_else(HubCache::from_env);
+    let loaded = match load_from_cache(&cache, &name, revision.as_deref(), apply_defaults) {
+        Ok(value) => value,
+        Err(err) => {
+            let code = match err {
+                HubCacheError::InvalidRevision(_) => 2,
+                HubCacheError::NotCached(_) => 3,
+                HubCacheError::MissingFiles { .. } => 4,
+                HubCacheError::Directory(_) => 5,
+            };
+            store_error(&format!(
+                "tokenizers_from_cache failed: {}",
+                err.into_message()
+            ));
+            set_status(status, code);
+            return ptr::null_mut();
+        }
+    };
+
+    if !write_metadata(&loaded.metadata, metadata) {
+        store_error("tokenizers_from_cache failed to serialize metadata");
+        set_status(status, 6);
+        return ptr::null_mut();
+    }
+
+    clear_error();
+    set_status(status, 0);
+    Box::into_raw(Box::new(CTokenizer::new(loaded.tokenizer)))
+}
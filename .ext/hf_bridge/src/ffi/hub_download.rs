@@ -0,0 +1,108 @@
+use std::os::raw::{c_char, c_int};
+use std::path::PathBuf;
+use std::ptr;
+use std::time::Duration;
+
+use crate::error::{clear_error, store_error};
+use crate::hub_cache::HubCacheError;
+use crate::hub_download::{fetch_tokenizer, CHubOptions, DownloadError, HubOptions};
+use crate::tokenizer::CTokenizer;
+
+use super::directory::write_metadata;
+use super::utils::{read_optional_utf8, read_required_utf8, set_status};
+
+/// Reads the caller's options; a null `options` keeps every default and skips `apply_defaults`.
+fn read_options(options: *const CHubOptions) -> Result<(HubOptions, bool), &'static str> {
+    if options.is_null() {
+        return Ok((HubOptions::default(), false));
+    }
+
+    let options = unsafe { &*options };
+    let hub_options = HubOptions {
+        endpoint: read_optional_utf8(options.endpoint)?,
+        cache_dir: read_optional_utf8(options.cache_dir)?.map(PathBuf::from),
+        revision: read_optional_utf8(options.revision)?,
+        auth_token: read_optional_utf8(options.auth_token)?,
+        user_agent: read_optional_utf8(options.user_agent)?,
+        timeout: (options.timeout_ms > 0).then(|| Duration::from_millis(options.timeout_ms)),
+        local_files_only: options.local_files_only,
+        force_download: options.force_download,
+    };
+    Ok((hub_options, options.apply_defaults))
+}
+
+/// Downloads tokenizer.json and its companion configuration files (tokenizer_config.json,
+/// special_tokens_map.json, added_tokens.json, generation_config.json, chat_template.jinja) into
+/// the hub cache and loads them as `tokenizers_load_directory` does.
+///
+/// `options` selects the hub endpoint, cache directory, revision, token, user agent and timeout,
+/// and whether to read only the cache (`local_files_only`) or re-download cached files
+/// (`force_download`). When `metadata` is non-null it receives the resolved settings as JSON, to be
+/// released with `tokenizers_free_string`. A revision with empty, `.` or `..` components fails
+/// with status `2`, and a hub response whose commit is not a hash fails with status `5`.
+///
+/// # Safety
+/// `identifier` must be a null-terminated UTF-8 string, `options` must be null or point to a valid
+/// `CHubOptions` whose strings are null or UTF-8, and `metadata` and `status` must be null or
+/// writable.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_from_pretrained_with_options(
+    identifier: *const c_char,
+    options: *const CHubOptions,
+    metadata: *mut *mut c_char,
+    status: *mut c_int,
+) -> *mut CTokenizer {
+    if !metadata.is_null() {
+        unsafe { *metadata = ptr::null_mut() };
+    }
+
+    let name = match read_required_utf8(identifier) {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 1);
+            return ptr::null_mut();
+        }
+    };
+
+    let (options, apply_defaults) = match read_options(options) {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 2);
+            return ptr::null_mut();
+        }
+    };
+
+    let loaded = match fetch_tokenizer(&name, &options, apply_defaults) {
+        Ok(value) => value,
+        Err(err) => {
+            let code = match err {
+                DownloadError::Cache(HubCacheError::InvalidRevision(_)) => 2,
+                DownloadError::Cache(HubCacheError::Directory(_)) => 7,
+                DownloadError::Cache(_) => 3,
+                DownloadError::Http { .. } => 4,
+                DownloadError::Transport { .. } => 5,
+                #[cfg(any(target_family = "wasm", target_os = "ios", target_os = "android"))]
+                DownloadError::Unsupported => 5,
+                DownloadError::Io(_) => 6,
+            };
+            store_error(&format!(
+                "tokenizers_from_pretrained_with_options failed: {}",
+                err.into_message()
+            ));
+            set_status(status, code);
+            return ptr::null_mut();
+        }
+    };
+
+    if !write_metadata(&loaded.metadata, metadata) {
+        store_error("tokenizers_from_pretrained_with_options failed to serialize metadata");
+        set_status(status, 8);
+        return ptr::null_mut();
+    }
+
+    clear_error();
+    set_status(status, 0);
+    Box::into_raw(Box::new(CTokenizer::new(loaded.tokenizer)))
+}
@@ -0,0 +1,330 @@
+use std::os::raw::{c_char, c_int};
+
+use tokenizers::tokenizer::EncodeInput;
+use tokenizers::{Encoding, Tokenizer};
+
+use crate::batch::{attended_length, batch_inputs, encode_batch_fast, BatchError};
+use crate::error::{clear_error, store_error};
+use crate::tokenizer::CTokenizer;
+
+use super::utils::{
+    copy_slice, read_optional_utf8, read_optional_utf8_array, read_required_utf8, read_utf8_array,
+    set_length, set_status,
+};
+
+fn encode_fast(
+    tokenizer: &Tokenizer,
+    primary: &str,
+    pair: Option<&str>,
+    add_special_tokens: bool,
+) -> tokenizers::Result<Encoding> {
+    let input = match pair {
+        Some(pair_value) => EncodeInput::from((primary, pair_value)),
+        None => EncodeInput::from(primary),
+    };
+
+    tokenizer.encode_fast(input, add_special_tokens)
+}
+
+/// Counts the tokens `sequence` (and `pair`) encode to without building a `CEncoding`. Padding
+/// and truncation are ignored, so over-long input reports its full count.
+///
+/// # Safety
+/// `tokenizer` must be valid, `sequence` must be a UTF-8 string, `pair` must be null or UTF-8, and `status` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_count_tokens(
+    tokenizer: *const CTokenizer,
+    sequence: *const c_char,
+    pair: *const c_char,
+    add_special_tokens: bool,
+    status: *mut c_int,
+) -> usize {
+    let Some(tokenizer) = (unsafe { tokenizer.as_ref() }) else {
+        store_error("tokenizers_count_tokens received null tokenizer");
+        set_status(status, 1);
+        return 0;
+    };
+
+    let primary = match read_required_utf8(sequence) {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 2);
+            return 0;
+        }
+    };
+
+    let pair_text = match read_optional_utf8(pair) {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 3);
+            return 0;
+        }
+    };
+
+    match encode_fast(
+        tokenizer.unconstrained(),
+        &primary,
+        pair_text.as_deref(),
+        add_special_tokens,
+    ) {
+        Ok(encoding) => {
+            clear_error();
+            set_status(status, 0);
+            attended_length(&encoding)
+        }
+        Err(err) => {
+            store_error(&format!("tokenizers_count_tokens failed: {err}"));
+            set_status(status, 4);
+            0
+        }
+    }
+}
+
+/// Batch form of `tokenizers_count_tokens`; padding and truncation are ignored as well.
+///
+/// # Safety
+/// `sequences` must hold `count` UTF-8 pointers, `pairs` must be null or hold `count` nullable UTF-8 pointers, and `counts` must have room for `count` elements.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_count_tokens_batch(
+    tokenizer: *const CTokenizer,
+    sequences: *const *const c_char,
+    pairs: *const *const c_char,
+    count: usize,
+    add_special_tokens: bool,
+    max_threads: usize,
+    counts: *mut usize,
+    status: *mut c_int,
+) -> c_int {
+    let Some(tokenizer) = (unsafe { tokenizer.as_ref() }) else {
+        store_error("tokenizers_count_tokens_batch received null tokenizer");
+        set_status(status, 1);
+        return 0;
+    };
+
+    if count > 0 && counts.is_null() {
+        store_error("tokenizers_count_tokens_batch received null counts buffer");
+        set_status(status, 2);
+        return 0;
+    }
+
+    let primary = match read_utf8_array(sequences, count) {
+        Ok(values) => values,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 3);
+            return 0;
+        }
+    };
+
+    let pair_texts = match read_optional_utf8_array(pairs, count) {
+        Ok(values) => values,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 4);
+            return 0;
+        }
+    };
+
+    let inputs = batch_inputs(&primary, pair_texts.as_deref());
+    match encode_batch_fast(
+        tokenizer.unconstrained(),
+        inputs,
+        add_special_tokens,
+        max_threads,
+    ) {
+        Ok(encodings) => {
+            let values: Vec<usize> = encodings.iter().map(attended_length).collect();
+            copy_slice(&values, counts, count);
+            clear_error();
+            set_status(status, 0);
+            count as c_int
+        }
+        Err(err) => {
+            let code = match err {
+                BatchError::ThreadPool(_) => 5,
+                BatchError::Encode(_) => 6,
+            };
+            store_error(&format!(
+                "tokenizers_count_tokens_batch: {}",
+                err.into_message()
+            ));
+            set_status(status, code);
+            0
+        }
+    }
+}
+
+/// Encodes into caller buffers holding only ids and, when non-null, the attention mask.
+/// `length` always receives the required element count so an undersized call can be retried.
+///
+/// # Safety
+/// `ids` and `attention_mask` (when non-null) must hold `capacity` elements; string inputs must be UTF-8 and `length` and `status` writable.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_encode_ids(
+    tokenizer: *const CTokenizer,
+    sequence: *const c_char,
+    pair: *const c_char,
+    add_special_tokens: bool,
+    ids: *mut u32,
+    attention_mask: *mut u32,
+    capacity: usize,
+    length: *mut usize,
+    status: *mut c_int,
+) -> c_int {
+    let Some(tokenizer) = (unsafe { tokenizer.as_ref() }) else {
+        store_error("tokenizers_encode_ids received null tokenizer");
+        set_status(status, 1);
+        return 0;
+    };
+
+    if ids.is_null() {
+        store_error("tokenizers_encode_ids received null ids buffer");
+        set_status(status, 2);
+        return 0;
+    }
+
+    let primary = match read_required_utf8(sequence) {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 3);
+            return 0;
+        }
+    };
+
+    let pair_text = match read_optional_utf8(pair) {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 4);
+            return 0;
+        }
+    };
+
+    let encoding = match encode_fast(
+        tokenizer.inner(),
+        &primary,
+        pair_text.as_deref(),
+        add_special_tokens,
+    ) {
+        Ok(value) => value,
+        Err(err) => {
+            store_error(&format!("tokenizers_encode_ids failed: {err}"));
+            set_status(status, 5);
+            return 0;
+        }
+    };
+
+    let required = encoding.len();
+    set_length(length, required);
+
+    if required > capacity {
+        store_error("tokenizers_encode_ids received insufficient destination capacity");
+        set_status(status, 6);
+        return 0;
+    }
+
+    copy_slice(encoding.get_ids(), ids, required);
+    copy_slice(encoding.get_attention_mask(), attention_mask, required);
+    clear_error();
+    set_status(status, 0);
+    required as c_int
+}
+
+/// Encodes a batch into flat, back-to-back id (and optional attention mask) buffers with per-row `lengths`.
+/// `lengths` is filled even when `capacity` is too small so the caller can size a retry.
+///
+/// # Safety
+/// `sequences` must hold `count` UTF-8 pointers, `pairs` must be null or hold `count` nullable UTF-8 pointers, `lengths` must have room for `count` elements, and `ids` and `attention_mask` (when non-null) must hold `capacity` elements.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_encode_ids_batch(
+    tokenizer: *const CTokenizer,
+    sequences: *const *const c_char,
+    pairs: *const *const c_char,
+    count: usize,
+    add_special_tokens: bool,
+    max_threads: usize,
+    ids: *mut u32,
+    attention_mask: *mut u32,
+    capacity: usize,
+    lengths: *mut usize,
+    status: *mut c_int,
+) -> c_int {
+    let Some(tokenizer) = (unsafe { tokenizer.as_ref() }) else {
+        store_error("tokenizers_encode_ids_batch received null tokenizer");
+        set_status(status, 1);
+        return 0;
+    };
+
+    if count > 0 && (ids.is_null() || lengths.is_null()) {
+        store_error("tokenizers_encode_ids_batch received null buffer");
+        set_status(status, 2);
+        return 0;
+    }
+
+    let primary = match read_utf8_array(sequences, count) {
+        Ok(values) => values,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 3);
+            return 0;
+        }
+    };
+
+    let pair_texts = match read_optional_utf8_array(pairs, count) {
+        Ok(values) => values,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 4);
+            return 0;
+        }
+    };
+
+    let inputs = batch_inputs(&primary, pair_texts.as_deref());
+    let encodings =
+        match encode_batch_fast(tokenizer.inner(), inputs, add_special_tokens, max_threads) {
+            Ok(values) => values,
+            Err(err) => {
+                let code = match err {
+                    BatchError::ThreadPool(_) => 5,
+                    BatchError::Encode(_) => 6,
+                };
+                store_error(&format!(
+                    "tokenizers_encode_ids_batch: {}",
+                    err.into_message()
+                ));
+                set_status(status, code);
+                return 0;
+            }
+        };
+
+    let row_lengths: Vec<usize> = encodings.iter().map(Encoding::len).collect();
+    copy_slice(&row_lengths, lengths, count);
+
+    let required: usize = row_lengths.iter().sum();
+    if required > capacity {
+        store_error("tokenizers_encode_ids_batch received insufficient destination capacity");
+        set_status(status, 7);
+        return 0;
+    }
+
+    let mut offset = 0usize;
+    for encoding in encodings.iter() {
+        let row_length = encoding.len();
+        copy_slice(encoding.get_ids(), unsafe { ids.add(offset) }, row_length);
+        if !attention_mask.is_null() {
+            copy_slice(
+                encoding.get_attention_mask(),
+                unsafe { attention_mask.add(offset) },
+                row_length,
+            );
+        }
+        offset += row_length;
+    }
+
+    clear_error();
+    set_status(status, 0);
+    count as c_int
+}
@@ -2,10 +2,12 @@ use std::ffi::CString;
 use std::os::raw::{c_char, c_int};
 use std::ptr;
 
+use crate::directory::TOKENIZER_FILE;
 use crate::error::{clear_error, store_error};
+use crate::ffi::utils::{read_optional_utf8, read_required_utf8, set_status};
 #[cfg(not(any(target_family = "wasm", target_os = "ios", target_os = "android")))]
-use crate::ffi::utils::read_optional_utf8;
-use crate::ffi::utils::{read_required_utf8, set_status};
+use crate::hub_cache::offline_mode;
+use crate::hub_cache::{HubCache, HubCacheError};
 use crate::tokenizer::CTokenizer;
 
 #[cfg(not(any(target_family = "wasm", target_os = "ios", target_os = "android")))]
@@ -41,6 +43,8 @@ pub unsafe extern "C" fn tokenizers_create(
 }
 
 #[cfg(not(any(target_family = "wasm", target_os = "ios", target_os = "android")))]
+/// With `HF_HUB_OFFLINE` set, tokenizer.json is read from the local hub cache instead of the network.
+///
 /// # Safety
 /// All pointer arguments must be valid and reference UTF-8 strings when non-null; `status` must be writable.
 #[no_mangle]
@@ -77,6 +81,10 @@ pub unsafe extern "C" fn tokenizers_from_pretrained(
         }
     };
 
+    if offline_mode() {
+        return from_hub_cache(&name, revision_value.as_deref(), status);
+    }
+
     let mut params = FromPretrainedParameters::default();
     if let Some(revision_text) = revision_value {
         if !revision_text.is_empty() {
@@ -100,18 +108,60 @@ pub unsafe extern "C" fn tokenizers_from_pretrained(
 }
 
 #[cfg(any(target_family = "wasm", target_os = "ios", target_os = "android"))]
+/// Without the `http` feature only the local hub cache is consulted.
+///
 /// # Safety
-/// `status` must be a writable pointer supplied by the caller.
+/// `identifier` and `revision` must be null or UTF-8 strings and `status` must be writable.
 #[no_mangle]
 pub unsafe extern "C" fn tokenizers_from_pretrained(
-    _identifier: *const c_char,
-    _revision: *const c_char,
+    identifier: *const c_char,
+    revision: *const c_char,
     _auth_token: *const c_char,
     status: *mut c_int,
 ) -> *mut CTokenizer {
-    store_error("tokenizers_from_pretrained requires the 'http' feature");
-    set_status(status, 1);
-    ptr::null_mut()
+    let name = match read_required_utf8(identifier) {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 1);
+            return ptr::null_mut();
+        }
+    };
+
+    let revision_value = match read_optional_utf8(revision) {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 2);
+            return ptr::null_mut();
+        }
+    };
+
+    from_hub_cache(&name, revision_value.as_deref(), status)
+}
+
+/// Loads `tokenizer.json` from the cached snapshot of `revision`, as the online path would have
+/// downloaded it; failures report status `5`.
+fn from_hub_cache(name: &str, revision: Option<&str>, status: *mut c_int) -> *mut CTokenizer {
+    let loaded = HubCache::from_env()
+        .file(name, revision, TOKENIZER_FILE)
+        .map_err(HubCacheError::into_message)
+        .and_then(|path| Tokenizer::from_file(path).map_err(|err| err.to_string()));
+
+    match loaded {
+        Ok(tokenizer) => {
+            clear_error();
+            set_status(status, 0);
+            Box::into_raw(Box::new(CTokenizer::new(tokenizer)))
+        }
+        Err(message) => {
+            store_error(&format!(
+                "tokenizers_from_pretrained failed offline: {message}"
+            ));
+            set_status(status, 5);
+            ptr::null_mut()
+        }
+    }
 }
 
 /// # Safety
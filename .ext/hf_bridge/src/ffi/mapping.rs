@@ -0,0 +1,262 @@
+use std::os::raw::c_int;
+
+use crate::encoding::{CEncoding, CEncodingOffset};
+use crate::error::{clear_error, store_error};
+
+use super::utils::{set_length, set_status};
+
+// Status codes shared by the lookups below: 0 found, 1 null encoding, 2 index out of range,
+// 3 no mapping (special or padding token, or a position outside every token).
+
+fn resolve_token<'a>(
+    encoding: *const CEncoding,
+    token: usize,
+    operation: &str,
+    status: *mut c_int,
+) -> Option<&'a CEncoding> {
+    let Some(encoding) = (unsafe { encoding.as_ref() }) else {
+        store_error(&format!("{operation} received null encoding"));
+        set_status(status, 1);
+        return None;
+    };
+
+    if token >= encoding.len() {
+        store_error(&format!("{operation} token index out of range"));
+        set_status(status, 2);
+        return None;
+    }
+
+    Some(encoding)
+}
+
+fn report_missing(operation: &str, status: *mut c_int) {
+    store_error(&format!("{operation}: no mapping found"));
+    set_status(status, 3);
+}
+
+/// Returns the index of the token of `sequence_index` covering `position`, or `-1`. Positions use the encoding's offset unit.
+///
+/// # Safety
+/// `encoding` must be null or a valid encoding handle (including overflow handles) and `status` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_encoding_char_to_token(
+    encoding: *const CEncoding,
+    position: u32,
+    sequence_index: usize,
+    status: *mut c_int,
+) -> c_int {
+    let Some(encoding) = (unsafe { encoding.as_ref() }) else {
+        store_error("tokenizers_encoding_char_to_token received null encoding");
+        set_status(status, 1);
+        return -1;
+    };
+
+    match encoding.char_to_token(position, sequence_index) {
+        Some(token) => {
+            clear_error();
+            set_status(status, 0);
+            token as c_int
+        }
+        None => {
+            report_missing("tokenizers_encoding_char_to_token", status);
+            -1
+        }
+    }
+}
+
+/// Returns the word of `sequence_index` covering `position`, or `-1`.
+///
+/// # Safety
+/// `encoding` must be null or a valid encoding handle and `status` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_encoding_char_to_word(
+    encoding: *const CEncoding,
+    position: u32,
+    sequence_index: usize,
+    status: *mut c_int,
+) -> c_int {
+    let Some(encoding) = (unsafe { encoding.as_ref() }) else {
+        store_error("tokenizers_encoding_char_to_word received null encoding");
+        set_status(status, 1);
+        return -1;
+    };
+
+    match encoding.char_to_word(position, sequence_index) {
+        Some(word) => {
+            clear_error();
+            set_status(status, 0);
+            word as c_int
+        }
+        None => {
+            report_missing("tokenizers_encoding_char_to_word", status);
+            -1
+        }
+    }
+}
+
+/// Writes the span of `token` and the sequence it belongs to; returns `1` on success.
+///
+/// # Safety
+/// `encoding` must be null or a valid encoding handle; `sequence_index`, `offset`, and `status` must be null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_encoding_token_to_chars(
+    encoding: *const CEncoding,
+    token: usize,
+    sequence_index: *mut usize,
+    offset: *mut CEncodingOffset,
+    status: *mut c_int,
+) -> c_int {
+    let operation = "tokenizers_encoding_token_to_chars";
+    let Some(encoding) = resolve_token(encoding, token, operation, status) else {
+        return 0;
+    };
+
+    match encoding.token_to_chars(token) {
+        Some((sequence, (start, end))) => {
+            set_length(sequence_index, sequence);
+            if !offset.is_null() {
+                unsafe {
+                    offset.write(CEncodingOffset { start, end });
+                }
+            }
+            clear_error();
+            set_status(status, 0);
+            1
+        }
+        None => {
+            report_missing(operation, status);
+            0
+        }
+    }
+}
+
+/// Returns the word containing `token` (writing its sequence to `sequence_index`), or `-1`.
+///
+/// # Safety
+/// `encoding` must be null or a valid encoding handle; `sequence_index` and `status` must be null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_encoding_token_to_word(
+    encoding: *const CEncoding,
+    token: usize,
+    sequence_index: *mut usize,
+    status: *mut c_int,
+) -> c_int {
+    let operation = "tokenizers_encoding_token_to_word";
+    let Some(encoding) = resolve_token(encoding, token, operation, status) else {
+        return -1;
+    };
+
+    match encoding.token_to_word(token) {
+        Some((sequence, word)) => {
+            set_length(sequence_index, sequence);
+            clear_error();
+            set_status(status, 0);
+            word as c_int
+        }
+        None => {
+            report_missing(operation, status);
+            -1
+        }
+    }
+}
+
+/// Returns the sequence `token` belongs to, or `-1` for special and padding tokens.
+///
+/// # Safety
+/// `encoding` must be null or a valid encoding handle and `status` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_encoding_token_to_sequence(
+    encoding: *const CEncoding,
+    token: usize,
+    status: *mut c_int,
+) -> c_int {
+    let operation = "tokenizers_encoding_token_to_sequence";
+    let Some(encoding) = resolve_token(encoding, token, operation, status) else {
+        return -1;
+    };
+
+    match encoding.token_to_sequence(token) {
+        Some(sequence) => {
+            clear_error();
+            set_status(status, 0);
+            sequence as c_int
+        }
+        None => {
+            report_missing(operation, status);
+            -1
+        }
+    }
+}
+
+/// Writes the `[start, end)` token range of `word` in `sequence_index`; returns `1` on success.
+///
+/// # Safety
+/// `encoding` must be null or a valid encoding handle; `start`, `end`, and `status` must be null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_encoding_word_to_tokens(
+    encoding: *const CEncoding,
+    word: u32,
+    sequence_index: usize,
+    start: *mut usize,
+    end: *mut usize,
+    status: *mut c_int,
+) -> c_int {
+    let Some(encoding) = (unsafe { encoding.as_ref() }) else {
+        store_error("tokenizers_encoding_word_to_tokens received null encoding");
+        set_status(status, 1);
+        return 0;
+    };
+
+    match encoding.word_to_tokens(word, sequence_index) {
+        Some((first, last)) => {
+            set_length(start, first);
+            set_length(end, last);
+            clear_error();
+            set_status(status, 0);
+            1
+        }
+        None => {
+            report_missing("tokenizers_encoding_word_to_tokens", status);
+            0
+        }
+    }
+}
+
+/// Writes the span covered by `word` in `sequence_index`; returns `1` on success.
+///
+/// # Safety
+/// `encoding` must be null or a valid encoding handle; `offset` and `status` must be null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_encoding_word_to_chars(
+    encoding: *const CEncoding,
+    word: u32,
+    sequence_index: usize,
+    offset: *mut CEncodingOffset,
+    status: *mut c_int,
+) -> c_int {
+    let Some(encoding) = (unsafe { encoding.as_ref() }) else {
+        store_error("tokenizers_encoding_word_to_chars received null encoding");
+        set_status(status, 1);
+        return 0;
+    };
+
+    match encoding.word_to_chars(word, sequence_index) {
+        Some((first, last)) => {
+            if !offset.is_null() {
+                unsafe {
+                    offset.write(CEncodingOffset {
+                        start: first,
+                        end: last,
+                    });
+                }
+            }
+            clear_error();
+            set_status(status, 0);
+            1
+        }
+        None => {
+            report_missing("tokenizers_encoding_word_to_chars", status);
+            0
+        }
+    }
+}
@@ -1,18 +1,38 @@
 pub(crate) mod utils;
 
+pub mod added_tokens;
+pub mod batch_plan;
 pub mod chat;
+pub mod chunking;
+pub mod components;
 pub mod config;
+pub mod convert;
 pub mod decode;
+pub mod decode_stream;
+pub mod directory;
 pub mod encoding;
+pub mod encoding_ops;
+pub mod fingerprint;
 pub mod generation;
+pub mod hub_cache;
+pub mod hub_download;
+pub mod lean_encoding;
 pub mod lifecycle;
+pub mod mapping;
 pub mod padding;
+pub mod stop_matcher;
+pub mod tensor;
+pub mod tiktoken;
+pub mod token_bytes;
+pub mod trace;
 pub mod truncation;
+pub mod vocab;
 
 #[cfg_attr(not(test), doc(hidden))]
 pub mod test_helpers {
     use ahash::AHashMap;
     use std::ffi::CString;
+    use std::str::FromStr;
 
     use tokenizers::models::wordlevel::WordLevel;
     use tokenizers::pre_tokenizers::whitespace::Whitespace;
@@ -37,6 +57,32 @@ pub mod test_helpers {
         CTokenizer::new(tokenizer)
     }
 
+    /// A WordLevel tokenizer with a ByteLevel decoder whose vocab splits `é` into its two byte
+    /// tokens (`Ã` = 1, `©` = 2), plus `Ġcaf` = 3, `hello` = 4 and the special `</s>` = 5.
+    pub fn create_byte_level_tokenizer() -> CTokenizer {
+        let json = r#"{
+            "version": "1.0",
+            "truncation": null,
+            "padding": null,
+            "added_tokens": [
+                {"id": 5, "content": "</s>", "single_word": false, "lstrip": false, "rstrip": false, "normalized": false, "special": true}
+            ],
+            "normalizer": null,
+            "pre_tokenizer": null,
+            "post_processor": null,
+            "decoder": {"type": "ByteLevel", "add_prefix_space": false, "trim_offsets": true, "use_regex": true},
+            "model": {
+                "type": "WordLevel",
+                "vocab": {"[UNK]": 0, "Ã": 1, "©": 2, "Ġcaf": 3, "hello": 4, "</s>": 5},
+                "unk_token": "[UNK]"
+            }
+        }"#;
+
+        let tokenizer =
+            Tokenizer::from_str(json).expect("byte-level tokenizer json should be valid");
+        CTokenizer::new(tokenizer)
+    }
+
     pub fn tokenizer_config_json() -> CString {
         let tokenizer = create_tokenizer();
         let json = tokenizer
@@ -0,0 +1,320 @@
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+
+use crate::error::{clear_error, store_error};
+use crate::generation::collect_stop_conditions;
+use crate::stop_matcher::{CStopMatcher, StopStep};
+use crate::tokenizer::CTokenizer;
+
+use super::utils::{read_required_utf8, read_utf8_array, set_status};
+
+fn read_ids<'a>(ids: *const u32, length: usize) -> Result<&'a [u32], &'static str> {
+    if length == 0 {
+        return Ok(&[]);
+    }
+
+    if ids.is_null() {
+        return Err("received null ids pointer");
+    }
+
+    Ok(unsafe { std::slice::from_raw_parts(ids, length) })
+}
+
+fn read_sequences(
+    ids: *const u32,
+    lengths: *const usize,
+    count: usize,
+) -> Result<Vec<Vec<u32>>, &'static str> {
+    if count == 0 {
+        return Ok(Vec::new());
+    }
+
+    if lengths.is_null() {
+        return Err("received null sequence lengths pointer");
+    }
+
+    let lengths = unsafe { std::slice::from_raw_parts(lengths, count) };
+    let total = lengths
+        .iter()
+        .try_fold(0usize, |total, length| total.checked_add(*length))
+        .ok_or("sequence lengths overflow")?;
+    let flat = read_ids(ids, total)?;
+
+    let mut offset = 0usize;
+    Ok(lengths
+        .iter()
+        .map(|length| {
+            let sequence = flat[offset..offset + length].to_vec();
+            offset += length;
+            sequence
+        })
+        .collect())
+}
+
+fn into_step_result(
+    step: StopStep,
+    context: &str,
+    action: *mut c_int,
+    status: *mut c_int,
+    code: c_int,
+) -> *mut c_char {
+    match CString::new(step.text) {
+        Ok(value) => {
+            set_status(action, step.action.as_raw());
+            clear_error();
+            set_status(status, 0);
+            value.into_raw()
+        }
+        Err(_) => {
+            store_error(&format!("{context} produced interior null byte"));
+            set_status(status, code);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Creates a stop matcher from stop strings, stop token-id sequences stored back to back in
+/// `sequence_ids` with per-sequence `sequence_lengths`, and EOS ids. Token ids fed to the matcher are
+/// decoded with `skip_special_tokens`. Sequence lengths whose sum overflows fail with status `2`.
+///
+/// # Safety
+/// `stop_strings` must hold `stop_string_count` UTF-8 pointers, `sequence_lengths` must hold `sequence_count` entries whose sum `sequence_ids` covers, and `eos_token_ids` must hold `eos_count` ids; free the matcher with `tokenizers_stop_matcher_free`.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_stop_matcher_new(
+    stop_strings: *const *const c_char,
+    stop_string_count: usize,
+    sequence_ids: *const u32,
+    sequence_lengths: *const usize,
+    sequence_count: usize,
+    eos_token_ids: *const u32,
+    eos_count: usize,
+    skip_special_tokens: bool,
+    status: *mut c_int,
+) -> *mut CStopMatcher {
+    let strings = match read_utf8_array(stop_strings, stop_string_count) {
+        Ok(values) => values,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 1);
+            return ptr::null_mut();
+        }
+    };
+
+    let sequences = match read_sequences(sequence_ids, sequence_lengths, sequence_count) {
+        Ok(values) => values,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 2);
+            return ptr::null_mut();
+        }
+    };
+
+    let eos = match read_ids(eos_token_ids, eos_count) {
+        Ok(values) => values.to_vec(),
+        Err(message) => {
+            store_error(message);
+            set_status(status, 3);
+            return ptr::null_mut();
+        }
+    };
+
+    clear_error();
+    set_status(status, 0);
+    Box::into_raw(Box::new(CStopMatcher::new(
+        strings,
+        sequences,
+        eos,
+        skip_special_tokens,
+    )))
+}
+
+/// Creates a stop matcher from the stop strings and `eos_token_id` entries of a generation_config.json payload.
+///
+/// # Safety
+/// `config` must reference a null-terminated UTF-8 string; free the matcher with `tokenizers_stop_matcher_free`.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_stop_matcher_from_generation_config(
+    config: *const c_char,
+    skip_special_tokens: bool,
+    status: *mut c_int,
+) -> *mut CStopMatcher {
+    let payload = match read_required_utf8(config) {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 1);
+            return ptr::null_mut();
+        }
+    };
+
+    match collect_stop_conditions(&payload) {
+        Ok(conditions) => {
+            clear_error();
+            set_status(status, 0);
+            Box::into_raw(Box::new(CStopMatcher::new(
+                conditions.stop_strings,
+                Vec::new(),
+                conditions.eos_token_ids,
+                skip_special_tokens,
+            )))
+        }
+        Err(err) => {
+            store_error(&err.into_message());
+            set_status(status, 2);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Feeds generated token ids and returns the text that is safe to show. `action` receives `0` when
+/// everything was released, `1` when text is held back as a possible stop prefix or ids have not
+/// decoded to complete characters yet, or `2` on a stop, in which case the text is trimmed right before the match.
+///
+/// # Safety
+/// `matcher` and `tokenizer` must be valid pointers and `ids` must hold `length` ids; the caller frees the result with `tokenizers_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_stop_matcher_feed_tokens(
+    matcher: *mut CStopMatcher,
+    tokenizer: *const CTokenizer,
+    ids: *const u32,
+    length: usize,
+    action: *mut c_int,
+    status: *mut c_int,
+) -> *mut c_char {
+    let Some(matcher) = (unsafe { matcher.as_mut() }) else {
+        store_error("tokenizers_stop_matcher_feed_tokens received null matcher");
+        set_status(status, 1);
+        return ptr::null_mut();
+    };
+
+    let Some(tokenizer) = (unsafe { tokenizer.as_ref() }) else {
+        store_error("tokenizers_stop_matcher_feed_tokens received null tokenizer");
+        set_status(status, 2);
+        return ptr::null_mut();
+    };
+
+    let ids = match read_ids(ids, length) {
+        Ok(values) => values,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 3);
+            return ptr::null_mut();
+        }
+    };
+
+    match matcher.feed_tokens(tokenizer.inner(), ids) {
+        Ok(step) => into_step_result(
+            step,
+            "tokenizers_stop_matcher_feed_tokens",
+            action,
+            status,
+            4,
+        ),
+        Err(err) => {
+            store_error(&format!(
+                "tokenizers_stop_matcher_feed_tokens failed: {err}"
+            ));
+            set_status(status, 5);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Feeds a decoded text fragment; `action` follows `tokenizers_stop_matcher_feed_tokens`.
+///
+/// # Safety
+/// `matcher` must be a valid pointer and `text` a null-terminated UTF-8 string; the caller frees the result with `tokenizers_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_stop_matcher_feed_text(
+    matcher: *mut CStopMatcher,
+    text: *const c_char,
+    action: *mut c_int,
+    status: *mut c_int,
+) -> *mut c_char {
+    let Some(matcher) = (unsafe { matcher.as_mut() }) else {
+        store_error("tokenizers_stop_matcher_feed_text received null matcher");
+        set_status(status, 1);
+        return ptr::null_mut();
+    };
+
+    let fragment = match read_required_utf8(text) {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 2);
+            return ptr::null_mut();
+        }
+    };
+
+    into_step_result(
+        matcher.feed_text(&fragment),
+        "tokenizers_stop_matcher_feed_text",
+        action,
+        status,
+        3,
+    )
+}
+
+/// Releases any held-back text after generation ends without a stop and resets the matcher.
+/// `tokenizer` may be null when only text fragments were fed.
+///
+/// # Safety
+/// `matcher` must be a valid pointer and `tokenizer` null or valid; the caller frees the result with `tokenizers_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_stop_matcher_finish(
+    matcher: *mut CStopMatcher,
+    tokenizer: *const CTokenizer,
+    status: *mut c_int,
+) -> *mut c_char {
+    let Some(matcher) = (unsafe { matcher.as_mut() }) else {
+        store_error("tokenizers_stop_matcher_finish received null matcher");
+        set_status(status, 1);
+        return ptr::null_mut();
+    };
+
+    let tokenizer = unsafe { tokenizer.as_ref() }.map(CTokenizer::inner);
+    let text = match matcher.finish(tokenizer) {
+        Ok(value) => value,
+        Err(err) => {
+            store_error(&format!("tokenizers_stop_matcher_finish failed: {err}"));
+            set_status(status, 2);
+            return ptr::null_mut();
+        }
+    };
+
+    match CString::new(text) {
+        Ok(value) => {
+            clear_error();
+            set_status(status, 0);
+            value.into_raw()
+        }
+        Err(_) => {
+            store_error("tokenizers_stop_matcher_finish produced interior null byte");
+            set_status(status, 3);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// # Safety
+/// `matcher` must be null or a pointer returned by this module.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_stop_matcher_reset(matcher: *mut CStopMatcher) {
+    if let Some(matcher) = unsafe { matcher.as_mut() } {
+        matcher.reset();
+    }
+}
+
+/// # Safety
+/// `matcher` must be null or a pointer returned by this module and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_stop_matcher_free(matcher: *mut CStopMatcher) {
+    if matcher.is_null() {
+        return;
+    }
+
+    unsafe {
+        drop(Box::from_raw(matcher));
+    }
+}
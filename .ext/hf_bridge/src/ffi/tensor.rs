@@ -0,0 +1,118 @@
+use std::os::raw::{c_char, c_int};
+
+use crate::batch::{batch_inputs, encode_batch_fast, BatchError};
+use crate::error::{clear_error, store_error};
+use crate::tensor::{fill_tensors, uniform_sequence_length, CBatchTensorDest, TensorElementType};
+use crate::tokenizer::CTokenizer;
+
+use super::utils::{read_optional_utf8_array, read_utf8_array, set_length, set_status};
+
+/// Encodes a batch straight into row-major tensors; `element_type` is `0` for int64 or `1` for int32.
+/// When the destination is too small, `sequence_length` still reports the padded length so the caller can resize.
+/// Rows are padded with the settings from `tokenizers_enable_padding`; without them rows of
+/// different lengths fail with status `8` rather than being padded with an assumed pad id.
+///
+/// # Safety
+/// `sequences` must hold `count` UTF-8 pointers, `pairs` must be null or hold `count` nullable UTF-8 pointers, and every non-null buffer in `destination` must hold `capacity` elements of the chosen type.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_encode_batch_tensors(
+    tokenizer: *const CTokenizer,
+    sequences: *const *const c_char,
+    pairs: *const *const c_char,
+    count: usize,
+    add_special_tokens: bool,
+    max_threads: usize,
+    element_type: c_int,
+    destination: *const CBatchTensorDest,
+    sequence_length: *mut usize,
+    status: *mut c_int,
+) -> c_int {
+    let Some(tokenizer) = (unsafe { tokenizer.as_ref() }) else {
+        store_error("tokenizers_encode_batch_tensors received null tokenizer");
+        set_status(status, 1);
+        return 0;
+    };
+
+    let Some(destination) = (unsafe { destination.as_ref() }) else {
+        store_error("tokenizers_encode_batch_tensors received null destination");
+        set_status(status, 2);
+        return 0;
+    };
+
+    let element_type =
+        match TensorElementType::from_raw(element_type, "tokenizers_encode_batch_tensors") {
+            Ok(value) => value,
+            Err(message) => {
+                store_error(&message);
+                set_status(status, 3);
+                return 0;
+            }
+        };
+
+    let primary = match read_utf8_array(sequences, count) {
+        Ok(values) => values,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 4);
+            return 0;
+        }
+    };
+
+    let pair_texts = match read_optional_utf8_array(pairs, count) {
+        Ok(values) => values,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 5);
+            return 0;
+        }
+    };
+
+    let inputs = batch_inputs(&primary, pair_texts.as_deref());
+    let encodings =
+        match encode_batch_fast(tokenizer.inner(), inputs, add_special_tokens, max_threads) {
+            Ok(values) => values,
+            Err(err) => {
+                let code = match err {
+                    BatchError::ThreadPool(_) => 6,
+                    BatchError::Encode(_) => 7,
+                };
+                store_error(&format!(
+                    "tokenizers_encode_batch_tensors: {}",
+                    err.into_message()
+                ));
+                set_status(status, code);
+                return 0;
+            }
+        };
+
+    let padded_length = match uniform_sequence_length(&encodings, "tokenizers_encode_batch_tensors")
+    {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(&message);
+            set_status(status, 8);
+            return 0;
+        }
+    };
+
+    set_length(sequence_length, padded_length);
+
+    match fill_tensors(
+        &encodings,
+        padded_length,
+        element_type,
+        destination,
+        "tokenizers_encode_batch_tensors",
+    ) {
+        Ok(rows) => {
+            clear_error();
+            set_status(status, 0);
+            rows as c_int
+        }
+        Err(message) => {
+            store_error(&message);
+            set_status(status, 9);
+            0
+        }
+    }
+}
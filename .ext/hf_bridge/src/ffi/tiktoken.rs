@@ -0,0 +1,114 @@
+use std::collections::BTreeMap;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+
+use crate::error::{clear_error, store_error};
+use crate::tiktoken::{build_tokenizer, parse_ranks, TiktokenError, TiktokenPreset};
+use crate::tokenizer::CTokenizer;
+
+use super::utils::{read_optional_utf8, read_required_utf8, set_status};
+
+/// Builds a ByteLevel BPE tokenizer from a `.tiktoken` rank file.
+///
+/// `preset` is -1 (custom), 0 for cl100k_base or 1 for o200k_base and supplies the split pattern
+/// and special tokens. A non-null `pattern` replaces the preset pattern and is required for -1;
+/// `special_tokens` is an optional JSON object of token to id that extends or overrides the preset.
+///
+/// # Safety
+/// `rank_path` must be a null-terminated UTF-8 string, `pattern` and `special_tokens` must be null
+/// or UTF-8, and `status` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_from_tiktoken(
+    rank_path: *const c_char,
+    preset: c_int,
+    pattern: *const c_char,
+    special_tokens: *const c_char,
+    status: *mut c_int,
+) -> *mut CTokenizer {
+    let preset = match TiktokenPreset::from_raw(preset) {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 1);
+            return ptr::null_mut();
+        }
+    };
+
+    let path = match read_required_utf8(rank_path) {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 2);
+            return ptr::null_mut();
+        }
+    };
+
+    let (pattern, special_tokens) = match read_optional_utf8(pattern)
+        .and_then(|pattern| Ok((pattern, read_optional_utf8(special_tokens)?)))
+    {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 3);
+            return ptr::null_mut();
+        }
+    };
+
+    let Some(pattern) = pattern.or_else(|| preset.map(|preset| preset.pattern().to_string()))
+    else {
+        store_error("tokenizers_from_tiktoken requires a pattern when no preset is selected");
+        set_status(status, 4);
+        return ptr::null_mut();
+    };
+
+    let mut specials: BTreeMap<String, u32> = preset
+        .map(TiktokenPreset::special_tokens)
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+    if let Some(json) = special_tokens {
+        match serde_json::from_str::<BTreeMap<String, u32>>(&json) {
+            Ok(overrides) => specials.extend(overrides),
+            Err(err) => {
+                store_error(&format!(
+                    "tokenizers_from_tiktoken received invalid special tokens: {err}"
+                ));
+                set_status(status, 5);
+                return ptr::null_mut();
+            }
+        }
+    }
+
+    let content = match std::fs::read_to_string(&path) {
+        Ok(value) => value,
+        Err(err) => {
+            store_error(&format!(
+                "tokenizers_from_tiktoken failed to read {path}: {err}"
+            ));
+            set_status(status, 6);
+            return ptr::null_mut();
+        }
+    };
+
+    let mut specials: Vec<(String, u32)> = specials.into_iter().collect();
+    specials.sort_by_key(|(_, id)| *id);
+    match parse_ranks(&content).and_then(|ranks| build_tokenizer(&ranks, &pattern, &specials)) {
+        Ok(tokenizer) => {
+            clear_error();
+            set_status(status, 0);
+            Box::into_raw(Box::new(CTokenizer::new(tokenizer)))
+        }
+        Err(err) => {
+            let code = match err {
+                TiktokenError::Format(_) => 7,
+                TiktokenError::Build(_) => 8,
+            };
+            store_error(&format!(
+                "tokenizers_from_tiktoken failed: {}",
+                err.into_message()
+            ));
+            set_status(status, code);
+            ptr::null_mut()
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use std::os::raw::c_int;
+
+use crate::error::{clear_error, store_error};
+use crate::token_bytes::TokenByteMapper;
+use crate::tokenizer::CTokenizer;
+
+use super::utils::{copy_slice, set_length, set_status};
+
+/// Writes the raw bytes token `id` contributes to decoded text, undoing ByteLevel, ByteFallback
+/// `<0xNN>` and Metaspace surface forms. `flags` receives `1` for special tokens and `2` when the
+/// bytes are an incomplete UTF-8 fragment. A null `buffer` only reports `length`; a non-null
+/// buffer smaller than `length` fails with status `3`.
+///
+/// # Safety
+/// `tokenizer` must be a valid pointer, `buffer` must be null or hold `capacity` bytes, and `length`, `flags` and `status` must be null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_token_bytes(
+    tokenizer: *const CTokenizer,
+    id: u32,
+    buffer: *mut u8,
+    capacity: usize,
+    length: *mut usize,
+    flags: *mut u32,
+    status: *mut c_int,
+) -> c_int {
+    let Some(tokenizer) = (unsafe { tokenizer.as_ref() }) else {
+        store_error("tokenizers_token_bytes received null tokenizer");
+        set_status(status, 1);
+        return 0;
+    };
+
+    let mapper = TokenByteMapper::new(tokenizer.inner());
+    let Some(token) = mapper.token_bytes(tokenizer.inner(), id) else {
+        store_error("tokenizers_token_bytes: id not found");
+        set_status(status, 2);
+        return 0;
+    };
+
+    set_length(length, token.bytes.len());
+    if !flags.is_null() {
+        unsafe { *flags = token.flags() };
+    }
+
+    if buffer.is_null() {
+        clear_error();
+        set_status(status, 0);
+        return 0;
+    }
+
+    if capacity < token.bytes.len() {
+        store_error("tokenizers_token_bytes buffer is too small");
+        set_status(status, 3);
+        return 0;
+    }
+
+    copy_slice(&token.bytes, buffer, token.bytes.len());
+    clear_error();
+    set_status(status, 0);
+    token.bytes.len() as c_int
+}
+
+/// Bulk form of `tokenizers_token_bytes`: token `i`'s bytes occupy `offsets[i]..offsets[i + 1]` of
+/// `buffer` and its flags land in `flags[i]`. A null `buffer` only reports `required`; a non-null
+/// buffer smaller than that fails with status `5`.
+///
+/// # Safety
+/// `ids` must hold `count` ids, `buffer` must be null or hold `capacity` bytes, `offsets` must hold `count + 1` entries when `buffer` is non-null, and `flags` must be null or hold `count` entries.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_tokens_bytes(
+    tokenizer: *const CTokenizer,
+    ids: *const u32,
+    count: usize,
+    buffer: *mut u8,
+    capacity: usize,
+    offsets: *mut usize,
+    flags: *mut u32,
+    required: *mut usize,
+    status: *mut c_int,
+) -> c_int {
+    let Some(tokenizer) = (unsafe { tokenizer.as_ref() }) else {
+        store_error("tokenizers_tokens_bytes received null tokenizer");
+        set_status(status, 1);
+        return 0;
+    };
+
+    if count > 0 && ids.is_null() {
+        store_error("tokenizers_tokens_bytes received null ids pointer");
+        set_status(status, 2);
+        return 0;
+    }
+
+    let ids = if count == 0 {
+        &[][..]
+    } else {
+        unsafe { std::slice::from_raw_parts(ids, count) }
+    };
+
+    let mapper = TokenByteMapper::new(tokenizer.inner());
+    let mut tokens = Vec::with_capacity(count);
+    for (index, id) in ids.iter().enumerate() {
+        match mapper.token_bytes(tokenizer.inner(), *id) {
+            Some(token) => tokens.push(token),
+            None => {
+                store_error(&format!(
+                    "tokenizers_tokens_bytes: id {id} at index {index} not found"
+                ));
+                set_status(status, 3);
+                return 0;
+            }
+        }
+    }
+
+    let mut boundaries = Vec::with_capacity(count + 1);
+    boundaries.push(0usize);
+    let mut total = 0usize;
+    for token in &tokens {
+        total += token.bytes.len();
+        boundaries.push(total);
+    }
+
+    set_length(required, total);
+    if !flags.is_null() {
+        let token_flags: Vec<u32> = tokens.iter().map(|token| token.flags()).collect();
+        copy_slice(&token_flags, flags, count);
+    }
+
+    if buffer.is_null() {
+        clear_error();
+        set_status(status, 0);
+        return 0;
+    }
+
+    if offsets.is_null() {
+        store_error("tokenizers_tokens_bytes received null offsets pointer");
+        set_status(status, 4);
+        return 0;
+    }
+
+    if capacity < total {
+        store_error("tokenizers_tokens_bytes buffer is too small");
+        set_status(status, 5);
+        return 0;
+    }
+
+    let bytes: Vec<u8> = tokens.into_iter().flat_map(|token| token.bytes).collect();
+    copy_slice(&bytes, buffer, total);
+    copy_slice(&boundaries, offsets, boundaries.len());
+
+    clear_error();
+    set_status(status, 0);
+    count as c_int
+}
@@ -0,0 +1,185 @@
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+
+use serde::Serialize;
+
+use crate::error::{clear_error, store_error};
+use crate::offsets::OffsetMode;
+use crate::tokenizer::CTokenizer;
+use crate::trace::{
+    trace_model, trace_normalize, trace_post_process, trace_pre_tokenize, TraceError,
+};
+
+use super::utils::{read_optional_utf8, read_required_utf8, set_status};
+
+fn into_trace_json<T: Serialize>(
+    result: Result<T, TraceError>,
+    context: &str,
+    status: *mut c_int,
+) -> *mut c_char {
+    let trace = match result {
+        Ok(value) => value,
+        Err(err) => {
+            store_error(&format!("{context}: {}", err.into_message()));
+            set_status(status, 4);
+            return ptr::null_mut();
+        }
+    };
+
+    match serde_json::to_string(&trace).map(CString::new) {
+        Ok(Ok(json)) => {
+            clear_error();
+            set_status(status, 0);
+            json.into_raw()
+        }
+        _ => {
+            store_error(&format!("{context} failed to serialize trace"));
+            set_status(status, 5);
+            ptr::null_mut()
+        }
+    }
+}
+
+fn read_trace_input<'a>(
+    tokenizer: *const CTokenizer,
+    text: *const c_char,
+    offset_mode: c_int,
+    context: &str,
+    status: *mut c_int,
+) -> Option<(&'a CTokenizer, String, OffsetMode)> {
+    let Some(tokenizer) = (unsafe { tokenizer.as_ref() }) else {
+        store_error(&format!("{context} received null tokenizer"));
+        set_status(status, 1);
+        return None;
+    };
+
+    let text = match read_required_utf8(text) {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 2);
+            return None;
+        }
+    };
+
+    match OffsetMode::from_raw(offset_mode) {
+        Ok(mode) => Some((tokenizer, text, mode)),
+        Err(message) => {
+            store_error(message);
+            set_status(status, 3);
+            None
+        }
+    }
+}
+
+/// Runs only the normalizer and returns `{original, normalized, alignments}`, where each alignment
+/// maps one normalized character to its original span, in `offset_mode` units.
+///
+/// # Safety
+/// `tokenizer` must be a valid pointer and `text` a null-terminated UTF-8 string; the caller frees the result with `tokenizers_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_trace_normalize(
+    tokenizer: *const CTokenizer,
+    text: *const c_char,
+    offset_mode: c_int,
+    status: *mut c_int,
+) -> *mut c_char {
+    let context = "tokenizers_trace_normalize";
+    let Some((tokenizer, text, mode)) =
+        read_trace_input(tokenizer, text, offset_mode, context, status)
+    else {
+        return ptr::null_mut();
+    };
+
+    into_trace_json(
+        trace_normalize(tokenizer.inner(), &text, mode),
+        context,
+        status,
+    )
+}
+
+/// Runs only the pre-tokenizer on the raw text and returns `{splits: [{text, offsets}]}`.
+///
+/// # Safety
+/// `tokenizer` must be a valid pointer and `text` a null-terminated UTF-8 string; the caller frees the result with `tokenizers_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_trace_pre_tokenize(
+    tokenizer: *const CTokenizer,
+    text: *const c_char,
+    offset_mode: c_int,
+    status: *mut c_int,
+) -> *mut c_char {
+    let context = "tokenizers_trace_pre_tokenize";
+    let Some((tokenizer, text, mode)) =
+        read_trace_input(tokenizer, text, offset_mode, context, status)
+    else {
+        return ptr::null_mut();
+    };
+
+    into_trace_json(
+        trace_pre_tokenize(tokenizer.inner(), &text, mode),
+        context,
+        status,
+    )
+}
+
+/// Runs only the model on one pre-tokenized word and returns `{model, word, tokens}`, plus the
+/// replayed `merges` for BPE models.
+///
+/// # Safety
+/// `tokenizer` must be a valid pointer and `word` a null-terminated UTF-8 string; the caller frees the result with `tokenizers_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_trace_model(
+    tokenizer: *const CTokenizer,
+    word: *const c_char,
+    offset_mode: c_int,
+    status: *mut c_int,
+) -> *mut c_char {
+    let context = "tokenizers_trace_model";
+    let Some((tokenizer, word, mode)) =
+        read_trace_input(tokenizer, word, offset_mode, context, status)
+    else {
+        return ptr::null_mut();
+    };
+
+    let trace = tokenizer
+        .bpe_merges()
+        .map_err(TraceError::Model)
+        .and_then(|merges| trace_model(tokenizer.inner(), merges, &word, mode));
+    into_trace_json(trace, context, status)
+}
+
+/// Runs the post-processor over the encoded input and returns `{tokens}`, where tokens the
+/// post-processor inserted have `inserted: true` and a null `sequence_id`.
+///
+/// # Safety
+/// `tokenizer` must be a valid pointer, `sequence` a null-terminated UTF-8 string and `pair` null or one; the caller frees the result with `tokenizers_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_trace_post_process(
+    tokenizer: *const CTokenizer,
+    sequence: *const c_char,
+    pair: *const c_char,
+    status: *mut c_int,
+) -> *mut c_char {
+    let context = "tokenizers_trace_post_process";
+    let Some((tokenizer, sequence, _)) = read_trace_input(tokenizer, sequence, 0, context, status)
+    else {
+        return ptr::null_mut();
+    };
+
+    let pair = match read_optional_utf8(pair) {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 2);
+            return ptr::null_mut();
+        }
+    };
+
+    into_trace_json(
+        trace_post_process(tokenizer.unconstrained(), &sequence, pair.as_deref()),
+        context,
+        status,
+    )
+}
@@ -1,11 +1,14 @@
-use std::os::raw::c_int;
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
 
 use tokenizers::utils::truncation::{TruncationDirection, TruncationParams, TruncationStrategy};
 
 use crate::error::{clear_error, store_error};
+use crate::text_truncation::{truncate_text, TextTruncationError, TextTruncationStrategy};
 use crate::tokenizer::CTokenizer;
 
-use super::utils::set_status;
+use super::utils::{read_optional_utf8, read_required_utf8, set_length, set_status};
 
 /// # Safety
 /// `tokenizer` and `status` must be valid pointers supplied by the caller.
@@ -99,3 +102,96 @@ pub unsafe extern "C" fn tokenizers_disable_truncation(
         }
     }
 }
+
+/// Returns the head (`0`), tail (`1`), or head-plus-tail (`2`) of `text` that fits in `max_tokens`,
+/// cut from the original string at token boundaries and joined with the optional `marker`.
+/// The tokenizer's own truncation and padding settings are ignored. When the special tokens alone
+/// exceed `max_tokens` nothing fits and status `6` is reported. A marker that does not fit next to
+/// the special tokens is left out and `marker_dropped` is set to `true`.
+///
+/// # Safety
+/// `tokenizer` must be valid, `text` must be UTF-8, `marker` must be null or UTF-8, `token_count` and `marker_dropped` must be null or writable, and `status` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_truncate_text(
+    tokenizer: *const CTokenizer,
+    text: *const c_char,
+    max_tokens: usize,
+    strategy: c_int,
+    marker: *const c_char,
+    add_special_tokens: bool,
+    token_count: *mut usize,
+    marker_dropped: *mut bool,
+    status: *mut c_int,
+) -> *mut c_char {
+    let Some(tokenizer) = (unsafe { tokenizer.as_ref() }) else {
+        store_error("tokenizers_truncate_text received null tokenizer");
+        set_status(status, 1);
+        return ptr::null_mut();
+    };
+
+    let source = match read_required_utf8(text) {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 2);
+            return ptr::null_mut();
+        }
+    };
+
+    let strategy = match TextTruncationStrategy::from_raw(strategy) {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(&format!("tokenizers_truncate_text: {message}"));
+            set_status(status, 3);
+            return ptr::null_mut();
+        }
+    };
+
+    let marker_text = match read_optional_utf8(marker) {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 4);
+            return ptr::null_mut();
+        }
+    };
+
+    let truncated = match truncate_text(
+        tokenizer.unconstrained(),
+        &source,
+        max_tokens,
+        strategy,
+        marker_text.as_deref(),
+        add_special_tokens,
+    ) {
+        Ok(value) => value,
+        Err(err) => {
+            let code = match err {
+                TextTruncationError::Encode(_) => 5,
+                TextTruncationError::OverBudget { .. } => 6,
+            };
+            store_error(&format!("tokenizers_truncate_text: {}", err.into_message()));
+            set_status(status, code);
+            return ptr::null_mut();
+        }
+    };
+
+    match CString::new(truncated.text) {
+        Ok(value) => {
+            set_length(token_count, truncated.token_count);
+            if !marker_dropped.is_null() {
+                unsafe {
+                    *marker_dropped = truncated.marker_dropped;
+                }
+            }
+            clear_error();
+            set_status(status, 0);
+            value.into_raw()
+        }
+        Err(_) => {
+            store_error("tokenizers_truncate_text failed to allocate CString");
+            set_status(status, 7);
+            ptr::null_mut()
+        }
+    }
+}
@@ -37,6 +37,68 @@ pub(crate) fn read_optional_utf8(ptr_value: *const c_char) -> Result<Option<Stri
     }
 }
 
+pub(crate) fn read_required_utf16(
+    ptr_value: *const u16,
+    length: usize,
+) -> Result<String, &'static str> {
+    if length == 0 {
+        return Ok(String::new());
+    }
+
+    if ptr_value.is_null() {
+        return Err("received null pointer");
+    }
+
+    let units = unsafe { std::slice::from_raw_parts(ptr_value, length) };
+    String::from_utf16(units).map_err(|_| "invalid UTF-16 data")
+}
+
+pub(crate) fn read_optional_utf16(
+    ptr_value: *const u16,
+    length: usize,
+) -> Result<Option<String>, &'static str> {
+    if ptr_value.is_null() {
+        Ok(None)
+    } else {
+        read_required_utf16(ptr_value, length).map(Some)
+    }
+}
+
+pub(crate) fn read_utf8_array(
+    ptr_value: *const *const c_char,
+    count: usize,
+) -> Result<Vec<String>, &'static str> {
+    if count == 0 {
+        return Ok(Vec::new());
+    }
+
+    if ptr_value.is_null() {
+        return Err("received null array pointer");
+    }
+
+    let entries = unsafe { std::slice::from_raw_parts(ptr_value, count) };
+    entries
+        .iter()
+        .map(|entry| read_required_utf8(*entry))
+        .collect()
+}
+
+pub(crate) fn read_optional_utf8_array(
+    ptr_value: *const *const c_char,
+    count: usize,
+) -> Result<Option<Vec<Option<String>>>, &'static str> {
+    if ptr_value.is_null() {
+        return Ok(None);
+    }
+
+    let entries = unsafe { std::slice::from_raw_parts(ptr_value, count) };
+    entries
+        .iter()
+        .map(|entry| read_optional_utf8(*entry))
+        .collect::<Result<Vec<_>, _>>()
+        .map(Some)
+}
+
 pub(crate) fn copy_slice<T: Copy>(source: &[T], destination: *mut T, length: usize) {
     if destination.is_null() || length == 0 {
         return;
@@ -54,6 +116,14 @@ pub mod test_support {
 
     use super::set_status as inner_set_status;
     use super::{copy_slice as inner_copy_slice, read_optional_utf8 as inner_read_optional_utf8};
+    use super::{
+        read_optional_utf16 as inner_read_optional_utf16,
+        read_required_utf16 as inner_read_required_utf16,
+    };
+    use super::{
+        read_optional_utf8_array as inner_read_optional_utf8_array,
+        read_utf8_array as inner_read_utf8_array,
+    };
     use super::{read_required_utf8 as inner_read_required_utf8, set_length as inner_set_length};
 
     pub fn set_status(status: *mut c_int, value: c_int) {
@@ -72,6 +142,34 @@ pub mod test_support {
         inner_read_optional_utf8(ptr_value)
     }
 
+    pub fn read_required_utf16(
+        ptr_value: *const u16,
+        length: usize,
+    ) -> Result<String, &'static str> {
+        inner_read_required_utf16(ptr_value, length)
+    }
+
+    pub fn read_optional_utf16(
+        ptr_value: *const u16,
+        length: usize,
+    ) -> Result<Option<String>, &'static str> {
+        inner_read_optional_utf16(ptr_value, length)
+    }
+
+    pub fn read_utf8_array(
+        ptr_value: *const *const c_char,
+        count: usize,
+    ) -> Result<Vec<String>, &'static str> {
+        inner_read_utf8_array(ptr_value, count)
+    }
+
+    pub fn read_optional_utf8_array(
+        ptr_value: *const *const c_char,
+        count: usize,
+    ) -> Result<Option<Vec<Option<String>>>, &'static str> {
+        inner_read_optional_utf8_array(ptr_value, count)
+    }
+
     pub fn copy_slice<T: Copy>(source: &[T], destination: *mut T, length: usize) {
         inner_copy_slice(source, destination, length);
     }
@@ -0,0 +1,303 @@
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+
+use serde_json::{Map, Value};
+
+use crate::error::{clear_error, store_error};
+use crate::tokenizer::CTokenizer;
+use crate::vocab::{added_tokens, sorted_vocab};
+
+use super::utils::{copy_slice, read_utf8_array, set_length, set_status};
+
+/// Writes `values` back to back into `buffer`, with value `i` at `offsets[i]..offsets[i + 1]`.
+/// Returns `Ok(false)` for a size query (null `buffer`); `required` is set either way.
+fn write_flat_strings(
+    values: &[&str],
+    buffer: *mut u8,
+    capacity: usize,
+    offsets: *mut usize,
+    required: *mut usize,
+) -> Result<bool, &'static str> {
+    let mut boundaries = Vec::with_capacity(values.len() + 1);
+    boundaries.push(0usize);
+    let mut total = 0usize;
+    for value in values {
+        total += value.len();
+        boundaries.push(total);
+    }
+
+    set_length(required, total);
+    if buffer.is_null() {
+        return Ok(false);
+    }
+
+    if offsets.is_null() {
+        return Err("received null offsets pointer");
+    }
+
+    if capacity < total {
+        return Err("buffer is too small");
+    }
+
+    let bytes: Vec<u8> = values.iter().flat_map(|value| value.bytes()).collect();
+    copy_slice(&bytes, buffer, total);
+    copy_slice(&boundaries, offsets, boundaries.len());
+    Ok(true)
+}
+
+fn into_json_string(value: &Value, context: &str, status: *mut c_int) -> *mut c_char {
+    match serde_json::to_string(value).map(CString::new) {
+        Ok(Ok(json)) => {
+            clear_error();
+            set_status(status, 0);
+            json.into_raw()
+        }
+        _ => {
+            store_error(&format!("{context} failed to serialize result"));
+            set_status(status, 2);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// # Safety
+/// `tokenizer` must be a valid pointer and `status` must be null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_get_vocab_size(
+    tokenizer: *const CTokenizer,
+    with_added_tokens: bool,
+    status: *mut c_int,
+) -> usize {
+    let Some(tokenizer) = (unsafe { tokenizer.as_ref() }) else {
+        store_error("tokenizers_get_vocab_size received null tokenizer");
+        set_status(status, 1);
+        return 0;
+    };
+
+    clear_error();
+    set_status(status, 0);
+    tokenizer.inner().get_vocab_size(with_added_tokens)
+}
+
+/// Returns the vocabulary as a JSON object mapping each token to its id, ordered by id.
+///
+/// # Safety
+/// `tokenizer` must be a valid pointer; the caller frees the result with `tokenizers_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_get_vocab(
+    tokenizer: *const CTokenizer,
+    with_added_tokens: bool,
+    status: *mut c_int,
+) -> *mut c_char {
+    let Some(tokenizer) = (unsafe { tokenizer.as_ref() }) else {
+        store_error("tokenizers_get_vocab received null tokenizer");
+        set_status(status, 1);
+        return ptr::null_mut();
+    };
+
+    let vocab: Map<String, Value> = sorted_vocab(tokenizer.inner(), with_added_tokens)
+        .into_iter()
+        .map(|(id, token)| (token, Value::from(id)))
+        .collect();
+
+    into_json_string(&Value::Object(vocab), "tokenizers_get_vocab", status)
+}
+
+/// Writes the vocabulary ordered by id as flat buffers: `ids` receives every id and token `i`
+/// occupies `offsets[i]..offsets[i + 1]` of the UTF-8 `buffer`. `count` and `required` are always
+/// reported; pass null `ids` and `buffer` to only query them. Undersized buffers fail with status `3`.
+///
+/// # Safety
+/// `ids` must be null or hold `id_capacity` entries, `buffer` must be null or hold `capacity` bytes, and `offsets` must hold `id_capacity + 1` entries when `buffer` is non-null.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_get_vocab_flat(
+    tokenizer: *const CTokenizer,
+    with_added_tokens: bool,
+    ids: *mut u32,
+    id_capacity: usize,
+    buffer: *mut u8,
+    capacity: usize,
+    offsets: *mut usize,
+    count: *mut usize,
+    required: *mut usize,
+    status: *mut c_int,
+) -> c_int {
+    let Some(tokenizer) = (unsafe { tokenizer.as_ref() }) else {
+        store_error("tokenizers_get_vocab_flat received null tokenizer");
+        set_status(status, 1);
+        return 0;
+    };
+
+    let entries = sorted_vocab(tokenizer.inner(), with_added_tokens);
+    set_length(count, entries.len());
+
+    if (!ids.is_null() || !buffer.is_null()) && id_capacity < entries.len() {
+        store_error("tokenizers_get_vocab_flat id capacity is too small");
+        set_status(status, 2);
+        return 0;
+    }
+
+    let tokens: Vec<&str> = entries.iter().map(|(_, token)| token.as_str()).collect();
+    let written = match write_flat_strings(&tokens, buffer, capacity, offsets, required) {
+        Ok(value) => value,
+        Err(message) => {
+            store_error(&format!("tokenizers_get_vocab_flat: {message}"));
+            set_status(status, 3);
+            return 0;
+        }
+    };
+
+    let id_values: Vec<u32> = entries.iter().map(|(id, _)| *id).collect();
+    copy_slice(&id_values, ids, id_values.len());
+
+    clear_error();
+    set_status(status, 0);
+    if written || !ids.is_null() {
+        entries.len() as c_int
+    } else {
+        0
+    }
+}
+
+/// Looks up `count` tokens at once; unknown tokens receive `-1`. Returns how many were found.
+///
+/// # Safety
+/// `tokens` must hold `count` UTF-8 pointers and `ids` must have room for `count` entries.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_tokens_to_ids(
+    tokenizer: *const CTokenizer,
+    tokens: *const *const c_char,
+    count: usize,
+    ids: *mut c_int,
+    status: *mut c_int,
+) -> c_int {
+    let Some(tokenizer) = (unsafe { tokenizer.as_ref() }) else {
+        store_error("tokenizers_tokens_to_ids received null tokenizer");
+        set_status(status, 1);
+        return 0;
+    };
+
+    if count > 0 && ids.is_null() {
+        store_error("tokenizers_tokens_to_ids received null ids buffer");
+        set_status(status, 2);
+        return 0;
+    }
+
+    let values = match read_utf8_array(tokens, count) {
+        Ok(values) => values,
+        Err(message) => {
+            store_error(message);
+            set_status(status, 3);
+            return 0;
+        }
+    };
+
+    let resolved: Vec<c_int> = values
+        .iter()
+        .map(|token| {
+            tokenizer
+                .inner()
+                .token_to_id(token)
+                .map_or(-1, |id| id as c_int)
+        })
+        .collect();
+    copy_slice(&resolved, ids, count);
+
+    clear_error();
+    set_status(status, 0);
+    resolved.iter().filter(|id| **id >= 0).count() as c_int
+}
+
+/// Converts `count` ids to tokens written back to back into `buffer`; token `i` occupies
+/// `offsets[i]..offsets[i + 1]` and unknown ids get an empty span with `found[i]` set to `false`.
+/// A null `buffer` only reports `required`; an undersized one fails with status `3`.
+///
+/// # Safety
+/// `ids` must hold `count` ids, `found` must be null or hold `count` entries, `buffer` must be null or hold `capacity` bytes, and `offsets` must hold `count + 1` entries when `buffer` is non-null.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_ids_to_tokens(
+    tokenizer: *const CTokenizer,
+    ids: *const u32,
+    count: usize,
+    buffer: *mut u8,
+    capacity: usize,
+    offsets: *mut usize,
+    found: *mut bool,
+    required: *mut usize,
+    status: *mut c_int,
+) -> c_int {
+    let Some(tokenizer) = (unsafe { tokenizer.as_ref() }) else {
+        store_error("tokenizers_ids_to_tokens received null tokenizer");
+        set_status(status, 1);
+        return 0;
+    };
+
+    if count > 0 && ids.is_null() {
+        store_error("tokenizers_ids_to_tokens received null ids pointer");
+        set_status(status, 2);
+        return 0;
+    }
+
+    let ids = if count == 0 {
+        &[][..]
+    } else {
+        unsafe { std::slice::from_raw_parts(ids, count) }
+    };
+
+    let tokens: Vec<Option<String>> = ids
+        .iter()
+        .map(|id| tokenizer.inner().id_to_token(*id))
+        .collect();
+    let found_flags: Vec<bool> = tokens.iter().map(Option::is_some).collect();
+    copy_slice(&found_flags, found, count);
+
+    let values: Vec<&str> = tokens
+        .iter()
+        .map(|token| token.as_deref().unwrap_or_default())
+        .collect();
+    match write_flat_strings(&values, buffer, capacity, offsets, required) {
+        Ok(written) => {
+            clear_error();
+            set_status(status, 0);
+            if written {
+                count as c_int
+            } else {
+                0
+            }
+        }
+        Err(message) => {
+            store_error(&format!("tokenizers_ids_to_tokens: {message}"));
+            set_status(status, 3);
+            0
+        }
+    }
+}
+
+/// Returns the added-tokens decoder table as a JSON array ordered by id, with each token's
+/// `special`, `lstrip`, `rstrip`, `normalized` and `single_word` flags.
+///
+/// # Safety
+/// `tokenizer` must be a valid pointer; the caller frees the result with `tokenizers_free_string`.
+#[no_mangle]
+pub unsafe extern "C" fn tokenizers_get_added_tokens(
+    tokenizer: *const CTokenizer,
+    status: *mut c_int,
+) -> *mut c_char {
+    let Some(tokenizer) = (unsafe { tokenizer.as_ref() }) else {
+        store_error("tokenizers_get_added_tokens received null tokenizer");
+        set_status(status, 1);
+        return ptr::null_mut();
+    };
+
+    match serde_json::to_value(added_tokens(tokenizer.inner())) {
+        Ok(value) => into_json_string(&value, "tokenizers_get_added_tokens", status),
+        Err(err) => {
+            store_error(&format!(
+                "tokenizers_get_added_tokens failed to serialize result: {err}"
+            ));
+            set_status(status, 2);
+            ptr::null_mut()
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use tokenizers::Tokenizer;
+
+/// Bumped whenever canonicalization changes, so old and new digests never compare equal by accident.
+const FINGERPRINT_VERSION: u32 = 1;
+
+/// Sections of tokenizer.json that decide which ids a text maps to and how ids decode.
+const BEHAVIOR_SECTIONS: [&str; 6] = [
+    "model",
+    "normalizer",
+    "pre_tokenizer",
+    "post_processor",
+    "decoder",
+    "added_tokens",
+];
+const CONFIGURATION_SECTIONS: [&str; 2] = ["padding", "truncation"];
+
+#[derive(Debug)]
+pub(crate) enum FingerprintError {
+    Serialize(String),
+}
+
+impl FingerprintError {
+    pub(crate) fn into_message(self) -> String {
+        match self {
+            FingerprintError::Serialize(reason) => {
+                format!("failed to serialize tokenizer: {reason}")
+            }
+        }
+    }
+}
+
+/// SHA-256 digests (lowercase hex) of a tokenizer's canonical state.
+///
+/// `tokenizer` covers every behavior section and `components` each one on its own, so callers can
+/// tell which part differs. Padding and truncation only shape batches, so they are digested apart
+/// in `configuration`.
+#[derive(Debug, Serialize)]
+pub(crate) struct Fingerprint {
+    #[serde(rename = "version")]
+    pub(crate) version: u32,
+    #[serde(rename = "tokenizer")]
+    pub(crate) tokenizer: String,
+    #[serde(rename = "components")]
+    pub(crate) components: BTreeMap<&'static str, String>,
+    #[serde(rename = "configuration")]
+    pub(crate) configuration: String,
+}
+
+/// Rewrites `value` with object keys sorted at every level; array order is meaningful and kept.
+fn canonicalize(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+            entries.sort_by(|left, right| left.0.cmp(right.0));
+            let sorted: Map<String, Value> = entries
+                .into_iter()
+                .map(|(key, value)| (key.clone(), canonicalize(value)))
+                .collect();
+            Value::Object(sorted)
+        }
+        Value::Array(items) => Value::Array(items.iter().map(canonicalize).collect()),
+        other => other.clone(),
+    }
+}
+
+/// Digests `sections` of `document` as one compact, key-sorted JSON object.
+fn digest(document: &Map<String, Value>, sections: &[&str]) -> String {
+    let selected: Map<String, Value> = sections
+        .iter()
+        .map(|section| {
+            let value = document.get(*section).cloned().unwrap_or(Value::Null);
+            (section.to_string(), value)
+        })
+        .collect();
+
+    let mut hasher = Sha256::new();
+    hasher.update(format!("tokenx-fingerprint-v{FINGERPRINT_VERSION}\n").as_bytes());
+    hasher.update(
+        canonicalize(&Value::Object(selected))
+            .to_string()
+            .as_bytes(),
+    );
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Computes the fingerprint of `tokenizer` from its re-serialized form, so whitespace, key order,
+/// the legacy `"a b"` merge spelling and other cosmetic differences in the source file do not
+/// matter. Added tokens are ordered by id.
+pub(crate) fn fingerprint(tokenizer: &Tokenizer) -> Result<Fingerprint, FingerprintError> {
+    let json = tokenizer
+        .to_string(false)
+        .map_err(|err| FingerprintError::Serialize(err.to_string()))?;
+    let mut document = match serde_json::from_str(&json) {
+        Ok(Value::Object(map)) => map,
+        Ok(_) => {
+            return Err(FingerprintError::Serialize(String::from(
+                "tokenizer did not serialize to an object",
+            )))
+        }
+        Err(err) => return Err(FingerprintError::Serialize(err.to_string())),
+    };
+
+    if let Some(Value::Array(tokens)) = document.get_mut("added_tokens") {
+        tokens.sort_by_key(|token| token.get("id").and_then(Value::as_u64));
+    }
+
+    let components = BEHAVIOR_SECTIONS
+        .iter()
+        .map(|section| (*section, digest(&document, &[section])))
+        .collect();
+    Ok(Fingerprint {
+        version: FINGERPRINT_VERSION,
+        tokenizer: digest(&document, &BEHAVIOR_SECTIONS),
+        components,
+        configuration: digest(&document, &CONFIGURATION_SECTIONS),
+    })
+}
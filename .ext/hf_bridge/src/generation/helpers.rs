@@ -32,6 +32,27 @@ pub(crate) fn collect_stop_sequences(map: &Map<String, Value>) -> Option<Vec<Str
     None
 }
 
+pub(crate) fn collect_eos_token_ids(map: &Map<String, Value>) -> Vec<u32> {
+    let Some(value) = get_property(map, "eos_token_id") else {
+        return Vec::new();
+    };
+
+    let candidates = match value {
+        Value::Array(items) => items.iter().filter_map(to_u64).collect(),
+        _ => to_u64(value).into_iter().collect::<Vec<_>>(),
+    };
+
+    let mut unique: Vec<u32> = Vec::with_capacity(candidates.len());
+    for id in candidates {
+        if let Ok(id) = u32::try_from(id) {
+            if !unique.contains(&id) {
+                unique.push(id);
+            }
+        }
+    }
+    unique
+}
+
 pub(crate) fn get_property<'a>(map: &'a Map<String, Value>, key: &str) -> Option<&'a Value> {
     map.get(key).or_else(|| {
         map.iter()
@@ -3,7 +3,7 @@ use serde_json::{Map, Value};
 mod helpers;
 mod models;
 
-use helpers::{collect_stop_sequences, extract_integer, extract_numeric};
+use helpers::{collect_eos_token_ids, collect_stop_sequences, extract_integer, extract_numeric};
 use models::{LogitsBinding, StoppingCriterion};
 
 const EPSILON: f64 = 1e-9;
@@ -64,6 +64,31 @@ pub fn plan_stopping_criteria(source: &str) -> Result<String, GenerationConfigEr
         .map_err(|err| GenerationConfigError::Serialize(err.to_string()))
 }
 
+/// Stop strings and EOS ids declared by a generation configuration.
+#[derive(Debug, Default)]
+pub(crate) struct StopConditions {
+    pub(crate) stop_strings: Vec<String>,
+    pub(crate) eos_token_ids: Vec<u32>,
+}
+
+pub(crate) fn collect_stop_conditions(
+    source: &str,
+) -> Result<StopConditions, GenerationConfigError> {
+    let parsed: Value = serde_json::from_str(source)
+        .map_err(|err| GenerationConfigError::Parse(err.to_string()))?;
+
+    let object = parsed.as_object().ok_or_else(|| {
+        GenerationConfigError::Parse(String::from(
+            "generation configuration must be a JSON object",
+        ))
+    })?;
+
+    Ok(StopConditions {
+        stop_strings: collect_stop_sequences(object).unwrap_or_default(),
+        eos_token_ids: collect_eos_token_ids(object),
+    })
+}
+
 pub(crate) fn build_logits_bindings(object: &Map<String, Value>) -> Vec<LogitsBinding> {
     let mut bindings: Vec<LogitsBinding> = Vec::new();
 
@@ -144,6 +169,10 @@ pub mod test_support {
         helpers::collect_stop_sequences(map)
     }
 
+    pub fn collect_eos_token_ids(map: &Map<String, Value>) -> Vec<u32> {
+        helpers::collect_eos_token_ids(map)
+    }
+
     pub fn convert_value_to_string(value: &Value) -> Option<String> {
         helpers::convert_value_to_string(value)
     }
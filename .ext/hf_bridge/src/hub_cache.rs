@@ -0,0 +1,288 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::directory::{
+    find_legacy_source, load_directory, DirectoryError, LoadedDirectory, TOKENIZER_FILE,
+};
+
+const DEFAULT_REVISION: &str = "main";
+pub(crate) const COMPANION_FILES: [&str; 4] = [
+    "tokenizer_config.json",
+    "special_tokens_map.json",
+    "added_tokens.json",
+    "generation_config.json",
+];
+
+#[derive(Debug)]
+pub(crate) enum HubCacheError {
+    InvalidRevision(String),
+    NotCached(String),
+    MissingFiles {
+        snapshot: PathBuf,
+        missing: Vec<String>,
+        absent_optional: Vec<String>,
+    },
+    Directory(DirectoryError),
+}
+
+impl HubCacheError {
+    pub(crate) fn into_message(self) -> String {
+        match self {
+            HubCacheError::InvalidRevision(revision) => {
+                format!("revision '{revision}' is not a valid ref name or commit hash")
+            }
+            HubCacheError::NotCached(reason) => reason,
+            HubCacheError::MissingFiles {
+                snapshot,
+                missing,
+                absent_optional,
+            } => {
+                let mut message = format!(
+                    "cached snapshot {} is missing {}",
+                    snapshot.display(),
+                    missing.join(", ")
+                );
+                if !absent_optional.is_empty() {
+                    message.push_str(&format!(
+                        " (optional files also absent: {})",
+                        absent_optional.join(", ")
+                    ));
+                }
+                message
+            }
+            HubCacheError::Directory(err) => err.into_message(),
+        }
+    }
+}
+
+/// Interprets `HF_HUB_OFFLINE` the way `huggingface_hub` does.
+pub(crate) fn is_truthy(value: Option<&str>) -> bool {
+    value.is_some_and(|value| {
+        matches!(
+            value.trim().to_ascii_lowercase().as_str(),
+            "1" | "on" | "yes" | "true"
+        )
+    })
+}
+
+pub(crate) fn offline_mode() -> bool {
+    is_truthy(std::env::var("HF_HUB_OFFLINE").ok().as_deref())
+}
+
+/// A Hugging Face hub cache laid out as `models--{org}--{name}/{refs,snapshots}`.
+#[derive(Clone, Debug)]
+pub(crate) struct HubCache {
+    root: PathBuf,
+}
+
+impl HubCache {
+    pub(crate) fn new(root: PathBuf) -> Self {
+        HubCache { root }
+    }
+
+    /// Resolves the cache root from `HF_HUB_CACHE` (or the legacy `HUGGINGFACE_HUB_CACHE`), then
+    /// `HF_HOME/hub`, then `$XDG_CACHE_HOME/huggingface/hub` and finally `~/.cache/huggingface/hub`.
+    pub(crate) fn from_env() -> Self {
+        Self::from_vars(|key| std::env::var(key).ok().filter(|value| !value.is_empty()))
+    }
+
+    pub(crate) fn from_vars(var: impl Fn(&str) -> Option<String>) -> Self {
+        if let Some(cache) = var("HF_HUB_CACHE").or_else(|| var("HUGGINGFACE_HUB_CACHE")) {
+            return HubCache::new(PathBuf::from(cache));
+        }
+
+        let home = var("HF_HOME").map(PathBuf::from).unwrap_or_else(|| {
+            let cache = var("XDG_CACHE_HOME").map(PathBuf::from).unwrap_or_else(|| {
+                let home = var("HOME")
+                    .or_else(|| var("USERPROFILE"))
+                    .unwrap_or_default();
+                Path::new(&home).join(".cache")
+            });
+            cache.join("huggingface")
+        });
+        HubCache::new(home.join("hub"))
+    }
+
+    pub(crate) fn repo_dir(&self, repo_id: &str) -> PathBuf {
+        self.root
+            .join(format!("models--{}", repo_id.replace('/', "--")))
+    }
+
+    /// Maps `revision` (a branch, tag, `refs/pr/N` or commit hash; `main` when absent) to the
+    /// commit hash whose snapshot is cached.
+    pub(crate) fn resolve_revision(
+        &self,
+        repo_id: &str,
+        revision: Option<&str>,
+    ) -> Result<String, HubCacheError> {
+        let revision = revision
+            .filter(|value| !value.is_empty())
+            .unwrap_or(DEFAULT_REVISION);
+        check_revision(revision)?;
+        let repo_dir = self.repo_dir(repo_id);
+        if !repo_dir.is_dir() {
+            return Err(HubCacheError::NotCached(format!(
+                "{repo_id} is not in the hub cache at {} (expected {})",
+                self.root.display(),
+                repo_dir.display()
+            )));
+        }
+
+        if is_commit_hash(revision) {
+            return Ok(revision.to_string());
+        }
+
+        // Pull request refs such as `refs/pr/1` are stored verbatim below `refs/`.
+        let ref_path = repo_dir.join("refs").join(revision);
+        match fs::read_to_string(&ref_path) {
+            Ok(commit) if is_commit_hash(commit.trim()) => Ok(commit.trim().to_string()),
+            Ok(commit) => Err(HubCacheError::NotCached(format!(
+                "ref '{revision}' of {repo_id} holds '{}', which is not a commit hash",
+                commit.trim()
+            ))),
+            Err(_) => Err(HubCacheError::NotCached(format!(
+                "revision '{revision}' of {repo_id} is not cached; cached refs: {}",
+                list_or_none(cached_refs(&repo_dir.join("refs")))
+            ))),
+        }
+    }
+
+    /// Returns the snapshot directory holding the files of `revision`.
+    pub(crate) fn snapshot(
+        &self,
+        repo_id: &str,
+        revision: Option<&str>,
+    ) -> Result<PathBuf, HubCacheError> {
+        let commit = self.resolve_revision(repo_id, revision)?;
+        let snapshots = self.repo_dir(repo_id).join("snapshots");
+        let snapshot = snapshots.join(&commit);
+        if snapshot.is_dir() {
+            return Ok(snapshot);
+        }
+
+        Err(HubCacheError::NotCached(format!(
+            "snapshot {commit} of {repo_id} is not cached; cached snapshots: {}",
+            list_or_none(directory_names(&snapshots))
+        )))
+    }
+
+    /// Returns the cached path of `filename` in the snapshot of `revision`.
+    pub(crate) fn file(
+        &self,
+        repo_id: &str,
+        revision: Option<&str>,
+        filename: &str,
+    ) -> Result<PathBuf, HubCacheError> {
+        let snapshot = self.snapshot(repo_id, revision)?;
+        let path = snapshot.join(filename);
+        if path.is_file() {
+            return Ok(path);
+        }
+
+        Err(HubCacheError::MissingFiles {
+            snapshot,
+            missing: vec![filename.to_string()],
+            absent_optional: Vec::new(),
+        })
+    }
+
+    /// Returns the snapshot of `revision` after checking it holds a tokenizer, either tokenizer.json
+    /// or a complete set of legacy vocabulary files. A vocabulary file without its companion (such
+    /// as vocab.json without merges.txt) reports the companion as missing.
+    pub(crate) fn tokenizer_snapshot(
+        &self,
+        repo_id: &str,
+        revision: Option<&str>,
+    ) -> Result<PathBuf, HubCacheError> {
+        let snapshot = self.snapshot(repo_id, revision)?;
+        let has = |name: &str| snapshot.join(name).is_file();
+        if has(TOKENIZER_FILE) {
+            return Ok(snapshot);
+        }
+        let missing = match find_legacy_source(&snapshot) {
+            Ok(_) => return Ok(snapshot),
+            Err(missing) if missing.is_empty() => vec![TOKENIZER_FILE.to_string()],
+            Err(missing) => missing,
+        };
+
+        let absent_optional = COMPANION_FILES
+            .iter()
+            .filter(|name| !has(name))
+            .map(|name| name.to_string())
+            .collect();
+        Err(HubCacheError::MissingFiles {
+            snapshot,
+            missing,
+            absent_optional,
+        })
+    }
+}
+
+pub(crate) fn is_commit_hash(revision: &str) -> bool {
+    revision.len() == 40 && revision.bytes().all(|byte| byte.is_ascii_hexdigit())
+}
+
+/// Rejects revisions that would not stay below `refs/` once joined onto it: empty, `.` and `..`
+/// components, absolute paths, and Windows separators or drive prefixes.
+pub(crate) fn check_revision(revision: &str) -> Result<(), HubCacheError> {
+    let valid = !revision.contains(['\\', ':'])
+        && revision
+            .split('/')
+            .all(|component| !matches!(component, "" | "." | ".."));
+    if valid {
+        Ok(())
+    } else {
+        Err(HubCacheError::InvalidRevision(revision.to_string()))
+    }
+}
+
+fn list_or_none(names: Vec<String>) -> String {
+    if names.is_empty() {
+        String::from("none")
+    } else {
+        names.join(", ")
+    }
+}
+
+fn directory_names(directory: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(directory)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+/// Lists ref files below `refs`, including nested ones such as `refs/pr/1`.
+fn cached_refs(refs: &Path) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut pending = vec![(refs.to_path_buf(), String::new())];
+    while let Some((directory, prefix)) = pending.pop() {
+        for name in directory_names(&directory) {
+            let path = directory.join(&name);
+            let qualified = format!("{prefix}{name}");
+            if path.is_dir() {
+                pending.push((path, format!("{qualified}/")));
+            } else {
+                names.push(qualified);
+            }
+        }
+    }
+    names.sort();
+    names
+}
+
+/// Loads a tokenizer and its configuration files from the cached snapshot of `revision`.
+pub(crate) fn load_from_cache(
+    cache: &HubCache,
+    repo_id: &str,
+    revision: Option<&str>,
+    apply_defaults: bool,
+) -> Result<LoadedDirectory, HubCacheError> {
+    let snapshot = cache.tokenizer_snapshot(repo_id, revision)?;
+    load_directory(&snapshot, apply_defaults).map_err(HubCacheError::Directory)
+}
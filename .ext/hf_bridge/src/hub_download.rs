@@ -0,0 +1,395 @@
+use std::os::raw::c_char;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::directory::{load_directory, LoadedDirectory};
+use crate::hub_cache::{check_revision, is_commit_hash, offline_mode, HubCache, HubCacheError};
+
+#[cfg(not(any(target_family = "wasm", target_os = "ios", target_os = "android")))]
+use crate::directory::{CHAT_TEMPLATE_FILE, TOKENIZER_FILE};
+#[cfg(not(any(target_family = "wasm", target_os = "ios", target_os = "android")))]
+use crate::hub_cache::COMPANION_FILES;
+#[cfg(not(any(target_family = "wasm", target_os = "ios", target_os = "android")))]
+use std::fs;
+#[cfg(not(any(target_family = "wasm", target_os = "ios", target_os = "android")))]
+use std::path::Path;
+
+const DEFAULT_REVISION: &str = "main";
+#[cfg(not(any(target_family = "wasm", target_os = "ios", target_os = "android")))]
+const DEFAULT_ENDPOINT: &str = "https://huggingface.co";
+#[cfg(not(any(target_family = "wasm", target_os = "ios", target_os = "android")))]
+const MAX_HUB_REDIRECTS: usize = 5;
+
+/// Caller-side settings for `tokenizers_from_pretrained_with_options`. Null strings and a zero
+/// `timeout_ms` keep the defaults: the `HF_ENDPOINT` hub, the environment's cache, `main` and no
+/// timeout.
+#[repr(C)]
+pub struct CHubOptions {
+    pub endpoint: *const c_char,
+    pub cache_dir: *const c_char,
+    pub revision: *const c_char,
+    pub auth_token: *const c_char,
+    pub user_agent: *const c_char,
+    pub timeout_ms: u64,
+    pub local_files_only: bool,
+    pub force_download: bool,
+    pub apply_defaults: bool,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct HubOptions {
+    pub(crate) endpoint: Option<String>,
+    pub(crate) cache_dir: Option<PathBuf>,
+    pub(crate) revision: Option<String>,
+    pub(crate) auth_token: Option<String>,
+    pub(crate) user_agent: Option<String>,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) local_files_only: bool,
+    pub(crate) force_download: bool,
+}
+
+#[derive(Debug)]
+pub(crate) enum DownloadError {
+    Cache(HubCacheError),
+    Http {
+        url: String,
+        status: u16,
+    },
+    Transport {
+        url: String,
+        reason: String,
+    },
+    Io(String),
+    #[cfg(any(target_family = "wasm", target_os = "ios", target_os = "android"))]
+    Unsupported,
+}
+
+impl DownloadError {
+    pub(crate) fn into_message(self) -> String {
+        match self {
+            DownloadError::Cache(err) => err.into_message(),
+            DownloadError::Http { url, status } => {
+                let hint = match status {
+                    401 | 403 => " (the repository is gated or private, or the token is invalid)",
+                    404 => " (repository, revision or file not found)",
+                    _ => "",
+                };
+                format!("{url} returned HTTP {status}{hint}")
+            }
+            DownloadError::Transport { url, reason } => format!("request to {url} failed: {reason}"),
+            DownloadError::Io(reason) => reason,
+            #[cfg(any(target_family = "wasm", target_os = "ios", target_os = "android"))]
+            DownloadError::Unsupported => String::from(
+                "downloads are not supported on this platform; set local_files_only to read the hub cache",
+            ),
+        }
+    }
+}
+
+/// Resolves the snapshot of `repo_id` holding tokenizer.json and its companion configuration files,
+/// downloading into the hub cache what is not there yet.
+///
+/// With `local_files_only` or `HF_HUB_OFFLINE` only the cache is read. A commit-hash revision whose
+/// snapshot is cached is used without contacting the hub, and a hub that cannot be reached falls
+/// back to the cached snapshot of the requested revision, as `huggingface_hub` does.
+pub(crate) fn fetch_snapshot(
+    repo_id: &str,
+    options: &HubOptions,
+) -> Result<PathBuf, DownloadError> {
+    let cache = options
+        .cache_dir
+        .clone()
+        .map(HubCache::new)
+        .unwrap_or_else(HubCache::from_env);
+    let revision = options
+        .revision
+        .as_deref()
+        .filter(|value| !value.is_empty());
+    if let Some(revision) = revision {
+        check_revision(revision).map_err(DownloadError::Cache)?;
+    }
+
+    if options.local_files_only || offline_mode() {
+        return cache
+            .tokenizer_snapshot(repo_id, revision)
+            .map_err(DownloadError::Cache);
+    }
+
+    if !options.force_download && revision.is_some_and(is_commit_hash) {
+        if let Ok(snapshot) = cache.tokenizer_snapshot(repo_id, revision) {
+            return Ok(snapshot);
+        }
+    }
+
+    download_snapshot(
+        &cache,
+        repo_id,
+        revision.unwrap_or(DEFAULT_REVISION),
+        options,
+    )
+}
+
+/// Fetches the snapshot of `repo_id` and loads it as `tokenizers_load_directory` would.
+pub(crate) fn fetch_tokenizer(
+    repo_id: &str,
+    options: &HubOptions,
+    apply_defaults: bool,
+) -> Result<LoadedDirectory, DownloadError> {
+    let snapshot = fetch_snapshot(repo_id, options)?;
+    load_directory(&snapshot, apply_defaults)
+        .map_err(|err| DownloadError::Cache(HubCacheError::Directory(err)))
+}
+
+#[cfg(any(target_family = "wasm", target_os = "ios", target_os = "android"))]
+fn download_snapshot(
+    _cache: &HubCache,
+    _repo_id: &str,
+    _revision: &str,
+    _options: &HubOptions,
+) -> Result<PathBuf, DownloadError> {
+    Err(DownloadError::Unsupported)
+}
+
+/// Downloads tokenizer.json, then the companion files of the same commit, into
+/// `snapshots/<commit>/` and records `refs/<revision>`. Files are written into the snapshot
+/// directly, the layout `huggingface_hub` uses where symlinks are unavailable.
+#[cfg(not(any(target_family = "wasm", target_os = "ios", target_os = "android")))]
+fn download_snapshot(
+    cache: &HubCache,
+    repo_id: &str,
+    revision: &str,
+    options: &HubOptions,
+) -> Result<PathBuf, DownloadError> {
+    let client = HubClient::new(options);
+    let tokenizer = match client.metadata(repo_id, revision, TOKENIZER_FILE) {
+        Ok(value) => value,
+        Err(err @ DownloadError::Transport { .. }) if !options.force_download => {
+            return cache
+                .tokenizer_snapshot(repo_id, Some(revision))
+                .map_err(|_| err);
+        }
+        Err(err) => return Err(err),
+    };
+
+    let repo_dir = cache.repo_dir(repo_id);
+    let snapshot = repo_dir.join("snapshots").join(&tokenizer.commit);
+    if revision != tokenizer.commit {
+        write_file(
+            &repo_dir.join("refs").join(revision),
+            tokenizer.commit.as_bytes(),
+        )?;
+    }
+    client.fetch(&tokenizer, &snapshot.join(TOKENIZER_FILE), options)?;
+
+    // Companions are requested at the resolved commit so every file comes from the same snapshot.
+    for name in COMPANION_FILES.iter().chain([CHAT_TEMPLATE_FILE].iter()) {
+        match client.metadata(repo_id, &tokenizer.commit, name) {
+            Ok(file) => client.fetch(&file, &snapshot.join(name), options)?,
+            Err(DownloadError::Http { status: 404, .. }) => continue,
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(snapshot)
+}
+
+#[cfg(not(any(target_family = "wasm", target_os = "ios", target_os = "android")))]
+fn write_file(path: &Path, contents: &[u8]) -> Result<(), DownloadError> {
+    let io_error = |err: std::io::Error| {
+        DownloadError::Io(format!("failed to write {}: {err}", path.display()))
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(io_error)?;
+    }
+    fs::write(path, contents).map_err(io_error)
+}
+
+/// Where a hub file resolves to: the commit it belongs to and the URL serving its content.
+#[cfg(not(any(target_family = "wasm", target_os = "ios", target_os = "android")))]
+struct RemoteFile {
+    commit: String,
+    location: String,
+}
+
+#[cfg(not(any(target_family = "wasm", target_os = "ios", target_os = "android")))]
+struct HubClient {
+    endpoint: String,
+    auth_token: Option<String>,
+    metadata_agent: ureq::Agent,
+    download_agent: ureq::Agent,
+}
+
+#[cfg(not(any(target_family = "wasm", target_os = "ios", target_os = "android")))]
+impl HubClient {
+    fn new(options: &HubOptions) -> Self {
+        let endpoint = options
+            .endpoint
+            .clone()
+            .filter(|value| !value.is_empty())
+            .or_else(|| {
+                std::env::var("HF_ENDPOINT")
+                    .ok()
+                    .filter(|value| !value.is_empty())
+            })
+            .unwrap_or_else(|| DEFAULT_ENDPOINT.to_string());
+        let user_agent = options
+            .user_agent
+            .clone()
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| format!("tokenx-bridge/{}", env!("CARGO_PKG_VERSION")));
+        let auth_token = options
+            .auth_token
+            .clone()
+            .filter(|value| !value.is_empty())
+            .or_else(|| {
+                std::env::var("HF_TOKEN")
+                    .ok()
+                    .filter(|value| !value.is_empty())
+            });
+
+        let builder = || {
+            let builder = ureq::AgentBuilder::new().user_agent(&user_agent);
+            match options.timeout {
+                Some(timeout) => builder.timeout(timeout),
+                None => builder,
+            }
+        };
+        HubClient {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            auth_token,
+            metadata_agent: builder().redirects(0).build(),
+            download_agent: builder()
+                .redirect_auth_headers(ureq::RedirectAuthHeaders::SameHost)
+                .build(),
+        }
+    }
+
+    fn send(
+        &self,
+        agent: &ureq::Agent,
+        method: &str,
+        url: &str,
+    ) -> Result<ureq::Response, DownloadError> {
+        let mut request = agent.request(method, url);
+        // Storage URLs live on another host (the CDN behind LFS files); the token is for the hub.
+        if let Some(token) = self
+            .auth_token
+            .as_ref()
+            .filter(|_| origin(url) == origin(&self.endpoint))
+        {
+            request = request.set("Authorization", &format!("Bearer {token}"));
+        }
+        match request.call() {
+            Ok(response) => Ok(response),
+            Err(ureq::Error::Status(status, _)) => Err(DownloadError::Http {
+                url: url.to_string(),
+                status,
+            }),
+            Err(ureq::Error::Transport(err)) => Err(DownloadError::Transport {
+                url: url.to_string(),
+                reason: err.to_string(),
+            }),
+        }
+    }
+
+    /// Issues a `HEAD` for `filename` the way `huggingface_hub` does: relative redirects (renamed
+    /// repositories) are followed on the hub, while an absolute one names the storage URL of the
+    /// file and is only recorded.
+    fn metadata(
+        &self,
+        repo_id: &str,
+        revision: &str,
+        filename: &str,
+    ) -> Result<RemoteFile, DownloadError> {
+        // Revisions such as `refs/pr/1` are sent as a single path segment.
+        let mut url = format!(
+            "{}/{repo_id}/resolve/{}/{filename}",
+            self.endpoint,
+            revision.replace('/', "%2F")
+        );
+        let mut commit = None;
+        for _ in 0..MAX_HUB_REDIRECTS {
+            let response = self.send(&self.metadata_agent, "HEAD", &url)?;
+            if let Some(value) = response.header("x-repo-commit") {
+                commit = Some(value.to_string());
+            }
+            let redirect = (300..400).contains(&response.status());
+            match response.header("location") {
+                Some(location) if redirect && location.starts_with('/') => {
+                    url = format!("{}{location}", origin(&self.endpoint));
+                }
+                Some(location) if redirect => {
+                    return self.remote_file(&url, revision, commit, location.to_string());
+                }
+                _ => return self.remote_file(&url, revision, commit, url.clone()),
+            }
+        }
+
+        Err(DownloadError::Transport {
+            url,
+            reason: format!("more than {MAX_HUB_REDIRECTS} redirects"),
+        })
+    }
+
+    fn remote_file(
+        &self,
+        url: &str,
+        revision: &str,
+        commit: Option<String>,
+        location: String,
+    ) -> Result<RemoteFile, DownloadError> {
+        let commit = commit
+            .or_else(|| is_commit_hash(revision).then(|| revision.to_string()))
+            .ok_or_else(|| DownloadError::Transport {
+                url: url.to_string(),
+                reason: String::from("response carries no X-Repo-Commit header"),
+            })?;
+        // The commit names the snapshot directory, so anything but a hash could escape the cache.
+        if !is_commit_hash(&commit) {
+            return Err(DownloadError::Transport {
+                url: url.to_string(),
+                reason: format!("response carries invalid X-Repo-Commit header '{commit}'"),
+            });
+        }
+        Ok(RemoteFile { commit, location })
+    }
+
+    /// Downloads `file` to `path` unless it is already there and no re-download was requested.
+    fn fetch(
+        &self,
+        file: &RemoteFile,
+        path: &Path,
+        options: &HubOptions,
+    ) -> Result<(), DownloadError> {
+        if path.is_file() && !options.force_download {
+            return Ok(());
+        }
+
+        let response = self.send(&self.download_agent, "GET", &file.location)?;
+        let mut contents = Vec::new();
+        std::io::Read::read_to_end(&mut response.into_reader(), &mut contents).map_err(|err| {
+            DownloadError::Transport {
+                url: file.location.clone(),
+                reason: err.to_string(),
+            }
+        })?;
+
+        // Write next to the target first so an interrupted download never looks complete.
+        let mut partial = path.as_os_str().to_owned();
+        partial.push(".incomplete");
+        let partial = PathBuf::from(partial);
+        write_file(&partial, &contents)?;
+        fs::rename(&partial, path).map_err(|err| {
+            DownloadError::Io(format!("failed to move {}: {err}", partial.display()))
+        })
+    }
+}
+
+/// Returns the `scheme://host[:port]` part of `url`.
+#[cfg(not(any(target_family = "wasm", target_os = "ios", target_os = "android")))]
+fn origin(url: &str) -> &str {
+    let host_start = url.find("://").map_or(0, |index| index + 3);
+    match url[host_start..].find('/') {
+        Some(index) => &url[..host_start + index],
+        None => url,
+    }
+}
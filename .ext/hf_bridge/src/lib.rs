@@ -1,12 +1,38 @@
+pub(crate) mod added_tokens;
+pub(crate) mod batch;
+pub(crate) mod batch_plan;
 pub(crate) mod chat;
+pub(crate) mod chunking;
+pub(crate) mod components;
+pub(crate) mod convert;
+pub(crate) mod decode_spans;
+pub(crate) mod decode_stream;
+pub(crate) mod directory;
 pub(crate) mod encoding;
 pub(crate) mod error;
 pub mod ffi;
+pub(crate) mod fingerprint;
 pub mod generation;
+pub(crate) mod hub_cache;
+pub(crate) mod hub_download;
+pub(crate) mod offsets;
+pub(crate) mod stop_matcher;
+pub(crate) mod tensor;
+pub(crate) mod text_truncation;
+pub(crate) mod tiktoken;
+pub(crate) mod token_bytes;
 pub(crate) mod tokenizer;
+pub(crate) mod trace;
+pub(crate) mod vocab;
 
+pub use added_tokens::CAddedToken;
+pub use batch_plan::CBatchPlan;
+pub use decode_stream::CDecodeStream;
 pub use encoding::{CEncoding, CEncodingNumericDest, CEncodingOffset};
 pub use error::tokenizers_get_last_error;
+pub use hub_download::CHubOptions;
+pub use stop_matcher::CStopMatcher;
+pub use tensor::CBatchTensorDest;
 pub use tokenizer::CTokenizer;
 
 #[doc(hidden)]
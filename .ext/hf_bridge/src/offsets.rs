@@ -0,0 +1,73 @@
+use std::os::raw::c_int;
+
+const OFFSET_MODE_ERROR: &str = "unknown offset mode";
+
+/// Unit in which encoding offsets are reported back to the caller.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum OffsetMode {
+    Bytes,
+    Chars,
+    Utf16,
+}
+
+impl OffsetMode {
+    pub(crate) fn from_raw(value: c_int) -> Result<Self, &'static str> {
+        match value {
+            0 => Ok(OffsetMode::Bytes),
+            1 => Ok(OffsetMode::Chars),
+            2 => Ok(OffsetMode::Utf16),
+            _ => Err(OFFSET_MODE_ERROR),
+        }
+    }
+
+    fn units_for(self, ch: char) -> u32 {
+        match self {
+            OffsetMode::Bytes => ch.len_utf8() as u32,
+            OffsetMode::Chars => 1,
+            OffsetMode::Utf16 => ch.len_utf16() as u32,
+        }
+    }
+}
+
+/// Converts UTF-8 byte offsets within `text` into the requested unit.
+pub(crate) struct OffsetConverter<'a> {
+    text: &'a str,
+    mode: OffsetMode,
+    units: Vec<u32>,
+}
+
+impl<'a> OffsetConverter<'a> {
+    pub(crate) fn new(text: &'a str, mode: OffsetMode) -> Self {
+        let units = if mode == OffsetMode::Bytes {
+            Vec::new()
+        } else {
+            let mut table = vec![0u32; text.len() + 1];
+            let mut position = 0u32;
+            for (index, ch) in text.char_indices() {
+                let width = ch.len_utf8();
+                table[index..index + width].fill(position);
+                position += mode.units_for(ch);
+            }
+            table[text.len()] = position;
+            table
+        };
+
+        Self { text, mode, units }
+    }
+
+    /// Maps a byte span to the configured unit; positions inside a character widen to cover it.
+    pub(crate) fn convert(&self, start: usize, end: usize) -> (u32, u32) {
+        if self.mode == OffsetMode::Bytes {
+            return (start as u32, end as u32);
+        }
+
+        let limit = self.text.len();
+        let start = start.min(limit);
+        let mut end = end.min(limit).max(start);
+        while !self.text.is_char_boundary(end) {
+            end += 1;
+        }
+
+        (self.units[start], self.units[end])
+    }
+}
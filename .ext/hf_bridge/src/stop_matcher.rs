@@ -0,0 +1,257 @@
+use std::os::raw::c_int;
+
+use tokenizers::Tokenizer;
+
+use crate::decode_stream::CDecodeStream;
+
+/// What the caller should do with the text returned from a feed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum StopAction {
+    /// Everything fed so far has been released.
+    Emit,
+    /// Part of the input is held back because it may be the start of a stop sequence.
+    HoldBack,
+    /// A stop condition matched; the returned text ends right before the match.
+    Stop,
+}
+
+impl StopAction {
+    pub(crate) fn as_raw(self) -> c_int {
+        match self {
+            StopAction::Emit => 0,
+            StopAction::HoldBack => 1,
+            StopAction::Stop => 2,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct StopStep {
+    pub(crate) action: StopAction,
+    pub(crate) text: String,
+}
+
+/// Stateful matcher for stop strings, stop token-id sequences and EOS ids across token boundaries.
+pub struct CStopMatcher {
+    stop_strings: Vec<String>,
+    stop_sequences: Vec<Vec<u32>>,
+    eos_token_ids: Vec<u32>,
+    stream: CDecodeStream,
+    held_text: String,
+    held_ids: Vec<u32>,
+    stopped: bool,
+}
+
+impl CStopMatcher {
+    pub(crate) fn new(
+        stop_strings: Vec<String>,
+        stop_sequences: Vec<Vec<u32>>,
+        eos_token_ids: Vec<u32>,
+        skip_special_tokens: bool,
+    ) -> Self {
+        Self {
+            stop_strings: stop_strings
+                .into_iter()
+                .filter(|value| !value.is_empty())
+                .collect(),
+            stop_sequences: stop_sequences
+                .into_iter()
+                .filter(|sequence| !sequence.is_empty())
+                .collect(),
+            eos_token_ids,
+            stream: CDecodeStream::new(skip_special_tokens),
+            held_text: String::new(),
+            held_ids: Vec::new(),
+            stopped: false,
+        }
+    }
+
+    /// Feeds already-decoded text.
+    pub(crate) fn feed_text(&mut self, fragment: &str) -> StopStep {
+        if self.stopped {
+            return self.stopped_step();
+        }
+
+        let mut released = String::new();
+        let action = self.push_text(fragment, &mut released);
+        StopStep {
+            action,
+            text: released,
+        }
+    }
+
+    /// Feeds generated token ids, decoding them incrementally with `tokenizer`.
+    pub(crate) fn feed_tokens(
+        &mut self,
+        tokenizer: &Tokenizer,
+        ids: &[u32],
+    ) -> Result<StopStep, String> {
+        if self.stopped {
+            return Ok(self.stopped_step());
+        }
+
+        let mut released = String::new();
+        for &id in ids {
+            if self.eos_token_ids.contains(&id) {
+                let pending = std::mem::take(&mut self.held_ids);
+                return self.stop_at_token(tokenizer, &pending, released);
+            }
+
+            self.held_ids.push(id);
+            if let Some(length) = self.matched_sequence_length() {
+                let keep = self.held_ids.len() - length;
+                let pending: Vec<u32> = self.held_ids.drain(..).take(keep).collect();
+                return self.stop_at_token(tokenizer, &pending, released);
+            }
+
+            let keep = self.held_ids.len() - self.sequence_prefix_length();
+            let pending: Vec<u32> = self.held_ids.drain(..keep).collect();
+            if self.push_ids(tokenizer, &pending, &mut released)? == StopAction::Stop {
+                return Ok(StopStep {
+                    action: StopAction::Stop,
+                    text: released,
+                });
+            }
+        }
+
+        Ok(StopStep {
+            action: self.pending_action(),
+            text: released,
+        })
+    }
+
+    /// Releases everything still held back once generation ends without a stop, then resets the matcher.
+    pub(crate) fn finish(&mut self, tokenizer: Option<&Tokenizer>) -> Result<String, String> {
+        let mut released = String::new();
+        if !self.stopped {
+            if let Some(tokenizer) = tokenizer {
+                let pending = std::mem::take(&mut self.held_ids);
+                if let Some(text) = self.stream.step(tokenizer, &pending)? {
+                    self.held_text.push_str(&text);
+                }
+                self.held_text.push_str(&self.stream.flush(tokenizer)?);
+            }
+            released = std::mem::take(&mut self.held_text);
+        }
+
+        self.reset();
+        Ok(released)
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.stream.reset();
+        self.held_text.clear();
+        self.held_ids.clear();
+        self.stopped = false;
+    }
+
+    fn stopped_step(&self) -> StopStep {
+        StopStep {
+            action: StopAction::Stop,
+            text: String::new(),
+        }
+    }
+
+    /// Holds back while text, stop-sequence ids or ids the decode stream has not turned into text yet
+    /// are pending.
+    fn pending_action(&self) -> StopAction {
+        if self.held_text.is_empty() && self.held_ids.is_empty() && !self.stream.has_pending() {
+            StopAction::Emit
+        } else {
+            StopAction::HoldBack
+        }
+    }
+
+    /// Stops at an EOS id or stop token sequence. No later input can complete a stop string, so
+    /// bytes still pending in the decode stream and text held back as a stop-string prefix are
+    /// released instead of dropped.
+    fn stop_at_token(
+        &mut self,
+        tokenizer: &Tokenizer,
+        pending: &[u32],
+        mut released: String,
+    ) -> Result<StopStep, String> {
+        if self.push_ids(tokenizer, pending, &mut released)? != StopAction::Stop {
+            let flushed = self.stream.flush(tokenizer)?;
+            if self.push_text(&flushed, &mut released) != StopAction::Stop {
+                released.push_str(&std::mem::take(&mut self.held_text));
+            }
+        }
+
+        self.stopped = true;
+        Ok(StopStep {
+            action: StopAction::Stop,
+            text: released,
+        })
+    }
+
+    fn push_ids(
+        &mut self,
+        tokenizer: &Tokenizer,
+        ids: &[u32],
+        released: &mut String,
+    ) -> Result<StopAction, String> {
+        match self.stream.step(tokenizer, ids)? {
+            Some(text) => Ok(self.push_text(&text, released)),
+            None => Ok(self.pending_action()),
+        }
+    }
+
+    /// Appends `fragment` to the held text and moves every byte that can no longer start a stop string into `released`.
+    fn push_text(&mut self, fragment: &str, released: &mut String) -> StopAction {
+        self.held_text.push_str(fragment);
+
+        let earliest = self
+            .stop_strings
+            .iter()
+            .filter_map(|stop| self.held_text.find(stop.as_str()))
+            .min();
+        if let Some(start) = earliest {
+            released.push_str(&self.held_text[..start]);
+            self.held_text.clear();
+            self.stopped = true;
+            return StopAction::Stop;
+        }
+
+        let keep = self.held_text.len() - self.text_prefix_length();
+        released.push_str(&self.held_text[..keep]);
+        self.held_text.drain(..keep);
+        self.pending_action()
+    }
+
+    /// Length in bytes of the longest held suffix that begins some stop string.
+    fn text_prefix_length(&self) -> usize {
+        let text = self.held_text.as_str();
+        (1..=text.len())
+            .rev()
+            .filter(|length| text.is_char_boundary(text.len() - length))
+            .find(|length| {
+                let suffix = &text[text.len() - length..];
+                self.stop_strings
+                    .iter()
+                    .any(|stop| stop.starts_with(suffix))
+            })
+            .unwrap_or(0)
+    }
+
+    fn matched_sequence_length(&self) -> Option<usize> {
+        self.stop_sequences
+            .iter()
+            .find(|sequence| self.held_ids.ends_with(sequence))
+            .map(Vec::len)
+    }
+
+    /// Length of the longest held id suffix that is a proper prefix of some stop sequence.
+    fn sequence_prefix_length(&self) -> usize {
+        let ids = self.held_ids.as_slice();
+        (1..=ids.len())
+            .rev()
+            .find(|length| {
+                let suffix = &ids[ids.len() - length..];
+                self.stop_sequences
+                    .iter()
+                    .any(|sequence| sequence.len() > *length && sequence.starts_with(suffix))
+            })
+            .unwrap_or(0)
+    }
+}
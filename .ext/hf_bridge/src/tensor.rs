@@ -0,0 +1,150 @@
+use std::os::raw::{c_int, c_void};
+
+use tokenizers::Encoding;
+
+// Message tails prefixed with the name of the FFI function that failed.
+const TENSOR_CAPACITY_ERROR: &str = "received insufficient destination capacity";
+const TENSOR_NULL_ERROR: &str = "received null destination buffer";
+const TENSOR_RAGGED_ERROR: &str =
+    "produced rows of different lengths; configure fixed or batch-longest padding";
+const TENSOR_ELEMENT_ERROR: &str = "unknown element type";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum TensorElementType {
+    Int64,
+    Int32,
+}
+
+impl TensorElementType {
+    pub(crate) fn from_raw(value: c_int, operation: &str) -> Result<Self, String> {
+        match value {
+            0 => Ok(TensorElementType::Int64),
+            1 => Ok(TensorElementType::Int32),
+            _ => Err(format!("{operation} {TENSOR_ELEMENT_ERROR}")),
+        }
+    }
+}
+
+/// Row-major `[batch, seq_len]` destinations; `capacity` is the element count of each buffer.
+#[repr(C)]
+pub struct CBatchTensorDest {
+    pub input_ids: *mut c_void,
+    pub attention_mask: *mut c_void,
+    pub token_type_ids: *mut c_void,
+    pub position_ids: *mut c_void,
+    pub capacity: usize,
+}
+
+impl CBatchTensorDest {
+    pub(crate) fn validate(&self, operation: &str) -> Result<(), String> {
+        if self.input_ids.is_null() || self.attention_mask.is_null() {
+            Err(format!("{operation} {TENSOR_NULL_ERROR}"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Returns the shared row length of `encodings`, or an error when padding left them ragged.
+pub(crate) fn uniform_sequence_length(
+    encodings: &[Encoding],
+    operation: &str,
+) -> Result<usize, String> {
+    let Some(first) = encodings.first() else {
+        return Ok(0);
+    };
+
+    let length = first.len();
+    if encodings.iter().any(|encoding| encoding.len() != length) {
+        Err(format!("{operation} {TENSOR_RAGGED_ERROR}"))
+    } else {
+        Ok(length)
+    }
+}
+
+/// Writes the padded rows into `destination`, returning the number of rows written.
+pub(crate) fn fill_tensors(
+    encodings: &[Encoding],
+    sequence_length: usize,
+    element_type: TensorElementType,
+    destination: &CBatchTensorDest,
+    operation: &str,
+) -> Result<usize, String> {
+    destination.validate(operation)?;
+
+    let required = encodings.len().saturating_mul(sequence_length);
+    if required > destination.capacity {
+        return Err(format!("{operation} {TENSOR_CAPACITY_ERROR}"));
+    }
+
+    for (row, encoding) in encodings.iter().enumerate() {
+        let base = row * sequence_length;
+        write_row(
+            destination.input_ids,
+            element_type,
+            base,
+            encoding.get_ids(),
+        );
+        write_row(
+            destination.attention_mask,
+            element_type,
+            base,
+            encoding.get_attention_mask(),
+        );
+
+        if !destination.token_type_ids.is_null() {
+            write_row(
+                destination.token_type_ids,
+                element_type,
+                base,
+                encoding.get_type_ids(),
+            );
+        }
+
+        if !destination.position_ids.is_null() {
+            let positions = position_ids(encoding.get_attention_mask());
+            write_row(destination.position_ids, element_type, base, &positions);
+        }
+    }
+
+    Ok(encodings.len())
+}
+
+/// Mirrors the `transformers` decoder convention: positions count attended tokens only,
+/// so left padding does not shift them, and padded slots are filled with `1`.
+pub(crate) fn position_ids(attention_mask: &[u32]) -> Vec<u32> {
+    let mut next = 0u32;
+    attention_mask
+        .iter()
+        .map(|mask| {
+            if *mask == 0 {
+                1
+            } else {
+                let current = next;
+                next += 1;
+                current
+            }
+        })
+        .collect()
+}
+
+fn write_row(destination: *mut c_void, element_type: TensorElementType, base: usize, row: &[u32]) {
+    match element_type {
+        TensorElementType::Int64 => {
+            let typed = destination.cast::<i64>();
+            for (index, value) in row.iter().enumerate() {
+                unsafe {
+                    typed.add(base + index).write(i64::from(*value));
+                }
+            }
+        }
+        TensorElementType::Int32 => {
+            let typed = destination.cast::<i32>();
+            for (index, value) in row.iter().enumerate() {
+                unsafe {
+                    typed.add(base + index).write(*value as i32);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use std::os::raw::c_int;
+
+use tokenizers::Tokenizer;
+
+#[derive(Debug)]
+pub(crate) enum TextTruncationError {
+    Encode(String),
+    OverBudget { overhead: usize, max_tokens: usize },
+}
+
+impl TextTruncationError {
+    pub(crate) fn into_message(self) -> String {
+        match self {
+            TextTruncationError::Encode(reason) => format!("failed to encode text: {reason}"),
+            TextTruncationError::OverBudget {
+                overhead,
+                max_tokens,
+            } => format!(
+                "special tokens alone take {overhead} tokens, more than the budget of {max_tokens}"
+            ),
+        }
+    }
+}
+
+/// Which part of the text survives when it exceeds the budget.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum TextTruncationStrategy {
+    Head,
+    Tail,
+    Middle,
+}
+
+impl TextTruncationStrategy {
+    pub(crate) fn from_raw(value: c_int) -> Result<Self, &'static str> {
+        match value {
+            0 => Ok(TextTruncationStrategy::Head),
+            1 => Ok(TextTruncationStrategy::Tail),
+            2 => Ok(TextTruncationStrategy::Middle),
+            _ => Err("unknown text truncation strategy"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct TruncatedText {
+    pub(crate) text: String,
+    pub(crate) token_count: usize,
+    /// Set when a non-empty marker was requested but left out because it alone exceeds the budget.
+    pub(crate) marker_dropped: bool,
+}
+
+/// Cuts `text` at token boundaries so that re-encoding the result fits `max_tokens`.
+/// The cut uses the original string, so whitespace inside the kept spans is preserved
+/// byte for byte. `tokenizer` must not truncate or pad (see `CTokenizer::unconstrained`).
+pub(crate) fn truncate_text(
+    tokenizer: &Tokenizer,
+    text: &str,
+    max_tokens: usize,
+    strategy: TextTruncationStrategy,
+    marker: Option<&str>,
+    add_special_tokens: bool,
+) -> Result<TruncatedText, TextTruncationError> {
+    let count = |value: &str| {
+        tokenizer
+            .encode_fast(value, add_special_tokens)
+            .map(|encoding| encoding.len())
+            .map_err(|err| TextTruncationError::Encode(err.to_string()))
+    };
+
+    let original_count = count(text)?;
+    if original_count <= max_tokens {
+        return Ok(TruncatedText {
+            text: text.to_string(),
+            token_count: original_count,
+            marker_dropped: false,
+        });
+    }
+
+    let encoding = tokenizer
+        .encode(text, false)
+        .map_err(|err| TextTruncationError::Encode(err.to_string()))?;
+    let offsets = encoding.get_offsets();
+    let total = offsets.len();
+    let overhead = original_count - total;
+    if overhead > max_tokens {
+        return Err(TextTruncationError::OverBudget {
+            overhead,
+            max_tokens,
+        });
+    }
+
+    let marker = marker.filter(|value| !value.is_empty());
+    let marker_tokens = match marker {
+        Some(value) => tokenizer
+            .encode_fast(value, false)
+            .map(|encoding| encoding.len())
+            .map_err(|err| TextTruncationError::Encode(err.to_string()))?,
+        None => 0,
+    };
+    let marker_dropped = marker.is_some() && marker_tokens + overhead > max_tokens;
+    let marker = marker.filter(|_| !marker_dropped);
+    let marker_tokens = if marker.is_some() { marker_tokens } else { 0 };
+
+    let upper = max_tokens
+        .saturating_sub(overhead)
+        .saturating_sub(marker_tokens)
+        .min(total);
+    let measure = |keep: usize| -> Result<TruncatedText, TextTruncationError> {
+        let candidate = assemble(text, offsets, keep, strategy, marker);
+        let token_count = count(&candidate)?;
+        Ok(TruncatedText {
+            text: candidate,
+            token_count,
+            marker_dropped,
+        })
+    };
+
+    // Re-tokenizing at the cut can merge or split tokens, so `upper` may still overshoot; search
+    // for the largest span that fits instead of dropping one token at a time.
+    let best = measure(upper)?;
+    if best.token_count <= max_tokens || upper == 0 {
+        return Ok(best);
+    }
+
+    let (mut fits, mut overshoots) = (0, upper);
+    let mut best = measure(0)?;
+    while overshoots - fits > 1 {
+        let middle = fits + (overshoots - fits) / 2;
+        let candidate = measure(middle)?;
+        if candidate.token_count <= max_tokens {
+            fits = middle;
+            best = candidate;
+        } else {
+            overshoots = middle;
+        }
+    }
+    Ok(best)
+}
+
+fn assemble(
+    text: &str,
+    offsets: &[(usize, usize)],
+    keep: usize,
+    strategy: TextTruncationStrategy,
+    marker: Option<&str>,
+) -> String {
+    let total = offsets.len();
+    let head = |tokens: usize| {
+        if tokens == 0 {
+            ""
+        } else {
+            &text[..offsets[tokens - 1].1]
+        }
+    };
+    let tail = |tokens: usize| {
+        if tokens == 0 {
+            ""
+        } else {
+            &text[offsets[total - tokens].0..]
+        }
+    };
+    let marker = marker.unwrap_or("");
+
+    match strategy {
+        TextTruncationStrategy::Head => format!("{}{marker}", head(keep)),
+        TextTruncationStrategy::Tail => format!("{marker}{}", tail(keep)),
+        TextTruncationStrategy::Middle => {
+            let head_tokens = keep.div_ceil(2);
+            format!("{}{marker}{}", head(head_tokens), tail(keep - head_tokens))
+        }
+    }
+}
@@ -0,0 +1,279 @@
+use std::os::raw::c_int;
+use std::str::FromStr;
+
+use ahash::AHashMap;
+use serde_json::{json, Map, Value};
+use tokenizers::Tokenizer;
+
+use crate::token_bytes::bytes_to_unicode;
+
+const CL100K_PATTERN: &str = r"(?i:'s|'t|'re|'ve|'m|'ll|'d)|[^\r\n\p{L}\p{N}]?\p{L}+|\p{N}{1,3}| ?[^\s\p{L}\p{N}]+[\r\n]*|\s*[\r\n]+|\s+(?!\S)|\s+";
+
+const O200K_PATTERN: &str = concat!(
+    r"[^\r\n\p{L}\p{N}]?[\p{Lu}\p{Lt}\p{Lm}\p{Lo}\p{M}]*[\p{Ll}\p{Lm}\p{Lo}\p{M}]+(?i:'s|'t|'re|'ve|'m|'ll|'d)?",
+    r"|[^\r\n\p{L}\p{N}]?[\p{Lu}\p{Lt}\p{Lm}\p{Lo}\p{M}]+[\p{Ll}\p{Lm}\p{Lo}\p{M}]*(?i:'s|'t|'re|'ve|'m|'ll|'d)?",
+    r"|\p{N}{1,3}| ?[^\s\p{L}\p{N}]+[\r\n/]*|\s*[\r\n]+|\s+(?!\S)|\s+",
+);
+
+#[derive(Debug)]
+pub(crate) enum TiktokenError {
+    Format(String),
+    Build(String),
+}
+
+impl TiktokenError {
+    pub(crate) fn into_message(self) -> String {
+        match self {
+            TiktokenError::Format(reason) => format!("invalid tiktoken rank file: {reason}"),
+            TiktokenError::Build(reason) => format!("failed to build tiktoken tokenizer: {reason}"),
+        }
+    }
+}
+
+/// Split patterns and special tokens of the published tiktoken encodings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum TiktokenPreset {
+    Cl100kBase,
+    O200kBase,
+}
+
+impl TiktokenPreset {
+    /// Maps the FFI preset code; `-1` means a caller-supplied pattern and yields `None`.
+    pub(crate) fn from_raw(value: c_int) -> Result<Option<Self>, &'static str> {
+        match value {
+            -1 => Ok(None),
+            0 => Ok(Some(TiktokenPreset::Cl100kBase)),
+            1 => Ok(Some(TiktokenPreset::O200kBase)),
+            _ => Err("unknown tiktoken preset"),
+        }
+    }
+
+    pub(crate) fn pattern(self) -> &'static str {
+        match self {
+            TiktokenPreset::Cl100kBase => CL100K_PATTERN,
+            TiktokenPreset::O200kBase => O200K_PATTERN,
+        }
+    }
+
+    pub(crate) fn special_tokens(self) -> Vec<(String, u32)> {
+        let entries: &[(&str, u32)] = match self {
+            TiktokenPreset::Cl100kBase => &[
+                ("<|endoftext|>", 100257),
+                ("<|fim_prefix|>", 100258),
+                ("<|fim_middle|>", 100259),
+                ("<|fim_suffix|>", 100260),
+                ("<|endofprompt|>", 100276),
+            ],
+            TiktokenPreset::O200kBase => &[("<|endoftext|>", 199999), ("<|endofprompt|>", 200018)],
+        };
+        entries
+            .iter()
+            .map(|(token, id)| (token.to_string(), *id))
+            .collect()
+    }
+}
+
+fn base64_value(byte: u8) -> Option<u32> {
+    match byte {
+        b'A'..=b'Z' => Some(u32::from(byte - b'A')),
+        b'a'..=b'z' => Some(u32::from(byte - b'a') + 26),
+        b'0'..=b'9' => Some(u32::from(byte - b'0') + 52),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None,
+    }
+}
+
+/// Decodes standard padded base64, the encoding tiktoken uses for rank-file tokens.
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let trimmed = text.trim_end_matches('=');
+    if !text.len().is_multiple_of(4) || text.len() - trimmed.len() > 2 {
+        return None;
+    }
+
+    let mut bytes = Vec::with_capacity(trimmed.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0u32;
+    for byte in trimmed.bytes() {
+        buffer = (buffer << 6) | base64_value(byte)?;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(bytes)
+}
+
+/// Parses a `.tiktoken` rank file: one `<base64 token> <rank>` pair per line.
+pub(crate) fn parse_ranks(content: &str) -> Result<Vec<(Vec<u8>, u32)>, TiktokenError> {
+    let mut ranks = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        if line.is_empty() {
+            continue;
+        }
+
+        let invalid =
+            || TiktokenError::Format(format!("line {} is not a token/rank pair", index + 1));
+        let (token, rank) = line.split_once(' ').ok_or_else(invalid)?;
+        let token = decode_base64(token)
+            .filter(|bytes| !bytes.is_empty())
+            .ok_or_else(invalid)?;
+        let rank = rank.trim().parse::<u32>().map_err(|_| invalid())?;
+        ranks.push((token, rank));
+    }
+
+    if ranks.is_empty() {
+        return Err(TiktokenError::Format(String::from(
+            "rank file contains no tokens",
+        )));
+    }
+    Ok(ranks)
+}
+
+/// Runs tiktoken's byte-pair merge on `token`, stopping before any merge ranked `max_rank` or above.
+fn byte_pair_parts<'t>(
+    ranks: &AHashMap<&[u8], u32>,
+    token: &'t [u8],
+    max_rank: u32,
+) -> Vec<&'t [u8]> {
+    let mut boundaries: Vec<usize> = (0..=token.len()).collect();
+    loop {
+        let best = (0..boundaries.len().saturating_sub(2))
+            .filter_map(|index| {
+                let merged = &token[boundaries[index]..boundaries[index + 2]];
+                ranks.get(merged).map(|rank| (*rank, index))
+            })
+            .min();
+
+        match best {
+            Some((rank, index)) if rank < max_rank => {
+                boundaries.remove(index + 1);
+            }
+            _ => break,
+        }
+    }
+
+    boundaries
+        .windows(2)
+        .map(|window| &token[window[0]..window[1]])
+        .collect()
+}
+
+/// Builds a ByteLevel BPE tokenizer that reproduces tiktoken's encoder.
+///
+/// Each multi-byte token contributes the merge tiktoken itself performs last when building it, so
+/// merge priorities follow ranks exactly. Whole pre-tokenized pieces found in the vocabulary are
+/// taken as-is (`ignore_merges`), as tiktoken does. Special tokens are placed in the model
+/// vocabulary at their ids, since ids outside the rank range need not be contiguous.
+pub(crate) fn build_tokenizer(
+    ranks: &[(Vec<u8>, u32)],
+    pattern: &str,
+    special_tokens: &[(String, u32)],
+) -> Result<Tokenizer, TiktokenError> {
+    let alphabet = bytes_to_unicode();
+    let to_unicode = |bytes: &[u8]| -> String {
+        bytes
+            .iter()
+            .map(|byte| alphabet[usize::from(*byte)])
+            .collect()
+    };
+
+    let mut ordered: Vec<&(Vec<u8>, u32)> = ranks.iter().collect();
+    ordered.sort_by_key(|(_, rank)| *rank);
+    let lookup: AHashMap<&[u8], u32> = ordered
+        .iter()
+        .map(|(token, rank)| (token.as_slice(), *rank))
+        .collect();
+
+    let mut vocab = Map::new();
+    let mut merges = Vec::new();
+    for (token, rank) in &ordered {
+        if vocab
+            .insert(to_unicode(token), Value::from(*rank))
+            .is_some()
+        {
+            return Err(TiktokenError::Format(format!(
+                "token with rank {rank} is listed twice"
+            )));
+        }
+        if token.len() == 1 {
+            continue;
+        }
+
+        if let [left, right] = byte_pair_parts(&lookup, token, *rank)[..] {
+            merges.push((to_unicode(left), to_unicode(right)));
+        }
+    }
+
+    let mut added_tokens = Vec::with_capacity(special_tokens.len());
+    for (content, id) in special_tokens {
+        if lookup.values().any(|rank| rank == id) {
+            return Err(TiktokenError::Format(format!(
+                "special token '{content}' reuses rank {id}"
+            )));
+        }
+        vocab.insert(content.clone(), Value::from(*id));
+        added_tokens.push(json!({
+            "id": id,
+            "content": content,
+            "single_word": false,
+            "lstrip": false,
+            "rstrip": false,
+            "normalized": false,
+            "special": true,
+        }));
+    }
+
+    let document = json!({
+        "version": "1.0",
+        "truncation": null,
+        "padding": null,
+        "added_tokens": added_tokens,
+        "normalizer": null,
+        "pre_tokenizer": {
+            "type": "Sequence",
+            "pretokenizers": [
+                {
+                    "type": "Split",
+                    "pattern": {"Regex": pattern},
+                    "behavior": "Isolated",
+                    "invert": false,
+                },
+                {
+                    "type": "ByteLevel",
+                    "add_prefix_space": false,
+                    "trim_offsets": true,
+                    "use_regex": false,
+                },
+            ],
+        },
+        "post_processor": {
+            "type": "ByteLevel",
+            "add_prefix_space": true,
+            "trim_offsets": false,
+            "use_regex": true,
+        },
+        "decoder": {
+            "type": "ByteLevel",
+            "add_prefix_space": true,
+            "trim_offsets": true,
+            "use_regex": true,
+        },
+        "model": {
+            "type": "BPE",
+            "dropout": null,
+            "unk_token": null,
+            "continuing_subword_prefix": null,
+            "end_of_word_suffix": null,
+            "fuse_unk": false,
+            "byte_fallback": false,
+            "ignore_merges": true,
+            "vocab": vocab,
+            "merges": merges,
+        },
+    });
+
+    Tokenizer::from_str(&document.to_string()).map_err(|err| TiktokenError::Build(err.to_string()))
+}
@@ -0,0 +1,179 @@
+use std::sync::OnceLock;
+
+use tokenizers::decoders::DecoderWrapper;
+use tokenizers::normalizers::Replace;
+use tokenizers::{Decoder, Tokenizer};
+
+/// Flag set on tokens that are special added tokens.
+pub(crate) const TOKEN_BYTES_SPECIAL: u32 = 1;
+/// Flag set when a token's bytes are not valid UTF-8 on their own.
+pub(crate) const TOKEN_BYTES_INCOMPLETE: u32 = 2;
+
+#[derive(Debug)]
+pub(crate) struct TokenBytes {
+    pub(crate) bytes: Vec<u8>,
+    pub(crate) special: bool,
+}
+
+impl TokenBytes {
+    pub(crate) fn flags(&self) -> u32 {
+        let mut flags = 0;
+        if self.special {
+            flags |= TOKEN_BYTES_SPECIAL;
+        }
+        if std::str::from_utf8(&self.bytes).is_err() {
+            flags |= TOKEN_BYTES_INCOMPLETE;
+        }
+        flags
+    }
+}
+
+/// A surface-form rewrite applied in decoder order.
+enum Replacement<'t> {
+    /// Metaspace's replacement character back to a space.
+    Literal(String, &'static str),
+    /// A `Replace` decoder, applied through the decoder itself so its compiled pattern is reused.
+    Decoder(&'t Replace),
+}
+
+/// Maps token ids to the raw bytes they contribute to decoded text, following the decoder
+/// components that rewrite surface forms: ByteLevel, ByteFallback, Metaspace and Replace.
+pub(crate) struct TokenByteMapper<'t> {
+    byte_level: bool,
+    byte_fallback: bool,
+    replacements: Vec<Replacement<'t>>,
+    continuing_prefix: Option<&'t str>,
+}
+
+impl<'t> TokenByteMapper<'t> {
+    pub(crate) fn new(tokenizer: &'t Tokenizer) -> Self {
+        let mut mapper = Self {
+            byte_level: false,
+            byte_fallback: false,
+            replacements: Vec::new(),
+            continuing_prefix: None,
+        };
+
+        if let Some(decoder) = tokenizer.get_decoder() {
+            mapper.inspect(decoder);
+        }
+
+        mapper
+    }
+
+    fn inspect(&mut self, decoder: &'t DecoderWrapper) {
+        match decoder {
+            DecoderWrapper::ByteLevel(_) => self.byte_level = true,
+            DecoderWrapper::ByteFallback(_) => self.byte_fallback = true,
+            DecoderWrapper::Metaspace(metaspace) => self.replacements.push(Replacement::Literal(
+                metaspace.get_replacement().to_string(),
+                " ",
+            )),
+            DecoderWrapper::WordPiece(wordpiece) => {
+                self.continuing_prefix = Some(wordpiece.prefix.as_str())
+            }
+            DecoderWrapper::Replace(replace) => {
+                self.replacements.push(Replacement::Decoder(replace))
+            }
+            DecoderWrapper::Sequence(sequence) => {
+                for inner in sequence.get_decoders() {
+                    self.inspect(inner);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns `None` when `id` is not in the vocabulary. Added tokens keep their literal content.
+    pub(crate) fn token_bytes(&self, tokenizer: &Tokenizer, id: u32) -> Option<TokenBytes> {
+        if let Some(added) = tokenizer.get_added_tokens_decoder().get(&id) {
+            return Some(TokenBytes {
+                bytes: added.content.as_bytes().to_vec(),
+                special: added.special,
+            });
+        }
+
+        let token = tokenizer.id_to_token(id)?;
+        Some(TokenBytes {
+            bytes: self.surface_to_bytes(&token),
+            special: false,
+        })
+    }
+
+    fn surface_to_bytes(&self, token: &str) -> Vec<u8> {
+        if self.byte_fallback {
+            if let Some(byte) = parse_byte_fallback(token) {
+                return vec![byte];
+            }
+        }
+
+        if self.byte_level {
+            let mapped: Option<Vec<u8>> = token.chars().map(byte_level_byte).collect();
+            if let Some(bytes) = mapped {
+                return bytes;
+            }
+        }
+
+        let mut text = token.to_string();
+        if let Some(prefix) = self.continuing_prefix {
+            if let Some(stripped) = text.strip_prefix(prefix) {
+                text = stripped.to_string();
+            }
+        }
+        for replacement in &self.replacements {
+            text = match replacement {
+                Replacement::Literal(pattern, content) => text.replace(pattern.as_str(), content),
+                Replacement::Decoder(replace) => match replace.decode_chain(vec![text.clone()]) {
+                    Ok(decoded) => decoded.concat(),
+                    Err(_) => text,
+                },
+            };
+        }
+
+        text.into_bytes()
+    }
+}
+
+/// Parses ByteFallback tokens of the form `<0xNN>`.
+fn parse_byte_fallback(token: &str) -> Option<u8> {
+    let hex = token.strip_prefix("<0x")?.strip_suffix('>')?;
+    if hex.len() != 2 {
+        return None;
+    }
+    u8::from_str_radix(hex, 16).ok()
+}
+
+/// GPT-2's `bytes_to_unicode`: printable Latin-1 bytes map to themselves and the rest are shifted
+/// to code points from U+0100 upward.
+pub(crate) fn bytes_to_unicode() -> [char; 256] {
+    let printable = |byte: u8| matches!(byte, b'!'..=b'~' | 0xA1..=0xAC | 0xAE..=0xFF);
+
+    let mut table = ['\0'; 256];
+    let mut shifted = 0u32;
+    for byte in 0..=255u8 {
+        table[usize::from(byte)] = if printable(byte) {
+            char::from(byte)
+        } else {
+            shifted += 1;
+            char::from_u32(255 + shifted).expect("shifted byte-level code points are valid")
+        };
+    }
+
+    table
+}
+
+/// One past the highest code point [`bytes_to_unicode`] produces (U+0143).
+const BYTE_LEVEL_CODE_POINTS: usize = 0x144;
+
+/// Inverse of [`bytes_to_unicode`], indexed by code point and built once per process.
+fn byte_level_byte(ch: char) -> Option<u8> {
+    static TABLE: OnceLock<[Option<u8>; BYTE_LEVEL_CODE_POINTS]> = OnceLock::new();
+    let table = TABLE.get_or_init(|| {
+        let mut table = [None; BYTE_LEVEL_CODE_POINTS];
+        for (byte, ch) in bytes_to_unicode().iter().enumerate() {
+            table[*ch as usize] = Some(byte as u8);
+        }
+        table
+    });
+    table.get(ch as usize).copied().flatten()
+}
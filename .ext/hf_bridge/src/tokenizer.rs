@@ -1,19 +1,77 @@
+use std::sync::OnceLock;
+
 use tokenizers::Tokenizer;
 
+use crate::trace::BpeMerges;
+
 pub struct CTokenizer {
     inner: Tokenizer,
+    unpadded: OnceLock<Tokenizer>,
+    unconstrained: OnceLock<Tokenizer>,
+    bpe_merges: OnceLock<Result<Option<BpeMerges>, String>>,
 }
 
 impl CTokenizer {
     pub(crate) fn new(tokenizer: Tokenizer) -> Self {
-        Self { inner: tokenizer }
+        Self {
+            inner: tokenizer,
+            unpadded: OnceLock::new(),
+            unconstrained: OnceLock::new(),
+            bpe_merges: OnceLock::new(),
+        }
     }
 
     pub(crate) fn inner(&self) -> &Tokenizer {
         &self.inner
     }
 
+    /// Every mutation goes through here, so the cached copies are dropped and rebuilt from the
+    /// updated tokenizer on their next use.
     pub(crate) fn inner_mut(&mut self) -> &mut Tokenizer {
+        self.unpadded = OnceLock::new();
+        self.unconstrained = OnceLock::new();
+        self.bpe_merges = OnceLock::new();
         &mut self.inner
     }
+
+    /// The tokenizer itself when it has no padding configured, otherwise a copy without it that is
+    /// built once and kept until the next mutation.
+    pub(crate) fn unpadded(&self) -> &Tokenizer {
+        if self.inner.get_padding().is_none() {
+            return &self.inner;
+        }
+
+        self.unpadded.get_or_init(|| {
+            let mut copy = self.inner.clone();
+            copy.with_padding(None);
+            copy
+        })
+    }
+
+    /// The tokenizer itself when it has no truncation or padding configured, otherwise a copy with
+    /// both disabled so callers see full-length encodings without touching shared state. The copy
+    /// is built once and kept until the next mutation.
+    pub(crate) fn unconstrained(&self) -> &Tokenizer {
+        if self.inner.get_truncation().is_none() && self.inner.get_padding().is_none() {
+            return &self.inner;
+        }
+
+        self.unconstrained.get_or_init(|| {
+            let mut copy = self.inner.clone();
+            copy.with_padding(None);
+            // Disabling truncation cannot fail; only enabling it validates parameters.
+            let _ = copy.with_truncation(None);
+            copy
+        })
+    }
+
+    /// Merge ranks of the BPE model for tracing, or `None` for other models. Reading them
+    /// serializes the whole model, so they are kept until the next mutation.
+    pub(crate) fn bpe_merges(&self) -> Result<Option<&BpeMerges>, String> {
+        self.bpe_merges
+            .get_or_init(|| BpeMerges::from_model(self.inner.get_model()))
+            .as_ref()
+            .map(Option::as_ref)
+            .map_err(Clone::clone)
+    }
 }
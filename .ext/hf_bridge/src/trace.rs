@@ -0,0 +1,365 @@
+use ahash::AHashMap;
+use serde::Serialize;
+use serde_json::Value;
+use tokenizers::normalizer::Range;
+use tokenizers::{
+    Model, ModelWrapper, NormalizedString, Normalizer, OffsetReferential, OffsetType,
+    PreTokenizedString, PreTokenizer, Tokenizer,
+};
+
+use crate::offsets::{OffsetConverter, OffsetMode};
+
+#[derive(Debug)]
+pub(crate) enum TraceError {
+    Normalize(String),
+    PreTokenize(String),
+    Model(String),
+    PostProcess(String),
+}
+
+impl TraceError {
+    pub(crate) fn into_message(self) -> String {
+        match self {
+            TraceError::Normalize(reason) => format!("normalizer failed: {reason}"),
+            TraceError::PreTokenize(reason) => format!("pre-tokenizer failed: {reason}"),
+            TraceError::Model(reason) => format!("model failed: {reason}"),
+            TraceError::PostProcess(reason) => format!("post-processor failed: {reason}"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct CharAlignment {
+    #[serde(rename = "normalized")]
+    normalized: (u32, u32),
+    #[serde(rename = "original")]
+    original: (u32, u32),
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct NormalizeTrace {
+    #[serde(rename = "original")]
+    original: String,
+    #[serde(rename = "normalized")]
+    normalized: String,
+    #[serde(rename = "alignments")]
+    alignments: Vec<CharAlignment>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct PreTokenizeSplit {
+    #[serde(rename = "text")]
+    text: String,
+    #[serde(rename = "offsets")]
+    offsets: (u32, u32),
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct PreTokenizeTrace {
+    #[serde(rename = "splits")]
+    splits: Vec<PreTokenizeSplit>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ModelToken {
+    #[serde(rename = "id")]
+    id: u32,
+    #[serde(rename = "value")]
+    value: String,
+    #[serde(rename = "offsets")]
+    offsets: (u32, u32),
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct MergeStep {
+    #[serde(rename = "left")]
+    left: String,
+    #[serde(rename = "right")]
+    right: String,
+    #[serde(rename = "result")]
+    result: String,
+    #[serde(rename = "rank")]
+    rank: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ModelTrace {
+    #[serde(rename = "model")]
+    model: &'static str,
+    #[serde(rename = "word")]
+    word: String,
+    #[serde(rename = "tokens")]
+    tokens: Vec<ModelToken>,
+    #[serde(skip_serializing_if = "Option::is_none", rename = "merges")]
+    merges: Option<Vec<MergeStep>>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ProcessedToken {
+    #[serde(rename = "id")]
+    id: u32,
+    #[serde(rename = "token")]
+    token: String,
+    #[serde(rename = "type_id")]
+    type_id: u32,
+    #[serde(rename = "sequence_id")]
+    sequence_id: Option<usize>,
+    #[serde(rename = "inserted")]
+    inserted: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct PostProcessTrace {
+    #[serde(rename = "tokens")]
+    tokens: Vec<ProcessedToken>,
+}
+
+/// Runs only the normalizer, like `normalize_str`, and aligns every normalized character with the
+/// original span it came from.
+pub(crate) fn trace_normalize(
+    tokenizer: &Tokenizer,
+    text: &str,
+    mode: OffsetMode,
+) -> Result<NormalizeTrace, TraceError> {
+    let mut normalized = NormalizedString::from(text);
+    if let Some(normalizer) = tokenizer.get_normalizer() {
+        normalizer
+            .normalize(&mut normalized)
+            .map_err(|err| TraceError::Normalize(err.to_string()))?;
+    }
+
+    let original_units = OffsetConverter::new(text, mode);
+    let normalized_units = OffsetConverter::new(normalized.get(), mode);
+    let alignments = normalized
+        .get()
+        .char_indices()
+        .map(|(start, ch)| {
+            let end = start + ch.len_utf8();
+            let original = normalized
+                .convert_offsets(Range::Normalized(start..end))
+                .unwrap_or(0..0);
+            CharAlignment {
+                normalized: normalized_units.convert(start, end),
+                original: original_units.convert(original.start, original.end),
+            }
+        })
+        .collect();
+
+    Ok(NormalizeTrace {
+        original: text.to_string(),
+        normalized: normalized.get().to_string(),
+        alignments,
+    })
+}
+
+/// Runs only the pre-tokenizer, like `pre_tokenize_str`, on the raw text.
+pub(crate) fn trace_pre_tokenize(
+    tokenizer: &Tokenizer,
+    text: &str,
+    mode: OffsetMode,
+) -> Result<PreTokenizeTrace, TraceError> {
+    let mut pre_tokenized = PreTokenizedString::from(text);
+    if let Some(pre_tokenizer) = tokenizer.get_pre_tokenizer() {
+        pre_tokenizer
+            .pre_tokenize(&mut pre_tokenized)
+            .map_err(|err| TraceError::PreTokenize(err.to_string()))?;
+    }
+
+    let converter = OffsetConverter::new(text, mode);
+    let splits = pre_tokenized
+        .get_splits(OffsetReferential::Original, OffsetType::Byte)
+        .into_iter()
+        .map(|(split, (start, end), _)| PreTokenizeSplit {
+            text: split.to_string(),
+            offsets: converter.convert(start, end),
+        })
+        .collect();
+
+    Ok(PreTokenizeTrace { splits })
+}
+
+/// Runs only the model on one pre-tokenized word (for ByteLevel models that is the mapped form,
+/// such as `Ġhello`). For BPE the merges are replayed by rank with `merges` (see
+/// `CTokenizer::bpe_merges`) to show how the pieces were built.
+pub(crate) fn trace_model(
+    tokenizer: &Tokenizer,
+    merges: Option<&BpeMerges>,
+    word: &str,
+    mode: OffsetMode,
+) -> Result<ModelTrace, TraceError> {
+    let model = tokenizer.get_model();
+    let converter = OffsetConverter::new(word, mode);
+    let tokens = model
+        .tokenize(word)
+        .map_err(|err| TraceError::Model(err.to_string()))?
+        .into_iter()
+        .map(|token| ModelToken {
+            id: token.id,
+            value: token.value,
+            offsets: converter.convert(token.offsets.0, token.offsets.1),
+        })
+        .collect();
+
+    let (name, merges) = match model {
+        ModelWrapper::BPE(_) => (
+            "BPE",
+            Some(merges.map_or_else(Vec::new, |merges| merges.replay(model, word))),
+        ),
+        ModelWrapper::WordPiece(_) => ("WordPiece", None),
+        ModelWrapper::WordLevel(_) => ("WordLevel", None),
+        ModelWrapper::Unigram(_) => ("Unigram", None),
+    };
+
+    Ok(ModelTrace {
+        model: name,
+        word: word.to_string(),
+        tokens,
+        merges,
+    })
+}
+
+/// Encodes without special tokens, then runs the post-processor and flags the tokens it inserted.
+/// `tokenizer` must not truncate or pad (see `CTokenizer::unconstrained`).
+pub(crate) fn trace_post_process(
+    tokenizer: &Tokenizer,
+    sequence: &str,
+    pair: Option<&str>,
+) -> Result<PostProcessTrace, TraceError> {
+    let encode = |text: &str| {
+        tokenizer
+            .encode(text, false)
+            .map_err(|err| TraceError::PostProcess(err.to_string()))
+    };
+
+    let primary = encode(sequence)?;
+    let secondary = pair.map(encode).transpose()?;
+    let processed = tokenizer
+        .post_process(primary, secondary, true)
+        .map_err(|err| TraceError::PostProcess(err.to_string()))?;
+
+    let tokens = processed
+        .get_ids()
+        .iter()
+        .zip(processed.get_tokens())
+        .zip(processed.get_type_ids())
+        .zip(processed.get_sequence_ids())
+        .map(|(((id, token), type_id), sequence_id)| ProcessedToken {
+            id: *id,
+            token: token.clone(),
+            type_id: *type_id,
+            sequence_id,
+            inserted: sequence_id.is_none(),
+        })
+        .collect();
+
+    Ok(PostProcessTrace { tokens })
+}
+
+/// Subword affixes and merge ranks of a BPE model. The model keeps its merges private, so they are
+/// read from its serialized form; that walks the whole vocabulary, so callers build this once per
+/// tokenizer (see `CTokenizer::bpe_merges`) rather than per trace.
+pub(crate) struct BpeMerges {
+    prefix: String,
+    suffix: String,
+    ignore_merges: bool,
+    ranks: AHashMap<(String, String), usize>,
+}
+
+impl BpeMerges {
+    /// Returns `None` for models other than BPE.
+    pub(crate) fn from_model(model: &ModelWrapper) -> Result<Option<Self>, String> {
+        if !matches!(model, ModelWrapper::BPE(_)) {
+            return Ok(None);
+        }
+
+        let model = serde_json::to_value(model).map_err(|err| err.to_string())?;
+        let ranks = model["merges"]
+            .as_array()
+            .map(|merges| {
+                merges
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(rank, merge)| {
+                        let (left, right) = match merge {
+                            Value::String(text) => text.split_once(' ')?,
+                            Value::Array(parts) => {
+                                (parts.first()?.as_str()?, parts.get(1)?.as_str()?)
+                            }
+                            _ => return None,
+                        };
+                        Some(((left.to_string(), right.to_string()), rank))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(Some(BpeMerges {
+            prefix: model["continuing_subword_prefix"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            suffix: model["end_of_word_suffix"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            ignore_merges: model["ignore_merges"].as_bool() == Some(true),
+            ranks,
+        }))
+    }
+
+    /// Replays the merges on `word` by ascending rank, mirroring the model's merge loop without
+    /// dropout.
+    fn replay(&self, model: &ModelWrapper, word: &str) -> Vec<MergeStep> {
+        let prefix = self.prefix.as_str();
+        let suffix = self.suffix.as_str();
+        if self.ignore_merges && model.token_to_id(word).is_some() {
+            return Vec::new();
+        }
+
+        let count = word.chars().count();
+        let mut symbols: Vec<String> = word
+            .chars()
+            .enumerate()
+            .map(|(index, ch)| {
+                let mut symbol = String::new();
+                if index > 0 {
+                    symbol.push_str(prefix);
+                }
+                symbol.push(ch);
+                if index + 1 == count {
+                    symbol.push_str(suffix);
+                }
+                symbol
+            })
+            .collect();
+
+        let mut steps = Vec::new();
+        loop {
+            let best = symbols
+                .windows(2)
+                .enumerate()
+                .filter_map(|(index, pair)| {
+                    self.ranks
+                        .get(&(pair[0].clone(), pair[1].clone()))
+                        .map(|rank| (*rank, index))
+                })
+                .min();
+            let Some((rank, index)) = best else {
+                break;
+            };
+
+            let right = symbols.remove(index + 1);
+            let left = symbols[index].clone();
+            let result = format!("{left}{}", right.strip_prefix(prefix).unwrap_or(&right));
+            symbols[index] = result.clone();
+            steps.push(MergeStep {
+                left,
+                right,
+                result,
+                rank,
+            });
+        }
+
+        steps
+    }
+}
@@ -0,0 +1,50 @@
+use serde::Serialize;
+use tokenizers::Tokenizer;
+
+/// Vocabulary entries ordered by id.
+pub(crate) fn sorted_vocab(tokenizer: &Tokenizer, with_added_tokens: bool) -> Vec<(u32, String)> {
+    let mut entries: Vec<(u32, String)> = tokenizer
+        .get_vocab(with_added_tokens)
+        .into_iter()
+        .map(|(token, id)| (id, token))
+        .collect();
+    entries.sort_unstable();
+    entries
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct AddedTokenEntry {
+    #[serde(rename = "id")]
+    pub(crate) id: u32,
+    #[serde(rename = "content")]
+    pub(crate) content: String,
+    #[serde(rename = "special")]
+    pub(crate) special: bool,
+    #[serde(rename = "lstrip")]
+    pub(crate) lstrip: bool,
+    #[serde(rename = "rstrip")]
+    pub(crate) rstrip: bool,
+    #[serde(rename = "normalized")]
+    pub(crate) normalized: bool,
+    #[serde(rename = "single_word")]
+    pub(crate) single_word: bool,
+}
+
+/// The added-tokens decoder table ordered by id.
+pub(crate) fn added_tokens(tokenizer: &Tokenizer) -> Vec<AddedTokenEntry> {
+    let mut entries: Vec<AddedTokenEntry> = tokenizer
+        .get_added_tokens_decoder()
+        .into_iter()
+        .map(|(id, token)| AddedTokenEntry {
+            id,
+            content: token.content,
+            special: token.special,
+            lstrip: token.lstrip,
+            rstrip: token.rstrip,
+            normalized: token.normalized,
+            single_word: token.single_word,
+        })
+        .collect();
+    entries.sort_unstable_by_key(|entry| entry.id);
+    entries
+}
@@ -0,0 +1,181 @@
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+use tokenx_bridge::ffi::added_tokens::{tokenizers_add_tokens, tokenizers_remove_tokens};
+use tokenx_bridge::ffi::config::{tokenizers_get_config, tokenizers_token_to_id};
+use tokenx_bridge::ffi::lean_encoding::tokenizers_encode_ids;
+use tokenx_bridge::ffi::lifecycle::tokenizers_free_string;
+use tokenx_bridge::ffi::test_helpers;
+use tokenx_bridge::{tokenizers_get_last_error, CAddedToken, CTokenizer};
+
+fn add(tokenizer: &mut CTokenizer, tokens: &[(&str, bool, i64)]) -> (Vec<u32>, i32) {
+    let contents: Vec<CString> = tokens
+        .iter()
+        .map(|(content, _, _)| CString::new(*content).unwrap())
+        .collect();
+    let entries: Vec<CAddedToken> = tokens
+        .iter()
+        .zip(contents.iter())
+        .map(|((_, special, pinned_id), content)| CAddedToken {
+            content: content.as_ptr(),
+            special: *special,
+            single_word: false,
+            lstrip: false,
+            rstrip: false,
+            normalized: !*special,
+            pinned_id: *pinned_id,
+        })
+        .collect();
+    let mut ids = vec![0u32; entries.len()];
+    let mut status = -1;
+
+    unsafe {
+        tokenizers_add_tokens(
+            tokenizer as *mut CTokenizer,
+            entries.as_ptr(),
+            entries.len(),
+            ids.as_mut_ptr(),
+            ptr::addr_of_mut!(status),
+        );
+    }
+
+    (ids, status)
+}
+
+fn remove(tokenizer: &mut CTokenizer, tokens: &[&str]) -> (i32, i32) {
+    let contents: Vec<CString> = tokens
+        .iter()
+        .map(|token| CString::new(*token).unwrap())
+        .collect();
+    let pointers: Vec<*const c_char> = contents.iter().map(|token| token.as_ptr()).collect();
+    let mut status = -1;
+    let removed = unsafe {
+        tokenizers_remove_tokens(
+            tokenizer as *mut CTokenizer,
+            pointers.as_ptr(),
+            pointers.len(),
+            ptr::addr_of_mut!(status),
+        )
+    };
+    (removed, status)
+}
+
+fn token_id(tokenizer: &CTokenizer, token: &str) -> i32 {
+    let token = CString::new(token).unwrap();
+    unsafe {
+        tokenizers_token_to_id(
+            tokenizer as *const CTokenizer,
+            token.as_ptr(),
+            ptr::null_mut(),
+        )
+    }
+}
+
+#[test]
+fn tokenizers_add_tokens_is_visible_to_encode_and_config() {
+    let mut tokenizer = test_helpers::create_tokenizer();
+    let (ids, status) = add(
+        &mut tokenizer,
+        &[("<tool_call>", true, -1), ("foo", false, -1)],
+    );
+    assert_eq!(status, 0);
+    assert_eq!(ids, [3, 4]);
+
+    let text = CString::new("hello<tool_call>foo").unwrap();
+    let mut encoded = [0u32; 8];
+    let mut length = 0usize;
+    unsafe {
+        tokenizers_encode_ids(
+            &tokenizer as *const CTokenizer,
+            text.as_ptr(),
+            ptr::null(),
+            false,
+            encoded.as_mut_ptr(),
+            ptr::null_mut(),
+            encoded.len(),
+            ptr::addr_of_mut!(length),
+            ptr::null_mut(),
+        );
+    }
+    assert_eq!(&encoded[..length], &[1, 3, 4]);
+
+    let config =
+        unsafe { tokenizers_get_config(&tokenizer as *const CTokenizer, false, ptr::null_mut()) };
+    let json = unsafe { CStr::from_ptr(config) }
+        .to_str()
+        .unwrap()
+        .to_string();
+    unsafe { tokenizers_free_string(config) };
+    assert!(json.contains("\"content\":\"<tool_call>\""));
+}
+
+#[test]
+fn tokenizers_add_tokens_pins_reserved_slots() {
+    let mut tokenizer = test_helpers::create_tokenizer();
+    assert_eq!(
+        add(&mut tokenizer, &[("<r0>", true, -1), ("<r1>", true, -1)]).0,
+        [3, 4]
+    );
+
+    let (ids, status) = add(&mut tokenizer, &[("<tool>", true, 3), ("<eot>", true, 5)]);
+    assert_eq!(status, 0);
+    assert_eq!(ids, [3, 5]);
+    assert_eq!(token_id(&tokenizer, "<tool>"), 3);
+    assert_eq!(token_id(&tokenizer, "<r0>"), -1);
+    assert_eq!(token_id(&tokenizer, "<r1>"), 4);
+}
+
+#[test]
+fn tokenizers_add_tokens_rejects_unreachable_pinned_id() {
+    let mut tokenizer = test_helpers::create_tokenizer();
+    let (_, status) = add(&mut tokenizer, &[("<far>", true, 9)]);
+
+    assert_eq!(status, 5);
+    assert_eq!(token_id(&tokenizer, "<far>"), -1);
+}
+
+#[test]
+fn tokenizers_add_tokens_keeps_vocab_ids_for_unpinned_vocab_tokens() {
+    let mut tokenizer = test_helpers::create_tokenizer();
+    let (ids, status) = add(&mut tokenizer, &[("<tool>", true, 3), ("world", true, -1)]);
+
+    assert_eq!(status, 0);
+    assert_eq!(ids, [3, 2]);
+    assert_eq!(token_id(&tokenizer, "<tool>"), 3);
+    assert_eq!(token_id(&tokenizer, "world"), 2);
+}
+
+#[test]
+fn tokenizers_add_tokens_rejects_pinning_into_model_vocab() {
+    let mut tokenizer = test_helpers::create_tokenizer();
+
+    let (_, status) = add(&mut tokenizer, &[("<unused>", true, 1)]);
+    assert_eq!(status, 5);
+    let message = unsafe { CStr::from_ptr(tokenizers_get_last_error()) }
+        .to_str()
+        .unwrap()
+        .to_string();
+    assert!(message.contains("already maps it to 'hello'"));
+    assert_eq!(token_id(&tokenizer, "<unused>"), -1);
+    assert_eq!(token_id(&tokenizer, "hello"), 1);
+
+    let (_, status) = add(&mut tokenizer, &[("world", true, 3)]);
+    assert_eq!(status, 5);
+    assert_eq!(token_id(&tokenizer, "world"), 2);
+
+    let (ids, status) = add(&mut tokenizer, &[("hello", true, 1)]);
+    assert_eq!(status, 0);
+    assert_eq!(ids, [1]);
+}
+
+#[test]
+fn tokenizers_remove_tokens_refuses_to_shift_ids() {
+    let mut tokenizer = test_helpers::create_tokenizer();
+    add(&mut tokenizer, &[("<a>", true, -1), ("<b>", true, -1)]);
+
+    assert_eq!(remove(&mut tokenizer, &["<a>"]), (0, 3));
+    assert_eq!(token_id(&tokenizer, "<a>"), 3);
+
+    assert_eq!(remove(&mut tokenizer, &["<b>", "<missing>"]), (1, 0));
+    assert_eq!(token_id(&tokenizer, "<b>"), -1);
+    assert_eq!(token_id(&tokenizer, "<a>"), 3);
+}
@@ -0,0 +1,211 @@
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::ptr;
+use tokenx_bridge::ffi::batch_plan::{
+    tokenizers_batch_plan_count, tokenizers_batch_plan_free, tokenizers_batch_plan_materialize,
+    tokenizers_batch_plan_to_json, tokenizers_plan_batches,
+};
+use tokenx_bridge::ffi::lifecycle::tokenizers_free_string;
+use tokenx_bridge::ffi::padding::tokenizers_enable_padding;
+use tokenx_bridge::ffi::test_helpers;
+use tokenx_bridge::{tokenizers_get_last_error, CBatchPlan, CBatchTensorDest, CTokenizer};
+
+fn plan(
+    tokenizer: &CTokenizer,
+    texts: &[&str],
+    max_rows: usize,
+    max_tokens: usize,
+) -> *mut CBatchPlan {
+    let texts: Vec<CString> = texts
+        .iter()
+        .map(|text| CString::new(*text).unwrap())
+        .collect();
+    let sequences: Vec<*const c_char> = texts.iter().map(|text| text.as_ptr()).collect();
+    let mut status = -1;
+
+    let plan = unsafe {
+        tokenizers_plan_batches(
+            tokenizer as *const CTokenizer,
+            sequences.as_ptr(),
+            ptr::null(),
+            sequences.len(),
+            false,
+            max_rows,
+            max_tokens,
+            0,
+            ptr::addr_of_mut!(status),
+        )
+    };
+
+    assert_eq!(status, 0);
+    assert!(!plan.is_null());
+    plan
+}
+
+fn plan_json(plan: *const CBatchPlan) -> serde_json::Value {
+    let mut status = -1;
+    let json_ptr = unsafe { tokenizers_batch_plan_to_json(plan, ptr::addr_of_mut!(status)) };
+    assert_eq!(status, 0);
+    let json = unsafe { CStr::from_ptr(json_ptr) }
+        .to_str()
+        .unwrap()
+        .to_string();
+    unsafe { tokenizers_free_string(json_ptr) };
+    serde_json::from_str(&json).unwrap()
+}
+
+#[test]
+fn tokenizers_plan_batches_groups_sequences_of_similar_length() {
+    let tokenizer = test_helpers::create_tokenizer();
+    let plan = plan(
+        &tokenizer,
+        &["hello world hello", "world", "hello world world", "hello"],
+        2,
+        0,
+    );
+
+    assert_eq!(unsafe { tokenizers_batch_plan_count(plan) }, 2);
+    let json = plan_json(plan);
+    assert_eq!(json["batches"][0]["indices"], serde_json::json!([1, 3]));
+    assert_eq!(json["batches"][0]["padded_length"], 1);
+    assert_eq!(json["batches"][1]["indices"], serde_json::json!([0, 2]));
+    assert_eq!(json["batches"][1]["padded_length"], 3);
+    assert_eq!(json["padding_waste"], 0.0);
+
+    unsafe { tokenizers_batch_plan_free(plan) };
+}
+
+#[test]
+fn tokenizers_plan_batches_respects_padded_token_budget_and_multiple() {
+    let mut tokenizer = test_helpers::create_tokenizer();
+    unsafe {
+        tokenizers_enable_padding(
+            &mut tokenizer as *mut CTokenizer,
+            1,
+            0,
+            0,
+            ptr::null(),
+            -1,
+            2,
+            ptr::null_mut(),
+        );
+    }
+
+    let plan = plan(&tokenizer, &["hello", "world", "hello world hello"], 0, 4);
+    let json = plan_json(plan);
+
+    assert_eq!(json["batches"][0]["indices"], serde_json::json!([0, 1]));
+    assert_eq!(json["batches"][0]["padded_length"], 2);
+    assert_eq!(json["batches"][0]["padding_waste"], 0.5);
+    assert_eq!(json["batches"][1]["indices"], serde_json::json!([2]));
+    assert_eq!(json["batches"][1]["padded_length"], 4);
+
+    unsafe { tokenizers_batch_plan_free(plan) };
+}
+
+#[test]
+fn tokenizers_batch_plan_materialize_writes_planned_rows() {
+    let tokenizer = test_helpers::create_tokenizer();
+    let plan = plan(&tokenizer, &["hello world", "world", "hello"], 0, 0);
+
+    let mut input_ids = [9i64; 6];
+    let mut attention_mask = [9i64; 6];
+    let destination = CBatchTensorDest {
+        input_ids: input_ids.as_mut_ptr() as *mut c_void,
+        attention_mask: attention_mask.as_mut_ptr() as *mut c_void,
+        token_type_ids: ptr::null_mut(),
+        position_ids: ptr::null_mut(),
+        capacity: input_ids.len(),
+    };
+    let mut sequence_length = 0usize;
+    let mut status = -1;
+
+    let rows = unsafe {
+        tokenizers_batch_plan_materialize(
+            plan,
+            0,
+            0,
+            &destination as *const CBatchTensorDest,
+            ptr::addr_of_mut!(sequence_length),
+            ptr::addr_of_mut!(status),
+        )
+    };
+
+    assert_eq!(status, 0);
+    assert_eq!(rows, 3);
+    assert_eq!(sequence_length, 2);
+    assert_eq!(input_ids, [2, 0, 1, 0, 1, 2]);
+    assert_eq!(attention_mask, [1, 0, 1, 0, 1, 1]);
+
+    unsafe { tokenizers_batch_plan_free(plan) };
+}
+
+#[test]
+fn tokenizers_batch_plan_materialize_rejects_unknown_batch() {
+    let tokenizer = test_helpers::create_tokenizer();
+    let plan = plan(&tokenizer, &["hello"], 0, 0);
+    let mut ids = [0i64; 1];
+    let mut mask = [0i64; 1];
+    let destination = CBatchTensorDest {
+        input_ids: ids.as_mut_ptr() as *mut c_void,
+        attention_mask: mask.as_mut_ptr() as *mut c_void,
+        token_type_ids: ptr::null_mut(),
+        position_ids: ptr::null_mut(),
+        capacity: 1,
+    };
+    let mut status = -1;
+
+    let rows = unsafe {
+        tokenizers_batch_plan_materialize(
+            plan,
+            3,
+            0,
+            &destination as *const CBatchTensorDest,
+            ptr::null_mut(),
+            ptr::addr_of_mut!(status),
+        )
+    };
+
+    assert_eq!(rows, 0);
+    assert_eq!(status, 4);
+
+    unsafe { tokenizers_batch_plan_free(plan) };
+}
+
+#[test]
+fn tokenizers_batch_plan_materialize_names_itself_when_capacity_is_short() {
+    let tokenizer = test_helpers::create_tokenizer();
+    let plan = plan(&tokenizer, &["hello world"], 0, 0);
+    let mut ids = [0i64; 1];
+    let mut mask = [0i64; 1];
+    let destination = CBatchTensorDest {
+        input_ids: ids.as_mut_ptr() as *mut c_void,
+        attention_mask: mask.as_mut_ptr() as *mut c_void,
+        token_type_ids: ptr::null_mut(),
+        position_ids: ptr::null_mut(),
+        capacity: 1,
+    };
+    let mut status = -1;
+
+    let rows = unsafe {
+        tokenizers_batch_plan_materialize(
+            plan,
+            0,
+            0,
+            &destination as *const CBatchTensorDest,
+            ptr::null_mut(),
+            ptr::addr_of_mut!(status),
+        )
+    };
+
+    assert_eq!((rows, status), (0, 5));
+    let message = unsafe { CStr::from_ptr(tokenizers_get_last_error()) }
+        .to_str()
+        .unwrap()
+        .to_string();
+    assert_eq!(
+        message,
+        "tokenizers_batch_plan_materialize received insufficient destination capacity"
+    );
+
+    unsafe { tokenizers_batch_plan_free(plan) };
+}
@@ -0,0 +1,124 @@
+use serde_json::Value;
+use std::ffi::{CStr, CString};
+use std::ptr;
+use tokenx_bridge::ffi::chunking::tokenizers_chunk_text;
+use tokenx_bridge::ffi::config::tokenizers_get_truncation;
+use tokenx_bridge::ffi::lifecycle::tokenizers_free_string;
+use tokenx_bridge::ffi::test_helpers;
+use tokenx_bridge::ffi::truncation::tokenizers_enable_truncation;
+use tokenx_bridge::CTokenizer;
+
+const DOCUMENT: &str = "hello world. hello world hello";
+
+fn chunk(
+    tokenizer: &CTokenizer,
+    max_tokens: usize,
+    overlap: usize,
+    reserved_tokens: usize,
+    boundary: i32,
+) -> (Option<Value>, i32) {
+    let text = CString::new(DOCUMENT).unwrap();
+    let mut status = -1;
+    let json_ptr = unsafe {
+        tokenizers_chunk_text(
+            tokenizer as *const CTokenizer,
+            text.as_ptr(),
+            max_tokens,
+            overlap,
+            reserved_tokens,
+            boundary,
+            1,
+            ptr::addr_of_mut!(status),
+        )
+    };
+
+    if json_ptr.is_null() {
+        return (None, status);
+    }
+
+    let json = unsafe { CStr::from_ptr(json_ptr) }
+        .to_str()
+        .unwrap()
+        .to_owned();
+    unsafe {
+        tokenizers_free_string(json_ptr);
+    }
+    (Some(serde_json::from_str(&json).unwrap()), status)
+}
+
+fn spans(chunks: &Value) -> Vec<(u64, u64)> {
+    chunks
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|chunk| {
+            (
+                chunk["start"].as_u64().unwrap(),
+                chunk["end"].as_u64().unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn tokenizers_chunk_text_slides_overlapping_windows() {
+    let tokenizer = test_helpers::create_tokenizer();
+    let (chunks, status) = chunk(&tokenizer, 4, 1, 0, 0);
+    let chunks = chunks.expect("chunking should succeed");
+
+    assert_eq!(status, 0);
+    assert_eq!(spans(&chunks), vec![(0, 18), (13, 30)]);
+    assert_eq!(chunks[0]["ids"], serde_json::json!([1, 2, 0, 1]));
+    assert_eq!(chunks[1]["text"], "hello world hello");
+}
+
+#[test]
+fn tokenizers_chunk_text_prefers_sentence_boundaries() {
+    let tokenizer = test_helpers::create_tokenizer();
+    let (chunks, status) = chunk(&tokenizer, 5, 0, 1, 2);
+    let chunks = chunks.expect("chunking should succeed");
+
+    assert_eq!(status, 0);
+    assert_eq!(spans(&chunks), vec![(0, 12), (13, 30)]);
+}
+
+#[test]
+fn tokenizers_chunk_text_rejects_overlap_filling_the_window() {
+    let tokenizer = test_helpers::create_tokenizer();
+    let (chunks, status) = chunk(&tokenizer, 4, 2, 2, 0);
+
+    assert!(chunks.is_none());
+    assert_ne!(status, 0);
+}
+
+#[test]
+fn tokenizers_chunk_text_ignores_and_preserves_tokenizer_truncation() {
+    let mut tokenizer = test_helpers::create_tokenizer();
+    unsafe {
+        tokenizers_enable_truncation(
+            &mut tokenizer as *mut CTokenizer,
+            2,
+            0,
+            0,
+            1,
+            ptr::null_mut(),
+        );
+    }
+
+    let (chunks, status) = chunk(&tokenizer, 6, 0, 0, 0);
+    let chunks = chunks.expect("chunking should succeed");
+    assert_eq!(status, 0);
+    assert_eq!(spans(&chunks), vec![(0, 30)]);
+
+    let mut truncation_status = -1;
+    let truncation = unsafe {
+        tokenizers_get_truncation(
+            &tokenizer as *const CTokenizer,
+            ptr::addr_of_mut!(truncation_status),
+        )
+    };
+    assert!(!truncation.is_null());
+    unsafe {
+        tokenizers_free_string(truncation);
+    }
+}
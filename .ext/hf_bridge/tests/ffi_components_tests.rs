@@ -0,0 +1,142 @@
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+use tokenx_bridge::ffi::components::{
+    tokenizers_get_decoder, tokenizers_get_normalizer, tokenizers_get_pre_tokenizer,
+    tokenizers_set_decoder, tokenizers_set_normalizer, tokenizers_set_post_processor,
+};
+use tokenx_bridge::ffi::lean_encoding::tokenizers_encode_ids;
+use tokenx_bridge::ffi::lifecycle::tokenizers_free_string;
+use tokenx_bridge::ffi::test_helpers;
+use tokenx_bridge::CTokenizer;
+
+fn take_string(value: *mut c_char) -> String {
+    assert!(!value.is_null());
+    let text = unsafe { CStr::from_ptr(value) }
+        .to_str()
+        .unwrap()
+        .to_string();
+    unsafe { tokenizers_free_string(value) };
+    text
+}
+
+fn encode(tokenizer: &CTokenizer, text: &str, add_special_tokens: bool) -> Vec<u32> {
+    let text = CString::new(text).unwrap();
+    let mut ids = [0u32; 8];
+    let mut length = 0usize;
+    let mut status = -1;
+    unsafe {
+        tokenizers_encode_ids(
+            tokenizer as *const CTokenizer,
+            text.as_ptr(),
+            ptr::null(),
+            add_special_tokens,
+            ids.as_mut_ptr(),
+            ptr::null_mut(),
+            ids.len(),
+            ptr::addr_of_mut!(length),
+            ptr::addr_of_mut!(status),
+        );
+    }
+    assert_eq!(status, 0);
+    ids[..length].to_vec()
+}
+
+#[test]
+fn tokenizers_set_normalizer_applies_and_removes_fragment() {
+    let mut tokenizer = test_helpers::create_tokenizer();
+    let fragment = CString::new(r#"{"type": "Lowercase"}"#).unwrap();
+    let mut status = -1;
+
+    let applied = unsafe {
+        tokenizers_set_normalizer(
+            &mut tokenizer as *mut CTokenizer,
+            fragment.as_ptr(),
+            ptr::addr_of_mut!(status),
+        )
+    };
+    assert_eq!((applied, status), (1, 0));
+    assert_eq!(encode(&tokenizer, "HELLO World", false), [1, 2]);
+
+    let current = unsafe {
+        tokenizers_get_normalizer(&tokenizer as *const CTokenizer, ptr::addr_of_mut!(status))
+    };
+    assert_eq!(take_string(current), r#"{"type":"Lowercase"}"#);
+
+    unsafe {
+        tokenizers_set_normalizer(
+            &mut tokenizer as *mut CTokenizer,
+            ptr::null(),
+            ptr::addr_of_mut!(status),
+        );
+    }
+    let current = unsafe {
+        tokenizers_get_normalizer(&tokenizer as *const CTokenizer, ptr::addr_of_mut!(status))
+    };
+    assert_eq!(take_string(current), "null");
+    assert_eq!(encode(&tokenizer, "HELLO", false), [0]);
+}
+
+#[test]
+fn tokenizers_set_post_processor_appends_eos_template() {
+    let mut tokenizer = test_helpers::create_tokenizer();
+    let fragment = CString::new(
+        r#"{
+            "type": "TemplateProcessing",
+            "single": [{"Sequence": {"id": "A", "type_id": 0}}, {"SpecialToken": {"id": "world", "type_id": 0}}],
+            "pair": [{"Sequence": {"id": "A", "type_id": 0}}, {"Sequence": {"id": "B", "type_id": 1}}],
+            "special_tokens": {"world": {"id": "world", "ids": [2], "tokens": ["world"]}}
+        }"#,
+    )
+    .unwrap();
+    let mut status = -1;
+
+    unsafe {
+        tokenizers_set_post_processor(
+            &mut tokenizer as *mut CTokenizer,
+            fragment.as_ptr(),
+            ptr::addr_of_mut!(status),
+        );
+    }
+
+    assert_eq!(status, 0);
+    assert_eq!(encode(&tokenizer, "hello", true), [1, 2]);
+    assert_eq!(encode(&tokenizer, "hello", false), [1]);
+}
+
+#[test]
+fn tokenizers_get_components_serialize_existing_settings() {
+    let tokenizer = test_helpers::create_byte_level_tokenizer();
+    let mut status = -1;
+
+    let decoder = unsafe {
+        tokenizers_get_decoder(&tokenizer as *const CTokenizer, ptr::addr_of_mut!(status))
+    };
+    let decoder: serde_json::Value = serde_json::from_str(&take_string(decoder)).unwrap();
+    assert_eq!(decoder["type"], "ByteLevel");
+
+    let pre_tokenizer = unsafe {
+        tokenizers_get_pre_tokenizer(&tokenizer as *const CTokenizer, ptr::addr_of_mut!(status))
+    };
+    assert_eq!(take_string(pre_tokenizer), "null");
+}
+
+#[test]
+fn tokenizers_set_decoder_rejects_invalid_fragment() {
+    let mut tokenizer = test_helpers::create_byte_level_tokenizer();
+    let fragment = CString::new(r#"{"type": "NoSuchDecoder"}"#).unwrap();
+    let mut status = -1;
+
+    let applied = unsafe {
+        tokenizers_set_decoder(
+            &mut tokenizer as *mut CTokenizer,
+            fragment.as_ptr(),
+            ptr::addr_of_mut!(status),
+        )
+    };
+
+    assert_eq!((applied, status), (0, 3));
+    let decoder = unsafe {
+        tokenizers_get_decoder(&tokenizer as *const CTokenizer, ptr::addr_of_mut!(status))
+    };
+    assert!(take_string(decoder).contains("ByteLevel"));
+}
@@ -0,0 +1,240 @@
+use std::ffi::{c_char, CStr, CString};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::ptr;
+use tokenx_bridge::ffi::config::{tokenizers_get_config, tokenizers_token_to_id};
+use tokenx_bridge::ffi::convert::tokenizers_from_legacy_files;
+use tokenx_bridge::ffi::decode::tokenizers_decode;
+use tokenx_bridge::ffi::lean_encoding::tokenizers_encode_ids;
+use tokenx_bridge::ffi::lifecycle::{tokenizers_create, tokenizers_free, tokenizers_free_string};
+use tokenx_bridge::CTokenizer;
+
+fn fixture_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("tokenx-convert-{}-{name}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn take_string(value: *mut c_char) -> String {
+    assert!(!value.is_null());
+    let text = unsafe { CStr::from_ptr(value) }
+        .to_str()
+        .unwrap()
+        .to_string();
+    unsafe { tokenizers_free_string(value) };
+    text
+}
+
+fn convert(
+    family: i32,
+    vocab: &Path,
+    merges: Option<&Path>,
+    config: Option<&str>,
+) -> *mut CTokenizer {
+    let vocab = CString::new(vocab.to_str().unwrap()).unwrap();
+    let merges = merges.map(|path| CString::new(path.to_str().unwrap()).unwrap());
+    let config = config.map(|json| CString::new(json).unwrap());
+    let mut status = -1;
+    let tokenizer = unsafe {
+        tokenizers_from_legacy_files(
+            family,
+            vocab.as_ptr(),
+            merges.as_ref().map_or(ptr::null(), |value| value.as_ptr()),
+            config.as_ref().map_or(ptr::null(), |value| value.as_ptr()),
+            ptr::addr_of_mut!(status),
+        )
+    };
+    assert_eq!(status, 0);
+    assert!(!tokenizer.is_null());
+    tokenizer
+}
+
+fn encode(tokenizer: *const CTokenizer, text: &str) -> Vec<u32> {
+    let text = CString::new(text).unwrap();
+    let mut ids = [0u32; 16];
+    let mut length = 0usize;
+    let mut status = -1;
+    unsafe {
+        tokenizers_encode_ids(
+            tokenizer,
+            text.as_ptr(),
+            ptr::null(),
+            true,
+            ids.as_mut_ptr(),
+            ptr::null_mut(),
+            ids.len(),
+            ptr::addr_of_mut!(length),
+            ptr::addr_of_mut!(status),
+        );
+    }
+    assert_eq!(status, 0);
+    ids[..length].to_vec()
+}
+
+fn varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn message(out: &mut Vec<u8>, field: u64, bytes: &[u8]) {
+    varint(out, field << 3 | 2);
+    varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+/// Serializes a minimal sentencepiece `ModelProto`; `kind` follows `SentencePiece.Type`.
+fn sentencepiece_model(pieces: &[(&str, f32, u64)], model_type: u64, unk_id: u64) -> Vec<u8> {
+    let mut proto = Vec::new();
+    for (piece, score, kind) in pieces {
+        let mut entry = Vec::new();
+        message(&mut entry, 1, piece.as_bytes());
+        varint(&mut entry, 2 << 3 | 5);
+        entry.extend_from_slice(&score.to_le_bytes());
+        varint(&mut entry, 3 << 3);
+        varint(&mut entry, *kind);
+        message(&mut proto, 1, &entry);
+    }
+
+    let mut trainer = Vec::new();
+    varint(&mut trainer, 3 << 3);
+    varint(&mut trainer, model_type);
+    varint(&mut trainer, 40 << 3);
+    varint(&mut trainer, unk_id);
+    message(&mut proto, 2, &trainer);
+
+    let mut normalizer = Vec::new();
+    varint(&mut normalizer, 3 << 3);
+    varint(&mut normalizer, 1);
+    message(&mut proto, 3, &normalizer);
+    proto
+}
+
+#[test]
+fn bert_vocab_txt_converts_and_exports() {
+    let dir = fixture_dir("bert");
+    let vocab = dir.join("vocab.txt");
+    fs::write(
+        &vocab,
+        "[PAD]\n[UNK]\n[CLS]\n[SEP]\n[MASK]\nhello\nwor\n##ld\n",
+    )
+    .unwrap();
+
+    let tokenizer = convert(-1, &vocab, None, Some(r#"{"do_lower_case": true}"#));
+    assert_eq!(encode(tokenizer, "Hello World"), vec![2, 5, 6, 7, 3]);
+
+    let mut status = -1;
+    let json =
+        take_string(unsafe { tokenizers_get_config(tokenizer, false, ptr::addr_of_mut!(status)) });
+    assert!(json.contains("\"WordPiece\""));
+    assert!(json.contains("\"BertNormalizer\""));
+
+    let exported = CString::new(json).unwrap();
+    let reloaded = unsafe { tokenizers_create(exported.as_ptr(), ptr::addr_of_mut!(status)) };
+    assert_eq!(status, 0);
+    assert_eq!(encode(reloaded, "Hello World"), vec![2, 5, 6, 7, 3]);
+
+    unsafe {
+        tokenizers_free(reloaded);
+        tokenizers_free(tokenizer);
+    }
+}
+
+#[test]
+fn byte_level_bpe_files_convert_for_gpt2_and_roberta() {
+    let dir = fixture_dir("bpe");
+    let vocab = dir.join("vocab.json");
+    let merges = dir.join("merges.txt");
+    fs::write(
+        &vocab,
+        r#"{"h":0,"e":1,"l":2,"o":3,"he":4,"ll":5,"hell":6,"hello":7,"<|endoftext|>":8,"Ġ":9,"Ġhello":10,"<s>":11,"</s>":12}"#,
+    )
+    .unwrap();
+    fs::write(&merges, "#version: 0.2\nh e\nl l\nhe ll\nhell o\nĠ hello\n").unwrap();
+
+    let gpt2 = convert(
+        -1,
+        &vocab,
+        Some(merges.as_path()),
+        Some(r#"{"tokenizer_class": "GPT2Tokenizer"}"#),
+    );
+    assert_eq!(encode(gpt2, "hello hello"), vec![7, 10]);
+
+    let roberta = convert(2, &vocab, Some(merges.as_path()), None);
+    assert_eq!(encode(roberta, "hello"), vec![11, 7, 12]);
+
+    unsafe {
+        tokenizers_free(roberta);
+        tokenizers_free(gpt2);
+    }
+}
+
+#[test]
+fn llama_sentencepiece_bpe_recovers_merges() {
+    let dir = fixture_dir("llama");
+    let model = dir.join("tokenizer.model");
+    let pieces = [
+        ("<unk>", 0.0, 2),
+        ("<s>", 0.0, 3),
+        ("</s>", 0.0, 3),
+        ("\u{2581}", -1.0, 1),
+        ("h", -2.0, 1),
+        ("i", -3.0, 1),
+        ("\u{2581}h", -4.0, 1),
+        ("hi", -5.0, 1),
+        ("\u{2581}hi", -6.0, 1),
+    ];
+    fs::write(&model, sentencepiece_model(&pieces, 2, 0)).unwrap();
+
+    let tokenizer = convert(3, &model, None, None);
+    let ids = encode(tokenizer, "hi");
+    assert_eq!(ids, vec![1, 8]);
+
+    let mut status = -1;
+    let text = take_string(unsafe {
+        tokenizers_decode(
+            tokenizer,
+            ids.as_ptr(),
+            ids.len(),
+            true,
+            ptr::addr_of_mut!(status),
+        )
+    });
+    assert_eq!(text, "hi");
+
+    unsafe { tokenizers_free(tokenizer) };
+}
+
+#[test]
+fn t5_sentencepiece_unigram_appends_eos_and_extra_ids() {
+    let dir = fixture_dir("t5");
+    let model = dir.join("spiece.model");
+    let pieces = [
+        ("<pad>", 0.0, 3),
+        ("</s>", 0.0, 3),
+        ("<unk>", 0.0, 2),
+        ("\u{2581}hello", -1.0, 1),
+        ("\u{2581}world", -1.0, 1),
+        ("\u{2581}", -2.0, 1),
+    ];
+    fs::write(&model, sentencepiece_model(&pieces, 1, 2)).unwrap();
+
+    let tokenizer = convert(
+        -1,
+        &model,
+        None,
+        Some(r#"{"tokenizer_class": "T5Tokenizer", "extra_ids": 2}"#),
+    );
+    assert_eq!(encode(tokenizer, "hello  world"), vec![3, 4, 1]);
+
+    let token = CString::new("<extra_id_0>").unwrap();
+    let mut status = -1;
+    let id =
+        unsafe { tokenizers_token_to_id(tokenizer, token.as_ptr(), ptr::addr_of_mut!(status)) };
+    assert_eq!(status, 0);
+    assert_eq!(id, 7);
+
+    unsafe { tokenizers_free(tokenizer) };
+}
@@ -0,0 +1,178 @@
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+use tokenx_bridge::ffi::decode_stream::{
+    tokenizers_decode_stream_flush, tokenizers_decode_stream_free, tokenizers_decode_stream_new,
+    tokenizers_decode_stream_reset, tokenizers_decode_stream_step,
+};
+use tokenx_bridge::ffi::lifecycle::{tokenizers_create, tokenizers_free, tokenizers_free_string};
+use tokenx_bridge::{CDecodeStream, CTokenizer};
+
+const BYTE_LEVEL_TOKENIZER: &str = r#"{
+    "version": "1.0",
+    "truncation": null,
+    "padding": null,
+    "added_tokens": [
+        {"id": 5, "content": "</s>", "single_word": false, "lstrip": false, "rstrip": false, "normalized": false, "special": true}
+    ],
+    "normalizer": null,
+    "pre_tokenizer": null,
+    "post_processor": null,
+    "decoder": {"type": "ByteLevel", "add_prefix_space": false, "trim_offsets": true, "use_regex": true},
+    "model": {
+        "type": "WordLevel",
+        "vocab": {"[UNK]": 0, "Ã": 1, "©": 2, "Ġcaf": 3, "hello": 4, "</s>": 5},
+        "unk_token": "[UNK]"
+    }
+}"#;
+
+fn create_byte_level_tokenizer() -> *mut CTokenizer {
+    let json = CString::new(BYTE_LEVEL_TOKENIZER).unwrap();
+    let mut status = -1;
+    let tokenizer = unsafe { tokenizers_create(json.as_ptr(), ptr::addr_of_mut!(status)) };
+    assert_eq!(status, 0);
+    tokenizer
+}
+
+fn take_string(value: *mut c_char) -> String {
+    assert!(!value.is_null());
+    let text = unsafe { CStr::from_ptr(value) }
+        .to_str()
+        .unwrap()
+        .to_string();
+    unsafe { tokenizers_free_string(value) };
+    text
+}
+
+fn step(stream: *mut CDecodeStream, tokenizer: *const CTokenizer, ids: &[u32]) -> String {
+    let mut status = -1;
+    let text = unsafe {
+        tokenizers_decode_stream_step(
+            stream,
+            tokenizer,
+            ids.as_ptr(),
+            ids.len(),
+            ptr::addr_of_mut!(status),
+        )
+    };
+    assert_eq!(status, 0);
+    take_string(text)
+}
+
+#[test]
+fn tokenizers_decode_stream_step_holds_back_split_characters() {
+    let tokenizer = create_byte_level_tokenizer();
+    let stream = unsafe { tokenizers_decode_stream_new(false) };
+
+    assert_eq!(step(stream, tokenizer, &[4]), "hello");
+    assert_eq!(step(stream, tokenizer, &[3]), " caf");
+    assert_eq!(step(stream, tokenizer, &[1]), "");
+    assert_eq!(step(stream, tokenizer, &[2]), "\u{e9}");
+
+    unsafe {
+        tokenizers_decode_stream_free(stream);
+        tokenizers_free(tokenizer);
+    }
+}
+
+#[test]
+fn tokenizers_decode_stream_step_skips_special_tokens() {
+    let tokenizer = create_byte_level_tokenizer();
+    let stream = unsafe { tokenizers_decode_stream_new(true) };
+
+    assert_eq!(step(stream, tokenizer, &[4, 5]), "hello");
+    assert_eq!(step(stream, tokenizer, &[5]), "");
+    assert_eq!(step(stream, tokenizer, &[3]), " caf");
+
+    unsafe {
+        tokenizers_decode_stream_free(stream);
+        tokenizers_free(tokenizer);
+    }
+}
+
+#[test]
+fn tokenizers_decode_stream_flush_returns_pending_text_and_resets() {
+    let tokenizer = create_byte_level_tokenizer();
+    let stream = unsafe { tokenizers_decode_stream_new(false) };
+
+    assert_eq!(step(stream, tokenizer, &[4]), "hello");
+    assert_eq!(step(stream, tokenizer, &[1]), "");
+
+    let mut status = -1;
+    let pending =
+        unsafe { tokenizers_decode_stream_flush(stream, tokenizer, ptr::addr_of_mut!(status)) };
+    assert_eq!(status, 0);
+    assert_eq!(take_string(pending), "\u{fffd}");
+    assert_eq!(step(stream, tokenizer, &[4]), "hello");
+
+    unsafe {
+        tokenizers_decode_stream_reset(stream);
+    }
+    assert_eq!(step(stream, tokenizer, &[3]), " caf");
+
+    unsafe {
+        tokenizers_decode_stream_free(stream);
+        tokenizers_free(tokenizer);
+    }
+}
+
+#[test]
+fn tokenizers_decode_stream_flush_keeps_text_the_decoder_rewrote() {
+    // Fusing then replacing `ab` with `X` rewrites the `a` already returned once `b` arrives.
+    let json = CString::new(
+        r#"{
+        "version": "1.0",
+        "truncation": null,
+        "padding": null,
+        "added_tokens": [],
+        "normalizer": null,
+        "pre_tokenizer": null,
+        "post_processor": null,
+        "decoder": {"type": "Sequence", "decoders": [
+            {"type": "Fuse"},
+            {"type": "Replace", "pattern": {"String": "ab"}, "content": "X"}
+        ]},
+        "model": {"type": "WordLevel", "vocab": {"[UNK]": 0, "a": 1, "b": 2}, "unk_token": "[UNK]"}
+    }"#,
+    )
+    .unwrap();
+    let mut status = -1;
+    let tokenizer = unsafe { tokenizers_create(json.as_ptr(), ptr::addr_of_mut!(status)) };
+    assert_eq!(status, 0);
+    let stream = unsafe { tokenizers_decode_stream_new(false) };
+
+    assert_eq!(step(stream, tokenizer, &[1]), "a");
+    assert_eq!(step(stream, tokenizer, &[2]), "");
+
+    status = -1;
+    let pending =
+        unsafe { tokenizers_decode_stream_flush(stream, tokenizer, ptr::addr_of_mut!(status)) };
+    assert_eq!(status, 0);
+    assert_eq!(take_string(pending), "X");
+
+    unsafe {
+        tokenizers_decode_stream_free(stream);
+        tokenizers_free(tokenizer);
+    }
+}
+
+#[test]
+fn tokenizers_decode_stream_step_rejects_null_stream() {
+    let tokenizer = create_byte_level_tokenizer();
+    let ids = [4u32];
+    let mut status = -1;
+
+    let text = unsafe {
+        tokenizers_decode_stream_step(
+            ptr::null_mut(),
+            tokenizer,
+            ids.as_ptr(),
+            ids.len(),
+            ptr::addr_of_mut!(status),
+        )
+    };
+
+    assert!(text.is_null());
+    assert_eq!(status, 1);
+
+    unsafe { tokenizers_free(tokenizer) };
+}
@@ -1,10 +1,13 @@
-use std::ffi::{c_char, CString};
+use std::ffi::{c_char, c_void, CStr, CString};
 use std::ptr;
 use tokenx_bridge::ffi::config::tokenizers_token_to_id;
-use tokenx_bridge::ffi::decode::{tokenizers_decode, tokenizers_decode_batch_flat};
-use tokenx_bridge::ffi::lifecycle::tokenizers_free_string;
+use tokenx_bridge::ffi::decode::{
+    tokenizers_decode, tokenizers_decode_batch_flat, tokenizers_decode_batch_into,
+    tokenizers_decode_with_spans,
+};
+use tokenx_bridge::ffi::lifecycle::{tokenizers_create, tokenizers_free, tokenizers_free_string};
 use tokenx_bridge::ffi::test_helpers;
-use tokenx_bridge::CTokenizer;
+use tokenx_bridge::{CEncodingOffset, CTokenizer};
 
 #[test]
 fn tokenizers_decode_round_trips_tokens() {
@@ -108,3 +111,236 @@ fn tokenizers_decode_handles_null_tokenizer() {
     assert!(result.is_null());
     assert_ne!(status, 0);
 }
+
+fn decode_spans(
+    tokenizer: &CTokenizer,
+    ids: &[u32],
+    skip_special_tokens: bool,
+    offset_mode: i32,
+) -> (String, Vec<(u32, u32)>) {
+    let mut spans: Vec<CEncodingOffset> = ids
+        .iter()
+        .map(|_| CEncodingOffset { start: 99, end: 99 })
+        .collect();
+    let mut status = -1;
+
+    let decoded = unsafe {
+        tokenizers_decode_with_spans(
+            tokenizer as *const CTokenizer,
+            ids.as_ptr(),
+            ids.len(),
+            skip_special_tokens,
+            offset_mode,
+            spans.as_mut_ptr(),
+            ptr::addr_of_mut!(status),
+        )
+    };
+
+    assert_eq!(status, 0);
+    let text = unsafe { CStr::from_ptr(decoded) }
+        .to_str()
+        .unwrap()
+        .to_string();
+    unsafe { tokenizers_free_string(decoded) };
+    (
+        text,
+        spans.iter().map(|span| (span.start, span.end)).collect(),
+    )
+}
+
+#[test]
+fn tokenizers_decode_with_spans_covers_each_token() {
+    let tokenizer = test_helpers::create_tokenizer();
+    let (text, spans) = decode_spans(&tokenizer, &[1, 2], true, 0);
+
+    assert_eq!(text, "hello world");
+    assert_eq!(spans, vec![(0, 5), (5, 11)]);
+}
+
+#[test]
+fn tokenizers_decode_with_spans_matches_decode_for_byte_level() {
+    let tokenizer = test_helpers::create_byte_level_tokenizer();
+    let ids = [4u32, 5, 3, 1, 2];
+    let (text, spans) = decode_spans(&tokenizer, &ids, true, 2);
+
+    let mut status = -1;
+    let decoded = unsafe {
+        tokenizers_decode(
+            &tokenizer as *const CTokenizer,
+            ids.as_ptr(),
+            ids.len(),
+            true,
+            ptr::addr_of_mut!(status),
+        )
+    };
+    assert_eq!(text, unsafe { CStr::from_ptr(decoded) }.to_str().unwrap());
+    unsafe { tokenizers_free_string(decoded) };
+
+    assert_eq!(text, "hello caf\u{e9}");
+    assert_eq!(spans, vec![(0, 5), (5, 5), (5, 9), (9, 9), (9, 10)]);
+}
+
+#[test]
+fn tokenizers_decode_with_spans_handles_long_generations() {
+    let tokenizer = test_helpers::create_tokenizer();
+    let ids: Vec<u32> = (0..20_000).map(|index| 1 + index % 2).collect();
+    let (text, spans) = decode_spans(&tokenizer, &ids, true, 0);
+
+    assert_eq!(text.len(), 20_000 * 6 - 1);
+    assert_eq!(spans[0], (0, 5));
+    assert!(spans.windows(2).all(|pair| pair[0].1 == pair[1].0));
+    assert_eq!(spans.last().unwrap().1 as usize, text.len());
+}
+
+/// Fuses the tokens and then rewrites `ab` to `XYZ`, which changes text a decode stream has already
+/// emitted once `b` follows `a`.
+fn rewriting_tokenizer() -> *mut CTokenizer {
+    let json = CString::new(
+        r#"{
+        "version": "1.0",
+        "truncation": null,
+        "padding": null,
+        "added_tokens": [],
+        "normalizer": null,
+        "pre_tokenizer": null,
+        "post_processor": null,
+        "decoder": {"type": "Sequence", "decoders": [
+            {"type": "Fuse"},
+            {"type": "Replace", "pattern": {"String": "ab"}, "content": "XYZ"}
+        ]},
+        "model": {"type": "WordLevel", "vocab": {"[UNK]": 0, "a": 1, "b": 2, "c": 3}, "unk_token": "[UNK]"}
+    }"#,
+    )
+    .unwrap();
+    let mut status = -1;
+    let tokenizer = unsafe { tokenizers_create(json.as_ptr(), ptr::addr_of_mut!(status)) };
+    assert_eq!(status, 0);
+    tokenizer
+}
+
+#[test]
+fn tokenizers_decode_with_spans_follows_decoder_rewrites() {
+    let tokenizer = rewriting_tokenizer();
+    let (text, spans) = decode_spans(unsafe { &*tokenizer }, &[3, 1, 2, 3], true, 0);
+    unsafe { tokenizers_free(tokenizer) };
+
+    assert_eq!(text, "cXYZc");
+    assert_eq!(spans, vec![(0, 1), (1, 1), (1, 4), (4, 5)]);
+}
+
+fn decode_into(
+    tokenizer: &CTokenizer,
+    tokens: &[u32],
+    lengths: &[usize],
+    text_encoding: i32,
+    buffer: *mut c_void,
+    capacity: usize,
+    offsets: &mut [usize],
+) -> (i32, usize, i32) {
+    let mut required = 0usize;
+    let mut status = -1;
+
+    let written = unsafe {
+        tokenizers_decode_batch_into(
+            tokenizer as *const CTokenizer,
+            tokens.as_ptr(),
+            tokens.len(),
+            lengths.as_ptr(),
+            lengths.len(),
+            true,
+            0,
+            text_encoding,
+            buffer,
+            capacity,
+            offsets.as_mut_ptr(),
+            ptr::addr_of_mut!(required),
+            ptr::addr_of_mut!(status),
+        )
+    };
+
+    (written, required, status)
+}
+
+#[test]
+fn tokenizers_decode_batch_into_queries_size_then_fills_utf8_buffer() {
+    let tokenizer = test_helpers::create_tokenizer();
+    let tokens = [1u32, 2, 2, 1];
+    let lengths = [2usize, 0, 1, 1];
+    let mut offsets = [0usize; 5];
+
+    let (written, required, status) = decode_into(
+        &tokenizer,
+        &tokens,
+        &lengths,
+        0,
+        ptr::null_mut(),
+        0,
+        &mut offsets,
+    );
+    assert_eq!((written, required, status), (0, 21, 0));
+
+    let mut buffer = vec![0u8; required];
+    let (written, _, status) = decode_into(
+        &tokenizer,
+        &tokens,
+        &lengths,
+        0,
+        buffer.as_mut_ptr() as *mut c_void,
+        buffer.len(),
+        &mut offsets,
+    );
+
+    assert_eq!((written, status), (4, 0));
+    assert_eq!(
+        std::str::from_utf8(&buffer).unwrap(),
+        "hello worldworldhello"
+    );
+    assert_eq!(offsets, [0, 11, 11, 16, 21]);
+}
+
+#[test]
+fn tokenizers_decode_batch_into_writes_utf16_units() {
+    let tokenizer = test_helpers::create_byte_level_tokenizer();
+    let tokens = [3u32, 1, 2, 4];
+    let lengths = [3usize, 1];
+    let mut offsets = [0usize; 3];
+    let mut buffer = [0u16; 16];
+
+    let (written, required, status) = decode_into(
+        &tokenizer,
+        &tokens,
+        &lengths,
+        1,
+        buffer.as_mut_ptr() as *mut c_void,
+        buffer.len(),
+        &mut offsets,
+    );
+
+    assert_eq!((written, required, status), (2, 10, 0));
+    assert_eq!(
+        String::from_utf16(&buffer[..required]).unwrap(),
+        " caf\u{e9}hello"
+    );
+    assert_eq!(offsets, [0, 5, 10]);
+}
+
+#[test]
+fn tokenizers_decode_batch_into_reports_small_buffer() {
+    let tokenizer = test_helpers::create_tokenizer();
+    let tokens = [1u32, 2];
+    let lengths = [2usize];
+    let mut offsets = [0usize; 2];
+    let mut buffer = [0u8; 4];
+
+    let (written, required, status) = decode_into(
+        &tokenizer,
+        &tokens,
+        &lengths,
+        0,
+        buffer.as_mut_ptr() as *mut c_void,
+        buffer.len(),
+        &mut offsets,
+    );
+
+    assert_eq!((written, required, status), (0, 11, 9));
+}
@@ -0,0 +1,288 @@
+use std::ffi::{c_char, CStr, CString};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::ptr;
+use tokenx_bridge::ffi::config::{tokenizers_get_config, tokenizers_get_padding};
+use tokenx_bridge::ffi::directory::tokenizers_load_directory;
+use tokenx_bridge::ffi::lean_encoding::tokenizers_encode_ids;
+use tokenx_bridge::ffi::lifecycle::{tokenizers_free, tokenizers_free_string};
+use tokenx_bridge::ffi::test_helpers;
+use tokenx_bridge::CTokenizer;
+
+fn take_string(value: *mut c_char) -> String {
+    assert!(!value.is_null());
+    let text = unsafe { CStr::from_ptr(value) }
+        .to_str()
+        .unwrap()
+        .to_string();
+    unsafe { tokenizers_free_string(value) };
+    text
+}
+
+fn fixture_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("tokenx-directory-{}-{name}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    for (file, content) in files {
+        fs::write(dir.join(file), content).unwrap();
+    }
+    dir
+}
+
+fn load(path: &Path, apply_defaults: bool) -> (*mut CTokenizer, serde_json::Value) {
+    let path = CString::new(path.to_str().unwrap()).unwrap();
+    let mut metadata: *mut c_char = ptr::null_mut();
+    let mut status = -1;
+    let tokenizer = unsafe {
+        tokenizers_load_directory(
+            path.as_ptr(),
+            apply_defaults,
+            ptr::addr_of_mut!(metadata),
+            ptr::addr_of_mut!(status),
+        )
+    };
+    assert_eq!(status, 0);
+    assert!(!tokenizer.is_null());
+    let metadata = serde_json::from_str(&take_string(metadata)).unwrap();
+    (tokenizer, metadata)
+}
+
+fn encode(tokenizer: *const CTokenizer, text: &str) -> Vec<u32> {
+    let text = CString::new(text).unwrap();
+    let mut ids = [0u32; 16];
+    let mut length = 0usize;
+    let mut status = -1;
+    unsafe {
+        tokenizers_encode_ids(
+            tokenizer,
+            text.as_ptr(),
+            ptr::null(),
+            true,
+            ids.as_mut_ptr(),
+            ptr::null_mut(),
+            ids.len(),
+            ptr::addr_of_mut!(length),
+            ptr::addr_of_mut!(status),
+        );
+    }
+    assert_eq!(status, 0);
+    ids[..length].to_vec()
+}
+
+fn model_directory(name: &str) -> PathBuf {
+    let tokenizer_json = test_helpers::tokenizer_config_json();
+    fixture_dir(
+        name,
+        &[
+            ("tokenizer.json", tokenizer_json.to_str().unwrap()),
+            (
+                "tokenizer_config.json",
+                r#"{"tokenizer_class": "PreTrainedTokenizerFast", "model_max_length": 3,
+                    "padding_side": "left", "add_bos_token": true, "add_eos_token": false,
+                    "bos_token": "[UNK]", "clean_up_tokenization_spaces": false}"#,
+            ),
+            (
+                "special_tokens_map.json",
+                r#"{"bos_token": {"content": "<s>", "normalized": false}, "pad_token": "<pad>"}"#,
+            ),
+            ("added_tokens.json", r#"{"<s>": 3, "<pad>": 4}"#),
+        ],
+    )
+}
+
+#[test]
+fn load_directory_applies_configuration_files() {
+    let dir = model_directory("applied");
+    let (tokenizer, metadata) = load(&dir, true);
+
+    assert_eq!(encode(tokenizer, "hello world"), vec![3, 1, 2]);
+    assert_eq!(encode(tokenizer, "world world hello"), vec![3, 2, 2]);
+
+    assert_eq!(metadata["model_max_length"], 3);
+    assert_eq!(metadata["padding_side"], "left");
+    assert_eq!(metadata["clean_up_tokenization_spaces"], false);
+    assert_eq!(metadata["converted"], false);
+    assert_eq!(metadata["special_tokens"]["bos_token"]["content"], "<s>");
+    assert_eq!(metadata["special_tokens"]["bos_token"]["id"], 3);
+    assert_eq!(metadata["special_tokens"]["pad_token"]["id"], 4);
+    assert_eq!(metadata["added_tokens"][1]["content"], "<pad>");
+    assert!(metadata["files"]
+        .as_array()
+        .unwrap()
+        .contains(&serde_json::json!("special_tokens_map.json")));
+
+    let mut status = -1;
+    let padding =
+        take_string(unsafe { tokenizers_get_padding(tokenizer, ptr::addr_of_mut!(status)) });
+    assert!(padding.contains("\"Left\""));
+    assert!(padding.contains("\"<pad>\""));
+
+    unsafe { tokenizers_free(tokenizer) };
+}
+
+#[test]
+fn load_directory_without_defaults_keeps_pipeline() {
+    let dir = model_directory("plain");
+    let (tokenizer, metadata) = load(&dir.join("tokenizer.json"), false);
+
+    assert_eq!(encode(tokenizer, "world world hello"), vec![2, 2, 1]);
+    assert_eq!(metadata["add_bos_token"], true);
+
+    let mut status = -1;
+    let padding = unsafe { tokenizers_get_padding(tokenizer, ptr::addr_of_mut!(status)) };
+    assert!(padding.is_null());
+
+    unsafe { tokenizers_free(tokenizer) };
+}
+
+#[test]
+fn load_directory_converts_legacy_vocab_files() {
+    let dir = fixture_dir(
+        "legacy",
+        &[
+            ("vocab.txt", "[PAD]\n[UNK]\n[CLS]\n[SEP]\n[MASK]\nhello\n"),
+            (
+                "tokenizer_config.json",
+                r#"{"tokenizer_class": "BertTokenizer"}"#,
+            ),
+        ],
+    );
+    let (tokenizer, metadata) = load(&dir, true);
+
+    assert_eq!(encode(tokenizer, "Hello"), vec![2, 5, 3]);
+    assert_eq!(metadata["converted"], true);
+    assert_eq!(metadata["special_tokens"], serde_json::json!({}));
+
+    unsafe { tokenizers_free(tokenizer) };
+}
+
+/// WordLevel without a pre-tokenizer, so `hello ` only encodes as `hello` when `<mask>` strips
+/// the space to its left.
+const LSTRIP_MASK_TOKENIZER: &str = r#"{
+    "version": "1.0",
+    "truncation": null,
+    "padding": null,
+    "added_tokens": [
+        {"id": 2, "content": "<mask>", "single_word": false, "lstrip": true, "rstrip": false, "normalized": false, "special": true}
+    ],
+    "normalizer": null,
+    "pre_tokenizer": null,
+    "post_processor": null,
+    "decoder": null,
+    "model": {"type": "WordLevel", "vocab": {"[UNK]": 0, "hello": 1, "<mask>": 2}, "unk_token": "[UNK]"}
+}"#;
+
+#[test]
+fn load_directory_keeps_flags_of_declared_special_tokens() {
+    let dir = fixture_dir(
+        "lstrip",
+        &[
+            ("tokenizer.json", LSTRIP_MASK_TOKENIZER),
+            ("special_tokens_map.json", r#"{"mask_token": "<mask>"}"#),
+        ],
+    );
+    let (tokenizer, metadata) = load(&dir, true);
+
+    assert_eq!(metadata["special_tokens"]["mask_token"]["id"], 2);
+    assert_eq!(encode(tokenizer, "hello <mask>"), vec![1, 2]);
+
+    unsafe { tokenizers_free(tokenizer) };
+}
+
+/// WordLevel with `<s>` = 3 and `</s>` = 4 and the given post-processor.
+fn post_processor_tokenizer(post_processor: &str) -> String {
+    format!(
+        r#"{{
+    "version": "1.0",
+    "truncation": null,
+    "padding": null,
+    "added_tokens": [
+        {{"id": 3, "content": "<s>", "single_word": false, "lstrip": false, "rstrip": false, "normalized": false, "special": true}},
+        {{"id": 4, "content": "</s>", "single_word": false, "lstrip": false, "rstrip": false, "normalized": false, "special": true}}
+    ],
+    "normalizer": null,
+    "pre_tokenizer": {{"type": "Whitespace"}},
+    "post_processor": {post_processor},
+    "decoder": null,
+    "model": {{"type": "WordLevel", "vocab": {{"[UNK]": 0, "hello": 1, "world": 2, "<s>": 3, "</s>": 4}}, "unk_token": "[UNK]"}}
+}}"#
+    )
+}
+
+#[test]
+fn load_directory_keeps_post_processor_when_flags_are_false() {
+    let template = r#"{"type": "TemplateProcessing",
+        "single": [{"SpecialToken": {"id": "<s>", "type_id": 0}}, {"Sequence": {"id": "A", "type_id": 0}}],
+        "pair": [{"Sequence": {"id": "A", "type_id": 0}}, {"Sequence": {"id": "B", "type_id": 1}}],
+        "special_tokens": {"<s>": {"id": "<s>", "ids": [3], "tokens": ["<s>"]}}}"#;
+    let config = r#"{"bos_token": "<s>", "eos_token": "</s>", "add_bos_token": false, "add_eos_token": false}"#;
+
+    let dir = fixture_dir(
+        "flags-false",
+        &[
+            ("tokenizer.json", &post_processor_tokenizer(template)),
+            ("tokenizer_config.json", config),
+        ],
+    );
+    let (tokenizer, _) = load(&dir, true);
+    assert_eq!(encode(tokenizer, "hello"), vec![3, 1]);
+    unsafe { tokenizers_free(tokenizer) };
+
+    let llama = config.replace("{", r#"{"tokenizer_class": "LlamaTokenizerFast", "#);
+    let dir = fixture_dir(
+        "flags-false-llama",
+        &[
+            ("tokenizer.json", &post_processor_tokenizer(template)),
+            ("tokenizer_config.json", &llama),
+        ],
+    );
+    let (tokenizer, _) = load(&dir, true);
+    assert_eq!(encode(tokenizer, "hello"), vec![1]);
+    unsafe { tokenizers_free(tokenizer) };
+}
+
+#[test]
+fn load_directory_keeps_byte_level_post_processing() {
+    let byte_level = r#"{"type": "ByteLevel", "add_prefix_space": false, "trim_offsets": true, "use_regex": true}"#;
+    let dir = fixture_dir(
+        "byte-level",
+        &[
+            ("tokenizer.json", &post_processor_tokenizer(byte_level)),
+            (
+                "tokenizer_config.json",
+                r#"{"bos_token": "<s>", "add_bos_token": true}"#,
+            ),
+        ],
+    );
+    let (tokenizer, _) = load(&dir, true);
+    assert_eq!(encode(tokenizer, "hello world"), vec![3, 1, 2]);
+
+    let mut status = -1;
+    let config: serde_json::Value = serde_json::from_str(&take_string(unsafe {
+        tokenizers_get_config(tokenizer, false, ptr::addr_of_mut!(status))
+    }))
+    .unwrap();
+    let steps = &config["post_processor"]["processors"];
+    assert_eq!(config["post_processor"]["type"], "Sequence");
+    assert_eq!(steps[0]["type"], "ByteLevel");
+    assert_eq!(steps[1]["type"], "TemplateProcessing");
+
+    unsafe { tokenizers_free(tokenizer) };
+}
+
+#[test]
+fn load_directory_reports_missing_location() {
+    let path = CString::new("/nonexistent/tokenx/model").unwrap();
+    let mut metadata: *mut c_char = ptr::null_mut();
+    let mut status = -1;
+    let tokenizer = unsafe {
+        tokenizers_load_directory(
+            path.as_ptr(),
+            true,
+            ptr::addr_of_mut!(metadata),
+            ptr::addr_of_mut!(status),
+        )
+    };
+    assert!(tokenizer.is_null());
+    assert!(metadata.is_null());
+    assert_eq!(status, 2);
+}
@@ -0,0 +1,270 @@
+use std::ffi::CString;
+use std::ptr;
+use tokenx_bridge::ffi::encoding::{
+    tokenizers_encode, tokenizers_encoding_free, tokenizers_encoding_get_attention_mask,
+    tokenizers_encoding_get_ids, tokenizers_encoding_get_offsets,
+    tokenizers_encoding_get_overflowing, tokenizers_encoding_get_overflowing_count,
+};
+use tokenx_bridge::ffi::encoding_ops::{
+    tokenizers_encoding_merge, tokenizers_encoding_pad, tokenizers_encoding_truncate,
+};
+use tokenx_bridge::ffi::mapping::{
+    tokenizers_encoding_char_to_token, tokenizers_encoding_token_to_sequence,
+};
+use tokenx_bridge::ffi::test_helpers;
+use tokenx_bridge::{CEncoding, CTokenizer};
+
+fn encode_text(tokenizer: &mut CTokenizer, text: &str) -> *mut CEncoding {
+    let text = CString::new(text).unwrap();
+    let mut status = -1;
+    let encoding = unsafe {
+        tokenizers_encode(
+            tokenizer as *mut CTokenizer,
+            text.as_ptr(),
+            ptr::null(),
+            true,
+            ptr::null_mut(),
+            ptr::addr_of_mut!(status),
+        )
+    };
+    assert_eq!(status, 0);
+    encoding
+}
+
+fn read_ids(encoding: *const CEncoding, length: usize) -> Vec<u32> {
+    let mut ids = vec![0u32; length];
+    unsafe {
+        tokenizers_encoding_get_ids(encoding, ids.as_mut_ptr(), ids.len());
+    }
+    ids
+}
+
+#[test]
+fn tokenizers_encoding_merge_concatenates_with_growing_offsets() {
+    let mut tokenizer = test_helpers::create_tokenizer();
+    let first = encode_text(&mut tokenizer, "hello");
+    let second = encode_text(&mut tokenizer, "world");
+    let parts = [first as *const CEncoding, second as *const CEncoding];
+
+    for (growing, expected_offsets) in [(true, [0u32, 5, 5, 10]), (false, [0u32, 5, 0, 5])] {
+        let mut length = 0usize;
+        let mut status = -1;
+        let merged = unsafe {
+            tokenizers_encoding_merge(
+                parts.as_ptr(),
+                parts.len(),
+                growing,
+                ptr::addr_of_mut!(length),
+                ptr::addr_of_mut!(status),
+            )
+        };
+
+        assert_eq!(status, 0);
+        assert_eq!(read_ids(merged, length), vec![1, 2]);
+
+        let mut offsets = [0u32; 4];
+        unsafe {
+            tokenizers_encoding_get_offsets(merged, offsets.as_mut_ptr(), offsets.len());
+            tokenizers_encoding_free(merged);
+        }
+        assert_eq!(offsets, expected_offsets);
+    }
+
+    unsafe {
+        tokenizers_encoding_free(first);
+        tokenizers_encoding_free(second);
+    }
+}
+
+fn sequences(encoding: *const CEncoding, length: usize) -> Vec<i32> {
+    (0..length)
+        .map(|token| unsafe {
+            tokenizers_encoding_token_to_sequence(encoding, token, ptr::null_mut())
+        })
+        .collect()
+}
+
+#[test]
+fn tokenizers_encoding_merge_keeps_sequence_of_every_part() {
+    let mut tokenizer = test_helpers::create_tokenizer();
+    let first = encode_text(&mut tokenizer, "hello world");
+    let second = encode_text(&mut tokenizer, "world");
+    let parts = [first as *const CEncoding, second as *const CEncoding];
+    let mut length = 0usize;
+    let mut status = -1;
+
+    let merged = unsafe {
+        tokenizers_encoding_merge(
+            parts.as_ptr(),
+            parts.len(),
+            true,
+            ptr::addr_of_mut!(length),
+            ptr::addr_of_mut!(status),
+        )
+    };
+    assert_eq!(status, 0);
+    assert_eq!(sequences(merged, length), vec![0, 0, 0]);
+    let token =
+        unsafe { tokenizers_encoding_char_to_token(merged, 7, 0, ptr::addr_of_mut!(status)) };
+    assert_eq!((token, status), (1, 0));
+
+    unsafe {
+        tokenizers_encoding_free(merged);
+        tokenizers_encoding_free(first);
+        tokenizers_encoding_free(second);
+    }
+}
+
+#[test]
+fn tokenizers_encoding_pad_and_truncate_leave_padding_without_sequence() {
+    let mut tokenizer = test_helpers::create_tokenizer();
+    let encoding = encode_text(&mut tokenizer, "hello");
+    let mut length = 0usize;
+    let mut status = -1;
+
+    let padded = unsafe {
+        tokenizers_encoding_pad(
+            encoding,
+            3,
+            0,
+            0,
+            ptr::null(),
+            0,
+            ptr::addr_of_mut!(length),
+            ptr::addr_of_mut!(status),
+        )
+    };
+    assert_eq!(sequences(padded, length), vec![-1, -1, 0]);
+
+    let truncated = unsafe {
+        tokenizers_encoding_truncate(
+            padded,
+            2,
+            0,
+            1,
+            ptr::addr_of_mut!(length),
+            ptr::addr_of_mut!(status),
+        )
+    };
+    assert_eq!(status, 0);
+    assert_eq!(sequences(truncated, length), vec![-1, -1]);
+
+    let mut overflow_length = 0usize;
+    let overflow = unsafe {
+        tokenizers_encoding_get_overflowing(
+            truncated,
+            0,
+            ptr::addr_of_mut!(overflow_length),
+            ptr::addr_of_mut!(status),
+        )
+    };
+    assert_eq!(sequences(overflow, overflow_length), vec![0]);
+
+    unsafe {
+        tokenizers_encoding_free(overflow);
+        tokenizers_encoding_free(truncated);
+        tokenizers_encoding_free(padded);
+        tokenizers_encoding_free(encoding);
+    }
+}
+
+#[test]
+fn tokenizers_encoding_pad_returns_new_left_padded_handle() {
+    let mut tokenizer = test_helpers::create_tokenizer();
+    let encoding = encode_text(&mut tokenizer, "hello");
+    let mut length = 0usize;
+    let mut status = -1;
+
+    let padded = unsafe {
+        tokenizers_encoding_pad(
+            encoding,
+            3,
+            0,
+            0,
+            ptr::null(),
+            0,
+            ptr::addr_of_mut!(length),
+            ptr::addr_of_mut!(status),
+        )
+    };
+
+    assert_eq!(status, 0);
+    assert_eq!(read_ids(padded, length), vec![0, 0, 1]);
+    assert_eq!(read_ids(encoding, 1), vec![1]);
+
+    let mut mask = vec![0u32; length];
+    unsafe {
+        tokenizers_encoding_get_attention_mask(padded, mask.as_mut_ptr(), mask.len());
+        tokenizers_encoding_free(padded);
+        tokenizers_encoding_free(encoding);
+    }
+    assert_eq!(mask, vec![0, 0, 1]);
+}
+
+#[test]
+fn tokenizers_encoding_truncate_produces_overflowing_windows() {
+    let mut tokenizer = test_helpers::create_tokenizer();
+    let encoding = encode_text(&mut tokenizer, "hello world hello");
+    let mut length = 0usize;
+    let mut status = -1;
+
+    let truncated = unsafe {
+        tokenizers_encoding_truncate(
+            encoding,
+            2,
+            1,
+            1,
+            ptr::addr_of_mut!(length),
+            ptr::addr_of_mut!(status),
+        )
+    };
+
+    assert_eq!(status, 0);
+    assert_eq!(read_ids(truncated, length), vec![1, 2]);
+    assert_eq!(
+        unsafe { tokenizers_encoding_get_overflowing_count(truncated) },
+        1
+    );
+
+    let mut overflow_length = 0usize;
+    let overflow = unsafe {
+        tokenizers_encoding_get_overflowing(
+            truncated,
+            0,
+            ptr::addr_of_mut!(overflow_length),
+            ptr::addr_of_mut!(status),
+        )
+    };
+    assert_eq!(read_ids(overflow, overflow_length), vec![2, 1]);
+
+    unsafe {
+        tokenizers_encoding_free(overflow);
+        tokenizers_encoding_free(truncated);
+        tokenizers_encoding_free(encoding);
+    }
+}
+
+#[test]
+fn tokenizers_encoding_truncate_rejects_stride_not_below_max_length() {
+    let mut tokenizer = test_helpers::create_tokenizer();
+    let encoding = encode_text(&mut tokenizer, "hello world hello");
+    let mut status = -1;
+
+    let truncated = unsafe {
+        tokenizers_encoding_truncate(
+            encoding,
+            2,
+            2,
+            1,
+            ptr::null_mut(),
+            ptr::addr_of_mut!(status),
+        )
+    };
+
+    assert!(truncated.is_null());
+    assert_ne!(status, 0);
+
+    unsafe {
+        tokenizers_encoding_free(encoding);
+    }
+}
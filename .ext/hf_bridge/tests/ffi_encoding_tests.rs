@@ -1,11 +1,15 @@
 use std::ffi::{c_char, CString};
 use std::ptr;
 use tokenx_bridge::ffi::encoding::{
-    tokenizers_encode, tokenizers_encoding_copy_numeric, tokenizers_encoding_free,
-    tokenizers_encoding_get_ids, tokenizers_encoding_get_overflowing,
-    tokenizers_encoding_get_tokens,
+    tokenizers_encode, tokenizers_encode_batch, tokenizers_encode_pretokenized,
+    tokenizers_encode_utf16, tokenizers_encode_with_offsets, tokenizers_encoding_copy_numeric,
+    tokenizers_encoding_free, tokenizers_encoding_get_attention_mask, tokenizers_encoding_get_ids,
+    tokenizers_encoding_get_offsets, tokenizers_encoding_get_overflowing,
+    tokenizers_encoding_get_sequence_ids, tokenizers_encoding_get_tokens,
+    tokenizers_encoding_get_word_ids,
 };
 use tokenx_bridge::ffi::lifecycle::tokenizers_free_string;
+use tokenx_bridge::ffi::padding::tokenizers_enable_padding;
 use tokenx_bridge::ffi::test_helpers;
 use tokenx_bridge::{CEncoding, CEncodingNumericDest, CEncodingOffset, CTokenizer};
 
@@ -143,3 +147,284 @@ fn tokenizers_encoding_get_overflowing_reports_missing_index() {
         tokenizers_encoding_free(encoding);
     }
 }
+
+fn encode_sample_batch(
+    tokenizer: &CTokenizer,
+    pairs: Option<&[*const c_char]>,
+    max_threads: usize,
+) -> (Vec<*mut CEncoding>, Vec<usize>, i32, i32) {
+    let texts = [
+        CString::new("hello world").unwrap(),
+        CString::new("hello").unwrap(),
+    ];
+    let sequences: Vec<*const c_char> = texts.iter().map(|text| text.as_ptr()).collect();
+    let mut output: Vec<*mut CEncoding> = vec![ptr::null_mut(); sequences.len()];
+    let mut lengths = vec![0usize; sequences.len()];
+    let mut status = -1;
+
+    let count = unsafe {
+        tokenizers_encode_batch(
+            tokenizer as *const CTokenizer,
+            sequences.as_ptr(),
+            pairs.map_or(ptr::null(), |values| values.as_ptr()),
+            sequences.len(),
+            true,
+            max_threads,
+            output.as_mut_ptr(),
+            lengths.as_mut_ptr(),
+            ptr::addr_of_mut!(status),
+        )
+    };
+
+    (output, lengths, count, status)
+}
+
+#[test]
+fn tokenizers_encode_batch_pads_to_longest_sequence() {
+    let mut tokenizer = test_helpers::create_tokenizer();
+    let pad_token = CString::new("[UNK]").unwrap();
+    unsafe {
+        tokenizers_enable_padding(
+            &mut tokenizer as *mut CTokenizer,
+            1,
+            0,
+            0,
+            pad_token.as_ptr(),
+            -1,
+            0,
+            ptr::null_mut(),
+        );
+    }
+
+    for max_threads in [0usize, 1, 2] {
+        let (output, lengths, count, status) = encode_sample_batch(&tokenizer, None, max_threads);
+        assert_eq!(status, 0);
+        assert_eq!(count, 2);
+        assert_eq!(lengths, vec![2, 2]);
+
+        let mut mask = vec![0u32; lengths[1]];
+        unsafe {
+            tokenizers_encoding_get_attention_mask(output[1], mask.as_mut_ptr(), mask.len());
+        }
+        assert_eq!(mask, vec![1, 0]);
+
+        for encoding in output {
+            unsafe {
+                tokenizers_encoding_free(encoding);
+            }
+        }
+    }
+}
+
+#[test]
+fn tokenizers_encode_batch_accepts_optional_pairs() {
+    let tokenizer = test_helpers::create_tokenizer();
+    let pair = CString::new("world").unwrap();
+    let pairs = [pair.as_ptr(), ptr::null()];
+
+    let (output, lengths, count, status) = encode_sample_batch(&tokenizer, Some(&pairs), 0);
+    assert_eq!(status, 0);
+    assert_eq!(count, 2);
+    assert_eq!(lengths, vec![3, 1]);
+
+    for encoding in output {
+        unsafe {
+            tokenizers_encoding_free(encoding);
+        }
+    }
+}
+
+#[test]
+fn tokenizers_encode_batch_rejects_null_sequences() {
+    let tokenizer = test_helpers::create_tokenizer();
+    let mut output: Vec<*mut CEncoding> = vec![ptr::null_mut(); 1];
+    let mut status = -1;
+
+    let count = unsafe {
+        tokenizers_encode_batch(
+            &tokenizer as *const CTokenizer,
+            ptr::null(),
+            ptr::null(),
+            1,
+            true,
+            0,
+            output.as_mut_ptr(),
+            ptr::null_mut(),
+            ptr::addr_of_mut!(status),
+        )
+    };
+
+    assert_eq!(count, 0);
+    assert_ne!(status, 0);
+    assert!(output[0].is_null());
+}
+
+fn read_offsets(encoding: *const CEncoding, length: usize) -> Vec<u32> {
+    let mut offsets = vec![0u32; length * 2];
+    unsafe {
+        tokenizers_encoding_get_offsets(encoding, offsets.as_mut_ptr(), offsets.len());
+    }
+    offsets
+}
+
+#[test]
+fn tokenizers_encode_with_offsets_honors_offset_mode() {
+    let tokenizer = test_helpers::create_tokenizer();
+    let text = CString::new("hello \u{1F600} world").unwrap();
+    let expectations = [
+        (0, vec![0u32, 5, 6, 10, 11, 16]),
+        (1, vec![0u32, 5, 6, 7, 8, 13]),
+        (2, vec![0u32, 5, 6, 8, 9, 14]),
+    ];
+
+    for (mode, expected) in expectations {
+        let mut length = 0usize;
+        let mut status = -1;
+        let encoding = unsafe {
+            tokenizers_encode_with_offsets(
+                &tokenizer as *const CTokenizer,
+                text.as_ptr(),
+                ptr::null(),
+                true,
+                mode,
+                ptr::addr_of_mut!(length),
+                ptr::addr_of_mut!(status),
+            )
+        };
+
+        assert_eq!(status, 0);
+        assert_eq!(read_offsets(encoding, length), expected);
+
+        unsafe {
+            tokenizers_encoding_free(encoding);
+        }
+    }
+}
+
+#[test]
+fn tokenizers_encode_utf16_reports_code_unit_offsets_for_pairs() {
+    let tokenizer = test_helpers::create_tokenizer();
+    let sequence: Vec<u16> = "\u{1F600} hello".encode_utf16().collect();
+    let pair: Vec<u16> = "world".encode_utf16().collect();
+    let mut length = 0usize;
+    let mut status = -1;
+
+    let encoding = unsafe {
+        tokenizers_encode_utf16(
+            &tokenizer as *const CTokenizer,
+            sequence.as_ptr(),
+            sequence.len(),
+            pair.as_ptr(),
+            pair.len(),
+            true,
+            2,
+            ptr::addr_of_mut!(length),
+            ptr::addr_of_mut!(status),
+        )
+    };
+
+    assert_eq!(status, 0);
+    assert_eq!(length, 3);
+
+    let mut offsets = vec![CEncodingOffset::default(); length];
+    let mut ids = vec![0u32; length];
+    let mut type_ids = vec![0u32; length];
+    let mut attention_mask = vec![0u32; length];
+    let mut special_tokens_mask = vec![0u32; length];
+    let mut word_ids = vec![0i32; length];
+    let mut sequence_ids = vec![0i32; length];
+    let mut destination = CEncodingNumericDest {
+        ids: ids.as_mut_ptr(),
+        type_ids: type_ids.as_mut_ptr(),
+        attention_mask: attention_mask.as_mut_ptr(),
+        special_tokens_mask: special_tokens_mask.as_mut_ptr(),
+        offsets: offsets.as_mut_ptr(),
+        word_ids: word_ids.as_mut_ptr(),
+        sequence_ids: sequence_ids.as_mut_ptr(),
+    };
+    let mut copy_status = -1;
+
+    unsafe {
+        tokenizers_encoding_copy_numeric(
+            encoding,
+            ptr::addr_of_mut!(destination),
+            length,
+            ptr::addr_of_mut!(copy_status),
+        );
+        tokenizers_encoding_free(encoding);
+    }
+
+    assert_eq!(copy_status, 0);
+    let spans: Vec<(u32, u32)> = offsets.iter().map(|o| (o.start, o.end)).collect();
+    assert_eq!(spans, vec![(0, 2), (3, 8), (0, 5)]);
+    assert_eq!(sequence_ids, vec![0, 0, 1]);
+}
+
+#[test]
+fn tokenizers_encode_utf16_rejects_lone_surrogates() {
+    let tokenizer = test_helpers::create_tokenizer();
+    let sequence = [0xD800u16, 0x0041];
+    let mut status = -1;
+
+    let encoding = unsafe {
+        tokenizers_encode_utf16(
+            &tokenizer as *const CTokenizer,
+            sequence.as_ptr(),
+            sequence.len(),
+            ptr::null(),
+            0,
+            true,
+            2,
+            ptr::null_mut(),
+            ptr::addr_of_mut!(status),
+        )
+    };
+
+    assert!(encoding.is_null());
+    assert_ne!(status, 0);
+}
+
+#[test]
+fn tokenizers_encode_pretokenized_maps_word_ids_to_input_words() {
+    let tokenizer = test_helpers::create_tokenizer();
+    let words = [
+        CString::new("hello world").unwrap(),
+        CString::new("hello").unwrap(),
+    ];
+    let pair_words = [CString::new("world").unwrap()];
+    let word_ptrs: Vec<*const c_char> = words.iter().map(|word| word.as_ptr()).collect();
+    let pair_ptrs: Vec<*const c_char> = pair_words.iter().map(|word| word.as_ptr()).collect();
+    let mut length = 0usize;
+    let mut status = -1;
+
+    let encoding = unsafe {
+        tokenizers_encode_pretokenized(
+            &tokenizer as *const CTokenizer,
+            word_ptrs.as_ptr(),
+            word_ptrs.len(),
+            pair_ptrs.as_ptr(),
+            pair_ptrs.len(),
+            true,
+            ptr::addr_of_mut!(length),
+            ptr::addr_of_mut!(status),
+        )
+    };
+
+    assert_eq!(status, 0);
+    assert_eq!(length, 4);
+
+    let mut word_ids = vec![0i32; length];
+    let mut sequence_ids = vec![0i32; length];
+    unsafe {
+        tokenizers_encoding_get_word_ids(encoding, word_ids.as_mut_ptr(), word_ids.len());
+        tokenizers_encoding_get_sequence_ids(
+            encoding,
+            sequence_ids.as_mut_ptr(),
+            sequence_ids.len(),
+        );
+        tokenizers_encoding_free(encoding);
+    }
+
+    assert_eq!(word_ids, vec![0, 0, 1, 0]);
+    assert_eq!(sequence_ids, vec![0, 0, 0, 1]);
+}
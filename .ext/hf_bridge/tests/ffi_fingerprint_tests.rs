@@ -0,0 +1,152 @@
+use std::ffi::{CStr, CString};
+use std::ptr;
+use tokenx_bridge::ffi::fingerprint::tokenizers_fingerprint;
+use tokenx_bridge::ffi::lifecycle::{tokenizers_create, tokenizers_free, tokenizers_free_string};
+use tokenx_bridge::ffi::padding::tokenizers_enable_padding;
+use tokenx_bridge::CTokenizer;
+
+const BPE_TOKENIZER: &str = r#"{
+    "version": "1.0",
+    "truncation": null,
+    "padding": null,
+    "added_tokens": [
+        {"id": 0, "content": "<s>", "single_word": false, "lstrip": false, "rstrip": false, "normalized": false, "special": true},
+        {"id": 1, "content": "</s>", "single_word": false, "lstrip": false, "rstrip": false, "normalized": false, "special": true}
+    ],
+    "normalizer": {"type": "NFC"},
+    "pre_tokenizer": {"type": "Whitespace"},
+    "post_processor": null,
+    "decoder": null,
+    "model": {
+        "type": "BPE",
+        "dropout": null,
+        "unk_token": null,
+        "continuing_subword_prefix": null,
+        "end_of_word_suffix": null,
+        "fuse_unk": false,
+        "byte_fallback": false,
+        "ignore_merges": false,
+        "vocab": {"<s>": 0, "</s>": 1, "h": 2, "i": 3, "hi": 4},
+        "merges": [["h", "i"]]
+    }
+}"#;
+
+/// The same tokenizer written differently: compact, keys reordered, added tokens listed out of
+/// order and merges in the legacy `"a b"` spelling.
+const REORDERED_TOKENIZER: &str = r#"{"model":{"merges":["h i"],"vocab":{"hi":4,"i":3,"h":2,"</s>":1,"<s>":0},"type":"BPE","dropout":null,"unk_token":null,"continuing_subword_prefix":null,"end_of_word_suffix":null,"fuse_unk":false,"byte_fallback":false,"ignore_merges":false},"decoder":null,"post_processor":null,"pre_tokenizer":{"type":"Whitespace"},"normalizer":{"type":"NFC"},"added_tokens":[{"special":true,"normalized":false,"rstrip":false,"lstrip":false,"single_word":false,"content":"</s>","id":1},{"id":0,"content":"<s>","single_word":false,"lstrip":false,"rstrip":false,"normalized":false,"special":true}],"padding":null,"truncation":null,"version":"1.0"}"#;
+
+fn create(json: &str) -> *mut CTokenizer {
+    let json = CString::new(json).unwrap();
+    let mut status = -1;
+    let tokenizer = unsafe { tokenizers_create(json.as_ptr(), ptr::addr_of_mut!(status)) };
+    assert_eq!(status, 0);
+    tokenizer
+}
+
+fn fingerprint(tokenizer: *const CTokenizer) -> serde_json::Value {
+    let mut status = -1;
+    let json = unsafe { tokenizers_fingerprint(tokenizer, ptr::addr_of_mut!(status)) };
+    assert_eq!(status, 0);
+    assert!(!json.is_null());
+    let text = unsafe { CStr::from_ptr(json) }
+        .to_str()
+        .unwrap()
+        .to_string();
+    unsafe { tokenizers_free_string(json) };
+    serde_json::from_str(&text).unwrap()
+}
+
+#[test]
+fn fingerprint_ignores_cosmetic_json_differences() {
+    let original = create(BPE_TOKENIZER);
+    let reordered = create(REORDERED_TOKENIZER);
+
+    let first = fingerprint(original);
+    assert_eq!(first, fingerprint(reordered));
+    assert_eq!(first, fingerprint(original));
+    assert_eq!(first["version"], 1);
+    let digest = first["tokenizer"].as_str().unwrap();
+    assert_eq!(digest.len(), 64);
+    assert!(digest.bytes().all(|byte| byte.is_ascii_hexdigit()));
+
+    unsafe {
+        tokenizers_free(original);
+        tokenizers_free(reordered);
+    }
+}
+
+#[test]
+fn fingerprint_components_pinpoint_behavior_changes() {
+    let original = create(BPE_TOKENIZER);
+    let lowercased =
+        create(&BPE_TOKENIZER.replace(r#"{"type": "NFC"}"#, r#"{"type": "Lowercase"}"#));
+    let renumbered = create(&BPE_TOKENIZER.replace(r#""hi": 4"#, r#""hi": 5"#));
+
+    let base = fingerprint(original);
+    let normalizer = fingerprint(lowercased);
+    let model = fingerprint(renumbered);
+
+    assert_ne!(base["tokenizer"], normalizer["tokenizer"]);
+    assert_ne!(
+        base["components"]["normalizer"],
+        normalizer["components"]["normalizer"]
+    );
+    assert_eq!(
+        base["components"]["model"],
+        normalizer["components"]["model"]
+    );
+
+    assert_ne!(base["tokenizer"], model["tokenizer"]);
+    assert_ne!(base["components"]["model"], model["components"]["model"]);
+    for section in [
+        "normalizer",
+        "pre_tokenizer",
+        "post_processor",
+        "decoder",
+        "added_tokens",
+    ] {
+        assert_eq!(base["components"][section], model["components"][section]);
+    }
+
+    unsafe {
+        tokenizers_free(original);
+        tokenizers_free(lowercased);
+        tokenizers_free(renumbered);
+    }
+}
+
+#[test]
+fn fingerprint_digests_padding_and_truncation_separately() {
+    let tokenizer = create(BPE_TOKENIZER);
+    let before = fingerprint(tokenizer);
+
+    let pad_token = CString::new("</s>").unwrap();
+    let mut status = -1;
+    let enabled = unsafe {
+        tokenizers_enable_padding(
+            tokenizer,
+            1,
+            1,
+            0,
+            pad_token.as_ptr(),
+            8,
+            0,
+            ptr::addr_of_mut!(status),
+        )
+    };
+    assert_eq!(enabled, 1);
+
+    let after = fingerprint(tokenizer);
+    assert_eq!(before["tokenizer"], after["tokenizer"]);
+    assert_ne!(before["configuration"], after["configuration"]);
+
+    unsafe { tokenizers_free(tokenizer) };
+}
+
+#[test]
+fn fingerprint_rejects_null_tokenizer() {
+    let mut status = -1;
+    let json = unsafe { tokenizers_fingerprint(ptr::null(), ptr::addr_of_mut!(status)) };
+    assert!(json.is_null());
+    assert_eq!(status, 1);
+}
@@ -0,0 +1,174 @@
+use std::ffi::{c_char, CStr, CString};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::ptr;
+use tokenx_bridge::ffi::hub_cache::tokenizers_from_cache;
+use tokenx_bridge::ffi::lifecycle::{
+    tokenizers_free, tokenizers_free_string, tokenizers_from_pretrained,
+};
+use tokenx_bridge::ffi::test_helpers;
+use tokenx_bridge::tokenizers_get_last_error;
+
+const MAIN_SHA: &str = "0123456789abcdef0123456789abcdef01234567";
+const PR_SHA: &str = "fedcba9876543210fedcba9876543210fedcba98";
+
+/// Builds a hub cache holding `org/model` with `main` and `refs/pr/1` snapshots; the PR snapshot
+/// has no tokenizer.json.
+fn hub_cache(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("tokenx-hub-{}-{name}", std::process::id()));
+    let repo = root.join("models--org--model");
+    fs::create_dir_all(repo.join("refs/refs/pr")).unwrap();
+    fs::write(repo.join("refs/main"), MAIN_SHA).unwrap();
+    fs::write(repo.join("refs/refs/pr/1"), format!("{PR_SHA}\n")).unwrap();
+
+    let main = repo.join("snapshots").join(MAIN_SHA);
+    fs::create_dir_all(&main).unwrap();
+    let tokenizer_json = test_helpers::tokenizer_config_json();
+    fs::write(main.join("tokenizer.json"), tokenizer_json.to_bytes()).unwrap();
+    fs::write(
+        main.join("tokenizer_config.json"),
+        r#"{"model_max_length": 8, "pad_token": "[UNK]"}"#,
+    )
+    .unwrap();
+
+    let pr = repo.join("snapshots").join(PR_SHA);
+    fs::create_dir_all(&pr).unwrap();
+    fs::write(pr.join("special_tokens_map.json"), "{}").unwrap();
+    root
+}
+
+fn from_cache(
+    root: &Path,
+    revision: Option<&str>,
+    metadata: *mut *mut c_char,
+    status: &mut i32,
+) -> *mut tokenx_bridge::CTokenizer {
+    let identifier = CString::new("org/model").unwrap();
+    let revision = revision.map(|value| CString::new(value).unwrap());
+    let root = CString::new(root.to_str().unwrap()).unwrap();
+    unsafe {
+        tokenizers_from_cache(
+            identifier.as_ptr(),
+            revision
+                .as_ref()
+                .map_or(ptr::null(), |value| value.as_ptr()),
+            root.as_ptr(),
+            true,
+            metadata,
+            status as *mut i32,
+        )
+    }
+}
+
+fn last_error() -> String {
+    let pointer = tokenizers_get_last_error();
+    assert!(!pointer.is_null());
+    unsafe { CStr::from_ptr(pointer) }
+        .to_str()
+        .unwrap()
+        .to_string()
+}
+
+#[test]
+fn from_cache_resolves_branch_ref_to_snapshot() {
+    let root = hub_cache("branch");
+    let mut metadata: *mut c_char = ptr::null_mut();
+    let mut status = -1;
+    let tokenizer = from_cache(&root, None, ptr::addr_of_mut!(metadata), &mut status);
+    assert_eq!(status, 0);
+    assert!(!tokenizer.is_null());
+
+    let json = unsafe { CStr::from_ptr(metadata) }
+        .to_str()
+        .unwrap()
+        .to_string();
+    unsafe { tokenizers_free_string(metadata) };
+    let metadata: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert!(metadata["directory"].as_str().unwrap().ends_with(MAIN_SHA));
+    assert_eq!(metadata["model_max_length"], 8);
+
+    unsafe { tokenizers_free(tokenizer) };
+
+    let tokenizer = from_cache(&root, Some(MAIN_SHA), ptr::null_mut(), &mut status);
+    assert_eq!(status, 0);
+    unsafe { tokenizers_free(tokenizer) };
+}
+
+#[test]
+fn from_cache_lists_missing_files_and_refs() {
+    let root = hub_cache("missing");
+    let mut status = -1;
+    let tokenizer = from_cache(&root, Some("refs/pr/1"), ptr::null_mut(), &mut status);
+    assert!(tokenizer.is_null());
+    assert_eq!(status, 4);
+    let message = last_error();
+    assert!(message.contains("missing tokenizer.json"));
+    assert!(message.contains("tokenizer_config.json"));
+    assert!(!message.contains("special_tokens_map.json"));
+
+    let tokenizer = from_cache(&root, Some("v2"), ptr::null_mut(), &mut status);
+    assert!(tokenizer.is_null());
+    assert_eq!(status, 3);
+    assert!(last_error().contains("cached refs: main, refs/pr/1"));
+}
+
+#[test]
+fn from_cache_requires_merges_next_to_vocab_json() {
+    let root = hub_cache("legacy");
+    let pr = root.join("models--org--model/snapshots").join(PR_SHA);
+    fs::write(pr.join("vocab.json"), r#"{"hello": 0}"#).unwrap();
+    let mut status = -1;
+    let tokenizer = from_cache(&root, Some("refs/pr/1"), ptr::null_mut(), &mut status);
+    assert!(tokenizer.is_null());
+    assert_eq!(status, 4);
+    let message = last_error();
+    assert!(message.contains("missing merges.txt"), "{message}");
+    assert!(!message.contains("missing tokenizer.json"), "{message}");
+}
+
+#[test]
+fn from_cache_rejects_revisions_outside_refs() {
+    let root = hub_cache("traversal");
+    fs::write(root.join("escape"), MAIN_SHA).unwrap();
+    let mut status = -1;
+    for revision in ["../../escape", "/etc/passwd", "refs//main", "..\\escape"] {
+        let tokenizer = from_cache(&root, Some(revision), ptr::null_mut(), &mut status);
+        assert!(tokenizer.is_null());
+        assert_eq!(status, 2, "{revision}");
+        assert!(last_error().contains("not a valid ref name"));
+    }
+}
+
+#[test]
+fn from_pretrained_uses_cache_when_offline() {
+    let root = hub_cache("offline");
+    std::env::set_var("HF_HUB_CACHE", &root);
+    std::env::set_var("HF_HUB_OFFLINE", "1");
+
+    let identifier = CString::new("org/model").unwrap();
+    let mut status = -1;
+    let tokenizer = unsafe {
+        tokenizers_from_pretrained(
+            identifier.as_ptr(),
+            ptr::null(),
+            ptr::null(),
+            ptr::addr_of_mut!(status),
+        )
+    };
+    assert_eq!(status, 0);
+    assert!(!tokenizer.is_null());
+    unsafe { tokenizers_free(tokenizer) };
+
+    let missing = CString::new("org/absent").unwrap();
+    let tokenizer = unsafe {
+        tokenizers_from_pretrained(
+            missing.as_ptr(),
+            ptr::null(),
+            ptr::null(),
+            ptr::addr_of_mut!(status),
+        )
+    };
+    assert!(tokenizer.is_null());
+    assert_eq!(status, 5);
+    assert!(last_error().contains("models--org--absent"));
+}
@@ -0,0 +1,369 @@
+use std::ffi::{c_char, CStr, CString};
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::ptr;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tokenx_bridge::ffi::hub_download::tokenizers_from_pretrained_with_options;
+use tokenx_bridge::ffi::lifecycle::{tokenizers_free, tokenizers_free_string};
+use tokenx_bridge::ffi::test_helpers;
+use tokenx_bridge::{tokenizers_get_last_error, CHubOptions};
+
+const COMMIT: &str = "89abcdef0123456789abcdef0123456789abcdef";
+
+/// A request seen by the stand-in hub: method, path, user agent and Authorization header.
+type Request = (String, String, String, Option<String>);
+
+/// Serves `org/model` the way the hub does: tokenizer.json answers `resolve` with a redirect to a
+/// storage URL on another host, like an LFS file, and special_tokens_map.json does not exist.
+/// `org/tampered` answers with a commit that is a path instead of a hash.
+struct StandInHub {
+    endpoint: String,
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+impl StandInHub {
+    fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        // A second port is a second origin, standing in for the storage CDN.
+        let storage_listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let storage = format!(
+            "http://{}/storage/tokenizer.json",
+            storage_listener.local_addr().unwrap()
+        );
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        for listener in [listener, storage_listener] {
+            let seen = Arc::clone(&requests);
+            let storage = storage.clone();
+            thread::spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    serve(stream, &storage, &seen);
+                }
+            });
+        }
+        StandInHub { endpoint, requests }
+    }
+
+    fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    fn gets(&self) -> usize {
+        self.requests()
+            .iter()
+            .filter(|(method, ..)| method == "GET")
+            .count()
+    }
+}
+
+fn serve(stream: TcpStream, storage: &str, seen: &Mutex<Vec<Request>>) {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).unwrap();
+    let mut user_agent = String::new();
+    let mut authorization = None;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        if line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("user-agent") {
+                user_agent = value.trim().to_string();
+            } else if name.eq_ignore_ascii_case("authorization") {
+                authorization = Some(value.trim().to_string());
+            }
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+    seen.lock()
+        .unwrap()
+        .push((method.clone(), path.clone(), user_agent, authorization));
+
+    let tokenizer_json = test_helpers::tokenizer_config_json();
+    let file = |name: &str| -> Option<Vec<u8>> {
+        match name {
+            "tokenizer_config.json" => {
+                Some(br#"{"model_max_length": 8, "pad_token": "[UNK]"}"#.to_vec())
+            }
+            "generation_config.json" => Some(br#"{"eos_token_id": 0}"#.to_vec()),
+            _ => None,
+        }
+    };
+
+    let resolve = |revision: &str| format!("/org/model/resolve/{revision}/");
+    let name = [resolve("main"), resolve(COMMIT)]
+        .iter()
+        .find_map(|prefix| path.strip_prefix(prefix.as_str()))
+        .map(str::to_string);
+    let (head, body) = match name.as_deref() {
+        Some("tokenizer.json") => (
+            format!("302 Found\r\nX-Repo-Commit: {COMMIT}\r\nLocation: {storage}"),
+            Vec::new(),
+        ),
+        Some(name) => match file(name) {
+            Some(body) => (format!("200 OK\r\nX-Repo-Commit: {COMMIT}"), body),
+            None => (String::from("404 Not Found"), Vec::new()),
+        },
+        None if path.starts_with("/org/tampered/resolve/") => (
+            String::from("200 OK\r\nX-Repo-Commit: ../../../outside"),
+            tokenizer_json.to_bytes().to_vec(),
+        ),
+        None if path == "/storage/tokenizer.json" => {
+            (String::from("200 OK"), tokenizer_json.to_bytes().to_vec())
+        }
+        None => (String::from("404 Not Found"), Vec::new()),
+    };
+
+    let mut stream = reader.into_inner();
+    let response = format!(
+        "HTTP/1.1 {head}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).unwrap();
+    if method == "GET" {
+        stream.write_all(&body).unwrap();
+    }
+}
+
+fn cache_dir(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("tokenx-download-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    root
+}
+
+fn options(endpoint: &CString, cache: &CString, user_agent: &CString) -> CHubOptions {
+    CHubOptions {
+        endpoint: endpoint.as_ptr(),
+        cache_dir: cache.as_ptr(),
+        revision: ptr::null(),
+        auth_token: ptr::null(),
+        user_agent: user_agent.as_ptr(),
+        timeout_ms: 5_000,
+        local_files_only: false,
+        force_download: false,
+        apply_defaults: true,
+    }
+}
+
+fn fetch(
+    identifier: &str,
+    options: &CHubOptions,
+    metadata: *mut *mut c_char,
+) -> (*mut tokenx_bridge::CTokenizer, i32) {
+    let identifier = CString::new(identifier).unwrap();
+    let mut status = -1;
+    let tokenizer = unsafe {
+        tokenizers_from_pretrained_with_options(
+            identifier.as_ptr(),
+            options as *const CHubOptions,
+            metadata,
+            ptr::addr_of_mut!(status),
+        )
+    };
+    (tokenizer, status)
+}
+
+fn fetch_ok(options: &CHubOptions) {
+    let (tokenizer, status) = fetch("org/model", options, ptr::null_mut());
+    assert_eq!(status, 0, "{}", last_error());
+    unsafe { tokenizers_free(tokenizer) };
+}
+
+fn last_error() -> String {
+    let pointer = tokenizers_get_last_error();
+    if pointer.is_null() {
+        return String::new();
+    }
+    unsafe { CStr::from_ptr(pointer) }
+        .to_str()
+        .unwrap()
+        .to_string()
+}
+
+fn c_path(path: &Path) -> CString {
+    CString::new(path.to_str().unwrap()).unwrap()
+}
+
+#[test]
+fn with_options_downloads_tokenizer_and_companions_into_cache() {
+    let hub = StandInHub::start();
+    let root = cache_dir("download");
+    let (endpoint, cache) = (CString::new(hub.endpoint.as_str()).unwrap(), c_path(&root));
+    let user_agent = CString::new("tokenx-tests/1.0").unwrap();
+    let options = options(&endpoint, &cache, &user_agent);
+
+    let mut metadata: *mut c_char = ptr::null_mut();
+    let (tokenizer, status) = fetch("org/model", &options, ptr::addr_of_mut!(metadata));
+    assert_eq!(status, 0, "{}", last_error());
+    assert!(!tokenizer.is_null());
+    let text = unsafe { CStr::from_ptr(metadata) }
+        .to_str()
+        .unwrap()
+        .to_string();
+    unsafe { tokenizers_free_string(metadata) };
+    let metadata: serde_json::Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(metadata["model_max_length"], 8);
+
+    let repo = root.join("models--org--model");
+    assert_eq!(fs::read_to_string(repo.join("refs/main")).unwrap(), COMMIT);
+    let snapshot = repo.join("snapshots").join(COMMIT);
+    for name in [
+        "tokenizer.json",
+        "tokenizer_config.json",
+        "generation_config.json",
+    ] {
+        assert!(snapshot.join(name).is_file(), "{name} was not cached");
+    }
+    assert!(!snapshot.join("special_tokens_map.json").exists());
+
+    let requests = hub.requests();
+    assert!(requests
+        .iter()
+        .any(|(method, path, ..)| method == "GET" && path == "/storage/tokenizer.json"));
+    assert!(requests
+        .iter()
+        .all(|(_, _, agent, _)| agent == "tokenx-tests/1.0"));
+
+    unsafe { tokenizers_free(tokenizer) };
+}
+
+#[test]
+fn with_options_sends_token_to_hub_but_not_to_storage() {
+    let hub = StandInHub::start();
+    let root = cache_dir("auth");
+    let (endpoint, cache) = (CString::new(hub.endpoint.as_str()).unwrap(), c_path(&root));
+    let user_agent = CString::new("tokenx-tests/1.0").unwrap();
+    let token = CString::new("hf_secret").unwrap();
+    let mut options = options(&endpoint, &cache, &user_agent);
+    options.auth_token = token.as_ptr();
+
+    fetch_ok(&options);
+
+    let requests = hub.requests();
+    let (storage, hub_requests): (Vec<_>, Vec<_>) = requests
+        .iter()
+        .partition(|(_, path, ..)| path == "/storage/tokenizer.json");
+    assert_eq!(storage.len(), 1);
+    assert_eq!(storage[0].3, None);
+    assert!(!hub_requests.is_empty());
+    assert!(hub_requests
+        .iter()
+        .all(|(.., authorization)| authorization.as_deref() == Some("Bearer hf_secret")));
+}
+
+#[test]
+fn with_options_keeps_downloads_inside_the_cache() {
+    let hub = StandInHub::start();
+    let root = cache_dir("tampered");
+    let (endpoint, cache) = (CString::new(hub.endpoint.as_str()).unwrap(), c_path(&root));
+    let user_agent = CString::new("tokenx-tests/1.0").unwrap();
+    let mut options = options(&endpoint, &cache, &user_agent);
+
+    let (tokenizer, status) = fetch("org/tampered", &options, ptr::null_mut());
+    assert!(tokenizer.is_null());
+    assert_eq!(status, 5);
+    assert!(
+        last_error().contains("invalid X-Repo-Commit"),
+        "{}",
+        last_error()
+    );
+    assert!(!root.join("outside").exists());
+
+    let revision = CString::new("../../escape").unwrap();
+    options.revision = revision.as_ptr();
+    let requests = hub.requests().len();
+    let (tokenizer, status) = fetch("org/model", &options, ptr::null_mut());
+    assert!(tokenizer.is_null());
+    assert_eq!(status, 2);
+    assert_eq!(hub.requests().len(), requests);
+    assert!(!root.join("escape").exists());
+}
+
+#[test]
+fn with_options_reuses_cache_unless_forced_or_unreachable() {
+    let hub = StandInHub::start();
+    let root = cache_dir("reuse");
+    let (endpoint, cache) = (CString::new(hub.endpoint.as_str()).unwrap(), c_path(&root));
+    let user_agent = CString::new("tokenx-tests/1.0").unwrap();
+    let mut options = options(&endpoint, &cache, &user_agent);
+
+    fetch_ok(&options);
+    let downloads = hub.gets();
+    assert_eq!(downloads, 3);
+
+    fetch_ok(&options);
+    assert_eq!(hub.gets(), downloads, "cached files were downloaded again");
+
+    options.force_download = true;
+    fetch_ok(&options);
+    assert_eq!(hub.gets(), downloads * 2);
+
+    // A hub that refuses connections falls back to the cached snapshot of the revision.
+    let closed = TcpListener::bind("127.0.0.1:0").unwrap();
+    let unreachable = CString::new(format!("http://{}", closed.local_addr().unwrap())).unwrap();
+    drop(closed);
+    options.endpoint = unreachable.as_ptr();
+    options.force_download = false;
+    fetch_ok(&options);
+
+    options.local_files_only = true;
+    fetch_ok(&options);
+}
+
+#[test]
+fn with_options_reports_missing_repository_and_cache_miss() {
+    let hub = StandInHub::start();
+    let root = cache_dir("missing");
+    let (endpoint, cache) = (CString::new(hub.endpoint.as_str()).unwrap(), c_path(&root));
+    let user_agent = CString::new("tokenx-tests/1.0").unwrap();
+    let mut options = options(&endpoint, &cache, &user_agent);
+
+    let (tokenizer, status) = fetch("org/absent", &options, ptr::null_mut());
+    assert!(tokenizer.is_null());
+    assert_eq!(status, 4);
+    assert!(last_error().contains("HTTP 404"), "{}", last_error());
+
+    options.local_files_only = true;
+    let request_count = hub.requests().len();
+    let (tokenizer, status) = fetch("org/model", &options, ptr::null_mut());
+    assert!(tokenizer.is_null());
+    assert_eq!(status, 3);
+    assert!(
+        last_error().contains("not in the hub cache"),
+        "{}",
+        last_error()
+    );
+    assert_eq!(hub.requests().len(), request_count);
+}
+
+#[test]
+fn with_options_times_out_on_unresponsive_hub() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let endpoint = CString::new(format!("http://{}", listener.local_addr().unwrap())).unwrap();
+    thread::spawn(move || {
+        let held: Vec<TcpStream> = listener.incoming().flatten().take(1).collect();
+        thread::sleep(Duration::from_secs(10));
+        drop(held);
+    });
+
+    let root = cache_dir("timeout");
+    let cache = c_path(&root);
+    let user_agent = CString::new("tokenx-tests/1.0").unwrap();
+    let mut options = options(&endpoint, &cache, &user_agent);
+    options.timeout_ms = 200;
+
+    let started = Instant::now();
+    let (tokenizer, status) = fetch("org/model", &options, ptr::null_mut());
+    assert!(tokenizer.is_null());
+    assert_eq!(status, 5);
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
@@ -0,0 +1,253 @@
+use std::ffi::{c_char, CString};
+use std::ptr;
+use tokenx_bridge::ffi::added_tokens::tokenizers_add_tokens;
+use tokenx_bridge::ffi::lean_encoding::{
+    tokenizers_count_tokens, tokenizers_count_tokens_batch, tokenizers_encode_ids,
+    tokenizers_encode_ids_batch,
+};
+use tokenx_bridge::ffi::padding::tokenizers_enable_padding;
+use tokenx_bridge::ffi::test_helpers;
+use tokenx_bridge::ffi::truncation::tokenizers_enable_truncation;
+use tokenx_bridge::{CAddedToken, CTokenizer};
+
+#[test]
+fn tokenizers_count_tokens_ignores_padding() {
+    let mut tokenizer = test_helpers::create_tokenizer();
+    unsafe {
+        tokenizers_enable_padding(
+            &mut tokenizer as *mut CTokenizer,
+            1,
+            0,
+            0,
+            ptr::null(),
+            8,
+            0,
+            ptr::null_mut(),
+        );
+    }
+
+    let text = CString::new("hello world hello").unwrap();
+    let mut status = -1;
+    let count = unsafe {
+        tokenizers_count_tokens(
+            &tokenizer as *const CTokenizer,
+            text.as_ptr(),
+            ptr::null(),
+            true,
+            ptr::addr_of_mut!(status),
+        )
+    };
+
+    assert_eq!(status, 0);
+    assert_eq!(count, 3);
+}
+
+#[test]
+fn tokenizers_count_tokens_ignores_truncation() {
+    let mut tokenizer = test_helpers::create_tokenizer();
+    let mut status = -1;
+    unsafe {
+        tokenizers_enable_truncation(
+            &mut tokenizer as *mut CTokenizer,
+            2,
+            0,
+            0,
+            0,
+            ptr::addr_of_mut!(status),
+        );
+    }
+    assert_eq!(status, 0);
+
+    let text = CString::new("hello world hello world").unwrap();
+    let count = unsafe {
+        tokenizers_count_tokens(
+            &tokenizer as *const CTokenizer,
+            text.as_ptr(),
+            ptr::null(),
+            true,
+            ptr::addr_of_mut!(status),
+        )
+    };
+    assert_eq!(status, 0);
+    assert_eq!(count, 4);
+
+    let texts = [text.as_ptr()];
+    let mut counts = [0usize; 1];
+    unsafe {
+        tokenizers_count_tokens_batch(
+            &tokenizer as *const CTokenizer,
+            texts.as_ptr(),
+            ptr::null(),
+            1,
+            true,
+            1,
+            counts.as_mut_ptr(),
+            ptr::addr_of_mut!(status),
+        );
+    }
+    assert_eq!(status, 0);
+    assert_eq!(counts, [4]);
+}
+
+#[test]
+fn tokenizers_count_tokens_sees_tokens_added_after_counting() {
+    let mut tokenizer = test_helpers::create_tokenizer();
+    let mut status = -1;
+    unsafe {
+        tokenizers_enable_truncation(
+            &mut tokenizer as *mut CTokenizer,
+            8,
+            0,
+            0,
+            0,
+            ptr::addr_of_mut!(status),
+        );
+    }
+    assert_eq!(status, 0);
+
+    let text = CString::new("hellofoo").unwrap();
+    let count = |tokenizer: &CTokenizer| unsafe {
+        tokenizers_count_tokens(
+            tokenizer as *const CTokenizer,
+            text.as_ptr(),
+            ptr::null(),
+            true,
+            ptr::null_mut(),
+        )
+    };
+    assert_eq!(count(&tokenizer), 1);
+
+    let content = CString::new("foo").unwrap();
+    let token = CAddedToken {
+        content: content.as_ptr(),
+        special: false,
+        single_word: false,
+        lstrip: false,
+        rstrip: false,
+        normalized: true,
+        pinned_id: -1,
+    };
+    unsafe {
+        tokenizers_add_tokens(
+            &mut tokenizer as *mut CTokenizer,
+            &token,
+            1,
+            ptr::null_mut(),
+            ptr::addr_of_mut!(status),
+        );
+    }
+    assert_eq!(status, 0);
+    assert_eq!(count(&tokenizer), 2);
+}
+
+#[test]
+fn tokenizers_count_tokens_batch_fills_counts() {
+    let tokenizer = test_helpers::create_tokenizer();
+    let texts = [
+        CString::new("hello world").unwrap(),
+        CString::new("world").unwrap(),
+    ];
+    let sequences: Vec<*const c_char> = texts.iter().map(|text| text.as_ptr()).collect();
+    let mut counts = vec![0usize; sequences.len()];
+    let mut status = -1;
+
+    let rows = unsafe {
+        tokenizers_count_tokens_batch(
+            &tokenizer as *const CTokenizer,
+            sequences.as_ptr(),
+            ptr::null(),
+            sequences.len(),
+            true,
+            1,
+            counts.as_mut_ptr(),
+            ptr::addr_of_mut!(status),
+        )
+    };
+
+    assert_eq!(status, 0);
+    assert_eq!(rows, 2);
+    assert_eq!(counts, vec![2, 1]);
+}
+
+#[test]
+fn tokenizers_encode_ids_reports_required_length() {
+    let tokenizer = test_helpers::create_tokenizer();
+    let text = CString::new("hello world").unwrap();
+    let mut ids = [0u32; 1];
+    let mut length = 0usize;
+    let mut status = -1;
+
+    let written = unsafe {
+        tokenizers_encode_ids(
+            &tokenizer as *const CTokenizer,
+            text.as_ptr(),
+            ptr::null(),
+            true,
+            ids.as_mut_ptr(),
+            ptr::null_mut(),
+            ids.len(),
+            ptr::addr_of_mut!(length),
+            ptr::addr_of_mut!(status),
+        )
+    };
+
+    assert_eq!(written, 0);
+    assert_ne!(status, 0);
+    assert_eq!(length, 2);
+
+    let mut ids = vec![0u32; length];
+    let mut mask = vec![0u32; length];
+    status = -1;
+    let written = unsafe {
+        tokenizers_encode_ids(
+            &tokenizer as *const CTokenizer,
+            text.as_ptr(),
+            ptr::null(),
+            true,
+            ids.as_mut_ptr(),
+            mask.as_mut_ptr(),
+            ids.len(),
+            ptr::addr_of_mut!(length),
+            ptr::addr_of_mut!(status),
+        )
+    };
+
+    assert_eq!(status, 0);
+    assert_eq!(written, 2);
+    assert_eq!(ids, vec![1, 2]);
+    assert_eq!(mask, vec![1, 1]);
+}
+
+#[test]
+fn tokenizers_encode_ids_batch_writes_rows_back_to_back() {
+    let tokenizer = test_helpers::create_tokenizer();
+    let texts = [
+        CString::new("world hello").unwrap(),
+        CString::new("hello").unwrap(),
+    ];
+    let sequences: Vec<*const c_char> = texts.iter().map(|text| text.as_ptr()).collect();
+    let mut ids = vec![0u32; 3];
+    let mut lengths = vec![0usize; sequences.len()];
+    let mut status = -1;
+
+    let rows = unsafe {
+        tokenizers_encode_ids_batch(
+            &tokenizer as *const CTokenizer,
+            sequences.as_ptr(),
+            ptr::null(),
+            sequences.len(),
+            true,
+            0,
+            ids.as_mut_ptr(),
+            ptr::null_mut(),
+            ids.len(),
+            lengths.as_mut_ptr(),
+            ptr::addr_of_mut!(status),
+        )
+    };
+
+    assert_eq!(status, 0);
+    assert_eq!(rows, 2);
+    assert_eq!(lengths, vec![2, 1]);
+    assert_eq!(ids, vec![2, 1, 1]);
+}
@@ -0,0 +1,183 @@
+use std::ffi::CString;
+use std::ptr;
+use tokenx_bridge::ffi::encoding::{
+    tokenizers_encode, tokenizers_encoding_free, tokenizers_encoding_get_overflowing,
+};
+use tokenx_bridge::ffi::mapping::{
+    tokenizers_encoding_char_to_token, tokenizers_encoding_char_to_word,
+    tokenizers_encoding_token_to_chars, tokenizers_encoding_token_to_sequence,
+    tokenizers_encoding_token_to_word, tokenizers_encoding_word_to_chars,
+    tokenizers_encoding_word_to_tokens,
+};
+use tokenx_bridge::ffi::test_helpers;
+use tokenx_bridge::ffi::truncation::tokenizers_enable_truncation;
+use tokenx_bridge::{CEncoding, CEncodingOffset, CTokenizer};
+
+fn encode_pair(tokenizer: &mut CTokenizer, sequence: &str, pair: Option<&str>) -> *mut CEncoding {
+    let sequence = CString::new(sequence).unwrap();
+    let pair = pair.map(|value| CString::new(value).unwrap());
+    let mut status = -1;
+    let encoding = unsafe {
+        tokenizers_encode(
+            tokenizer as *mut CTokenizer,
+            sequence.as_ptr(),
+            pair.as_ref().map_or(ptr::null(), |value| value.as_ptr()),
+            true,
+            ptr::null_mut(),
+            ptr::addr_of_mut!(status),
+        )
+    };
+    assert_eq!(status, 0);
+    encoding
+}
+
+#[test]
+fn char_lookups_respect_sequence_index() {
+    let mut tokenizer = test_helpers::create_tokenizer();
+    let encoding = encode_pair(&mut tokenizer, "hello world", Some("world"));
+    let mut status = -1;
+
+    let token =
+        unsafe { tokenizers_encoding_char_to_token(encoding, 6, 0, ptr::addr_of_mut!(status)) };
+    assert_eq!((token, status), (1, 0));
+
+    let token =
+        unsafe { tokenizers_encoding_char_to_token(encoding, 0, 1, ptr::addr_of_mut!(status)) };
+    assert_eq!((token, status), (2, 0));
+
+    let word =
+        unsafe { tokenizers_encoding_char_to_word(encoding, 7, 0, ptr::addr_of_mut!(status)) };
+    assert_eq!((word, status), (1, 0));
+
+    let missing =
+        unsafe { tokenizers_encoding_char_to_token(encoding, 5, 0, ptr::addr_of_mut!(status)) };
+    assert_eq!((missing, status), (-1, 3));
+
+    unsafe {
+        tokenizers_encoding_free(encoding);
+    }
+}
+
+#[test]
+fn token_lookups_report_sequence_and_word() {
+    let mut tokenizer = test_helpers::create_tokenizer();
+    let encoding = encode_pair(&mut tokenizer, "hello world", Some("world"));
+    let mut status = -1;
+    let mut sequence = usize::MAX;
+    let mut offset = CEncodingOffset::default();
+
+    let found = unsafe {
+        tokenizers_encoding_token_to_chars(
+            encoding,
+            1,
+            ptr::addr_of_mut!(sequence),
+            ptr::addr_of_mut!(offset),
+            ptr::addr_of_mut!(status),
+        )
+    };
+    assert_eq!((found, status, sequence), (1, 0, 0));
+    assert_eq!((offset.start, offset.end), (6, 11));
+
+    let word = unsafe {
+        tokenizers_encoding_token_to_word(
+            encoding,
+            2,
+            ptr::addr_of_mut!(sequence),
+            ptr::addr_of_mut!(status),
+        )
+    };
+    assert_eq!((word, status, sequence), (0, 0, 1));
+
+    let sequence_index =
+        unsafe { tokenizers_encoding_token_to_sequence(encoding, 2, ptr::addr_of_mut!(status)) };
+    assert_eq!((sequence_index, status), (1, 0));
+
+    let out_of_range = unsafe {
+        tokenizers_encoding_token_to_word(encoding, 9, ptr::null_mut(), ptr::addr_of_mut!(status))
+    };
+    assert_eq!((out_of_range, status), (-1, 2));
+
+    unsafe {
+        tokenizers_encoding_free(encoding);
+    }
+}
+
+#[test]
+fn word_lookups_return_token_range_and_span() {
+    let mut tokenizer = test_helpers::create_tokenizer();
+    let encoding = encode_pair(&mut tokenizer, "hello world", Some("world"));
+    let mut status = -1;
+    let mut start = 0usize;
+    let mut end = 0usize;
+
+    let found = unsafe {
+        tokenizers_encoding_word_to_tokens(
+            encoding,
+            1,
+            0,
+            ptr::addr_of_mut!(start),
+            ptr::addr_of_mut!(end),
+            ptr::addr_of_mut!(status),
+        )
+    };
+    assert_eq!((found, status, start, end), (1, 0, 1, 2));
+
+    let mut offset = CEncodingOffset::default();
+    let found = unsafe {
+        tokenizers_encoding_word_to_chars(
+            encoding,
+            0,
+            1,
+            ptr::addr_of_mut!(offset),
+            ptr::addr_of_mut!(status),
+        )
+    };
+    assert_eq!((found, status), (1, 0));
+    assert_eq!((offset.start, offset.end), (0, 5));
+
+    let missing = unsafe {
+        tokenizers_encoding_word_to_tokens(
+            encoding,
+            4,
+            0,
+            ptr::null_mut(),
+            ptr::null_mut(),
+            ptr::addr_of_mut!(status),
+        )
+    };
+    assert_eq!((missing, status), (0, 3));
+
+    unsafe {
+        tokenizers_encoding_free(encoding);
+    }
+}
+
+#[test]
+fn lookups_work_on_overflowing_encodings() {
+    let mut tokenizer = test_helpers::create_tokenizer();
+    unsafe {
+        tokenizers_enable_truncation(
+            &mut tokenizer as *mut CTokenizer,
+            1,
+            0,
+            0,
+            1,
+            ptr::null_mut(),
+        );
+    }
+
+    let encoding = encode_pair(&mut tokenizer, "hello world", None);
+    let mut status = -1;
+    let overflow =
+        unsafe { tokenizers_encoding_get_overflowing(encoding, 0, ptr::null_mut(), &mut status) };
+    assert_eq!(status, 0);
+
+    let token =
+        unsafe { tokenizers_encoding_char_to_token(overflow, 8, 0, ptr::addr_of_mut!(status)) };
+    assert_eq!((token, status), (0, 0));
+
+    unsafe {
+        tokenizers_encoding_free(overflow);
+        tokenizers_encoding_free(encoding);
+    }
+}
@@ -0,0 +1,242 @@
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+use tokenx_bridge::ffi::lifecycle::tokenizers_free_string;
+use tokenx_bridge::ffi::stop_matcher::{
+    tokenizers_stop_matcher_feed_text, tokenizers_stop_matcher_feed_tokens,
+    tokenizers_stop_matcher_finish, tokenizers_stop_matcher_free,
+    tokenizers_stop_matcher_from_generation_config, tokenizers_stop_matcher_new,
+};
+use tokenx_bridge::ffi::test_helpers;
+use tokenx_bridge::{CStopMatcher, CTokenizer};
+
+const EMIT: i32 = 0;
+const HOLD_BACK: i32 = 1;
+const STOP: i32 = 2;
+
+fn take_string(value: *mut c_char) -> String {
+    assert!(!value.is_null());
+    let text = unsafe { CStr::from_ptr(value) }
+        .to_str()
+        .unwrap()
+        .to_string();
+    unsafe { tokenizers_free_string(value) };
+    text
+}
+
+fn new_matcher(stop_strings: &[&str], sequences: &[&[u32]], eos: &[u32]) -> *mut CStopMatcher {
+    let strings: Vec<CString> = stop_strings
+        .iter()
+        .map(|value| CString::new(*value).unwrap())
+        .collect();
+    let string_ptrs: Vec<*const c_char> = strings.iter().map(|value| value.as_ptr()).collect();
+    let flat: Vec<u32> = sequences
+        .iter()
+        .flat_map(|sequence| sequence.iter().copied())
+        .collect();
+    let lengths: Vec<usize> = sequences.iter().map(|sequence| sequence.len()).collect();
+    let mut status = -1;
+
+    let matcher = unsafe {
+        tokenizers_stop_matcher_new(
+            string_ptrs.as_ptr(),
+            string_ptrs.len(),
+            flat.as_ptr(),
+            lengths.as_ptr(),
+            lengths.len(),
+            eos.as_ptr(),
+            eos.len(),
+            true,
+            ptr::addr_of_mut!(status),
+        )
+    };
+
+    assert_eq!(status, 0);
+    matcher
+}
+
+fn feed_text(matcher: *mut CStopMatcher, text: &str) -> (i32, String) {
+    let fragment = CString::new(text).unwrap();
+    let mut action = -1;
+    let mut status = -1;
+    let released = unsafe {
+        tokenizers_stop_matcher_feed_text(
+            matcher,
+            fragment.as_ptr(),
+            ptr::addr_of_mut!(action),
+            ptr::addr_of_mut!(status),
+        )
+    };
+    assert_eq!(status, 0);
+    (action, take_string(released))
+}
+
+fn feed_tokens(matcher: *mut CStopMatcher, tokenizer: &CTokenizer, ids: &[u32]) -> (i32, String) {
+    let mut action = -1;
+    let mut status = -1;
+    let released = unsafe {
+        tokenizers_stop_matcher_feed_tokens(
+            matcher,
+            tokenizer as *const CTokenizer,
+            ids.as_ptr(),
+            ids.len(),
+            ptr::addr_of_mut!(action),
+            ptr::addr_of_mut!(status),
+        )
+    };
+    assert_eq!(status, 0);
+    (action, take_string(released))
+}
+
+#[test]
+fn tokenizers_stop_matcher_feed_text_matches_across_fragments() {
+    let matcher = new_matcher(&["END"], &[], &[]);
+
+    assert_eq!(feed_text(matcher, "abc E"), (HOLD_BACK, "abc ".to_string()));
+    assert_eq!(feed_text(matcher, "Nxyz"), (EMIT, "ENxyz".to_string()));
+    assert_eq!(feed_text(matcher, "fooEN"), (HOLD_BACK, "foo".to_string()));
+    assert_eq!(feed_text(matcher, "D bar"), (STOP, String::new()));
+    assert_eq!(feed_text(matcher, "more"), (STOP, String::new()));
+
+    unsafe { tokenizers_stop_matcher_free(matcher) };
+}
+
+#[test]
+fn tokenizers_stop_matcher_feed_tokens_stops_on_token_sequence() {
+    let tokenizer = test_helpers::create_tokenizer();
+    let matcher = new_matcher(&[], &[&[2, 2]], &[]);
+
+    assert_eq!(
+        feed_tokens(matcher, &tokenizer, &[1, 2]),
+        (HOLD_BACK, "hello".to_string())
+    );
+    assert_eq!(
+        feed_tokens(matcher, &tokenizer, &[1]),
+        (EMIT, " world hello".to_string())
+    );
+    assert_eq!(
+        feed_tokens(matcher, &tokenizer, &[2, 2]),
+        (STOP, String::new())
+    );
+
+    unsafe { tokenizers_stop_matcher_free(matcher) };
+}
+
+#[test]
+fn tokenizers_stop_matcher_eos_releases_held_stop_string_prefix() {
+    let tokenizer = test_helpers::create_tokenizer();
+    let matcher = new_matcher(&[" world!"], &[], &[0]);
+
+    assert_eq!(
+        feed_tokens(matcher, &tokenizer, &[1, 2]),
+        (HOLD_BACK, "hello".to_string())
+    );
+    assert_eq!(
+        feed_tokens(matcher, &tokenizer, &[0]),
+        (STOP, " world".to_string())
+    );
+
+    unsafe { tokenizers_stop_matcher_free(matcher) };
+}
+
+#[test]
+fn tokenizers_stop_matcher_from_generation_config_uses_stop_strings_and_eos() {
+    let tokenizer = test_helpers::create_tokenizer();
+    let config = CString::new(r#"{"stop": ["world"], "eos_token_id": [0]}"#).unwrap();
+    let mut status = -1;
+    let matcher = unsafe {
+        tokenizers_stop_matcher_from_generation_config(
+            config.as_ptr(),
+            false,
+            ptr::addr_of_mut!(status),
+        )
+    };
+    assert_eq!(status, 0);
+
+    assert_eq!(
+        feed_tokens(matcher, &tokenizer, &[1, 2, 1]),
+        (STOP, "hello ".to_string())
+    );
+
+    let mut finish_status = -1;
+    let released = unsafe {
+        tokenizers_stop_matcher_finish(matcher, ptr::null(), ptr::addr_of_mut!(finish_status))
+    };
+    assert_eq!(finish_status, 0);
+    assert_eq!(take_string(released), "");
+
+    assert_eq!(
+        feed_tokens(matcher, &tokenizer, &[1, 0, 2]),
+        (STOP, "hello".to_string())
+    );
+
+    unsafe { tokenizers_stop_matcher_free(matcher) };
+}
+
+#[test]
+fn tokenizers_stop_matcher_finish_releases_held_text() {
+    let tokenizer = test_helpers::create_tokenizer();
+    let matcher = new_matcher(&["hello there"], &[], &[]);
+
+    assert_eq!(
+        feed_tokens(matcher, &tokenizer, &[2, 1]),
+        (HOLD_BACK, "world ".to_string())
+    );
+
+    let mut status = -1;
+    let released = unsafe {
+        tokenizers_stop_matcher_finish(
+            matcher,
+            &tokenizer as *const CTokenizer,
+            ptr::addr_of_mut!(status),
+        )
+    };
+    assert_eq!(status, 0);
+    assert_eq!(take_string(released), "hello");
+
+    unsafe { tokenizers_stop_matcher_free(matcher) };
+}
+
+#[test]
+fn tokenizers_stop_matcher_holds_back_split_characters() {
+    let tokenizer = test_helpers::create_byte_level_tokenizer();
+    let matcher = new_matcher(&[], &[], &[]);
+
+    assert_eq!(
+        feed_tokens(matcher, &tokenizer, &[4, 3]),
+        (EMIT, "hello caf".to_string())
+    );
+    assert_eq!(
+        feed_tokens(matcher, &tokenizer, &[1]),
+        (HOLD_BACK, String::new())
+    );
+    assert_eq!(
+        feed_tokens(matcher, &tokenizer, &[2]),
+        (EMIT, "\u{e9}".to_string())
+    );
+
+    unsafe { tokenizers_stop_matcher_free(matcher) };
+}
+
+#[test]
+fn tokenizers_stop_matcher_new_rejects_overflowing_sequence_lengths() {
+    let ids = [1u32];
+    let lengths = [usize::MAX, 2];
+    let mut status = -1;
+
+    let matcher = unsafe {
+        tokenizers_stop_matcher_new(
+            ptr::null(),
+            0,
+            ids.as_ptr(),
+            lengths.as_ptr(),
+            lengths.len(),
+            ptr::null(),
+            0,
+            true,
+            ptr::addr_of_mut!(status),
+        )
+    };
+
+    assert!(matcher.is_null());
+    assert_eq!(status, 2);
+}
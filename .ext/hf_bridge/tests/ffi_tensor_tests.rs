@@ -0,0 +1,165 @@
+use std::ffi::{c_char, c_void, CString};
+use std::ptr;
+use tokenx_bridge::ffi::padding::tokenizers_enable_padding;
+use tokenx_bridge::ffi::tensor::tokenizers_encode_batch_tensors;
+use tokenx_bridge::ffi::test_helpers;
+use tokenx_bridge::{CBatchTensorDest, CTokenizer};
+
+fn sample_texts() -> Vec<CString> {
+    vec![
+        CString::new("hello world").unwrap(),
+        CString::new("world").unwrap(),
+    ]
+}
+
+fn encode_tensors(
+    tokenizer: &CTokenizer,
+    element_type: i32,
+    destination: &CBatchTensorDest,
+) -> (i32, usize, i32) {
+    let texts = sample_texts();
+    let sequences: Vec<*const c_char> = texts.iter().map(|text| text.as_ptr()).collect();
+    let mut sequence_length = 0usize;
+    let mut status = -1;
+
+    let rows = unsafe {
+        tokenizers_encode_batch_tensors(
+            tokenizer as *const CTokenizer,
+            sequences.as_ptr(),
+            ptr::null(),
+            sequences.len(),
+            true,
+            0,
+            element_type,
+            destination as *const CBatchTensorDest,
+            ptr::addr_of_mut!(sequence_length),
+            ptr::addr_of_mut!(status),
+        )
+    };
+
+    (rows, sequence_length, status)
+}
+
+fn padded_tokenizer(direction: i32) -> CTokenizer {
+    let mut tokenizer = test_helpers::create_tokenizer();
+    unsafe {
+        tokenizers_enable_padding(
+            &mut tokenizer as *mut CTokenizer,
+            direction,
+            0,
+            0,
+            ptr::null(),
+            -1,
+            0,
+            ptr::null_mut(),
+        );
+    }
+    tokenizer
+}
+
+#[test]
+fn tokenizers_encode_batch_tensors_writes_left_padded_int64_rows() {
+    let tokenizer = padded_tokenizer(0);
+
+    let mut input_ids = [0i64; 4];
+    let mut attention_mask = [0i64; 4];
+    let mut token_type_ids = [9i64; 4];
+    let mut position_ids = [0i64; 4];
+    let destination = CBatchTensorDest {
+        input_ids: input_ids.as_mut_ptr().cast::<c_void>(),
+        attention_mask: attention_mask.as_mut_ptr().cast::<c_void>(),
+        token_type_ids: token_type_ids.as_mut_ptr().cast::<c_void>(),
+        position_ids: position_ids.as_mut_ptr().cast::<c_void>(),
+        capacity: 4,
+    };
+
+    let (rows, sequence_length, status) = encode_tensors(&tokenizer, 0, &destination);
+
+    assert_eq!(status, 0);
+    assert_eq!(rows, 2);
+    assert_eq!(sequence_length, 2);
+    assert_eq!(input_ids, [1, 2, 0, 2]);
+    assert_eq!(attention_mask, [1, 1, 0, 1]);
+    assert_eq!(token_type_ids, [0, 0, 0, 0]);
+    assert_eq!(position_ids, [0, 1, 1, 0]);
+}
+
+#[test]
+fn tokenizers_encode_batch_tensors_writes_right_padded_int32_rows() {
+    let tokenizer = padded_tokenizer(1);
+    let mut input_ids = [7i32; 4];
+    let mut attention_mask = [7i32; 4];
+    let destination = CBatchTensorDest {
+        input_ids: input_ids.as_mut_ptr().cast::<c_void>(),
+        attention_mask: attention_mask.as_mut_ptr().cast::<c_void>(),
+        token_type_ids: ptr::null_mut(),
+        position_ids: ptr::null_mut(),
+        capacity: 4,
+    };
+
+    let (rows, sequence_length, status) = encode_tensors(&tokenizer, 1, &destination);
+
+    assert_eq!(status, 0);
+    assert_eq!(rows, 2);
+    assert_eq!(sequence_length, 2);
+    assert_eq!(input_ids, [1, 2, 2, 0]);
+    assert_eq!(attention_mask, [1, 1, 1, 0]);
+}
+
+#[test]
+fn tokenizers_encode_batch_tensors_rejects_ragged_rows_without_padding_config() {
+    let tokenizer = test_helpers::create_tokenizer();
+    let mut input_ids = [7i64; 4];
+    let mut attention_mask = [7i64; 4];
+    let destination = CBatchTensorDest {
+        input_ids: input_ids.as_mut_ptr().cast::<c_void>(),
+        attention_mask: attention_mask.as_mut_ptr().cast::<c_void>(),
+        token_type_ids: ptr::null_mut(),
+        position_ids: ptr::null_mut(),
+        capacity: 4,
+    };
+
+    let (rows, _, status) = encode_tensors(&tokenizer, 0, &destination);
+
+    assert_eq!((rows, status), (0, 8));
+    assert_eq!(input_ids, [7; 4]);
+}
+
+#[test]
+fn tokenizers_encode_batch_tensors_reports_required_length_when_capacity_is_short() {
+    let tokenizer = padded_tokenizer(1);
+    let mut input_ids = [0i64; 2];
+    let mut attention_mask = [0i64; 2];
+    let destination = CBatchTensorDest {
+        input_ids: input_ids.as_mut_ptr().cast::<c_void>(),
+        attention_mask: attention_mask.as_mut_ptr().cast::<c_void>(),
+        token_type_ids: ptr::null_mut(),
+        position_ids: ptr::null_mut(),
+        capacity: 2,
+    };
+
+    let (rows, sequence_length, status) = encode_tensors(&tokenizer, 0, &destination);
+
+    assert_eq!(rows, 0);
+    assert_ne!(status, 0);
+    assert_eq!(sequence_length, 2);
+}
+
+#[test]
+fn tokenizers_encode_batch_tensors_rejects_unknown_element_type() {
+    let tokenizer = test_helpers::create_tokenizer();
+    let mut input_ids = [0i64; 4];
+    let mut attention_mask = [0i64; 4];
+    let destination = CBatchTensorDest {
+        input_ids: input_ids.as_mut_ptr().cast::<c_void>(),
+        attention_mask: attention_mask.as_mut_ptr().cast::<c_void>(),
+        token_type_ids: ptr::null_mut(),
+        position_ids: ptr::null_mut(),
+        capacity: 4,
+    };
+
+    let (rows, _, status) = encode_tensors(&tokenizer, 5, &destination);
+
+    assert_eq!(rows, 0);
+    assert_ne!(status, 0);
+}
@@ -0,0 +1,175 @@
+use std::ffi::{c_char, CStr, CString};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::ptr;
+use tokenx_bridge::ffi::decode::tokenizers_decode;
+use tokenx_bridge::ffi::lean_encoding::tokenizers_encode_ids;
+use tokenx_bridge::ffi::lifecycle::{tokenizers_free, tokenizers_free_string};
+use tokenx_bridge::ffi::tiktoken::tokenizers_from_tiktoken;
+use tokenx_bridge::CTokenizer;
+
+const MERGED_TOKENS: &[&str] = &[
+    "he", "ll", "llo", "hello", "aa", "aaa", "wo", "or", "wor", "ld", "world", "in", "ing",
+    "\u{e9}",
+];
+
+fn take_string(value: *mut c_char) -> String {
+    assert!(!value.is_null());
+    let text = unsafe { CStr::from_ptr(value) }
+        .to_str()
+        .unwrap()
+        .to_string();
+    unsafe { tokenizers_free_string(value) };
+    text
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::new();
+    for chunk in bytes.chunks(3) {
+        let value = chunk.iter().enumerate().fold(0u32, |acc, (index, byte)| {
+            acc | u32::from(*byte) << (16 - 8 * index)
+        });
+        for index in 0..4 {
+            if index <= chunk.len() {
+                out.push(ALPHABET[(value >> (18 - 6 * index) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Every single byte, ranked by value, followed by `MERGED_TOKENS` in order.
+fn ranks() -> Vec<(Vec<u8>, u32)> {
+    let mut ranks: Vec<(Vec<u8>, u32)> = (0..=255u8)
+        .map(|byte| (vec![byte], u32::from(byte)))
+        .collect();
+    for (index, token) in MERGED_TOKENS.iter().enumerate() {
+        ranks.push((token.as_bytes().to_vec(), 256 + index as u32));
+    }
+    ranks
+}
+
+fn write_rank_file(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "tokenx-tiktoken-{}-{name}.tiktoken",
+        std::process::id()
+    ));
+    let content: String = ranks()
+        .iter()
+        .map(|(token, rank)| format!("{} {rank}\n", base64(token)))
+        .collect();
+    fs::write(&path, content).unwrap();
+    path
+}
+
+fn load(path: &Path, preset: i32, pattern: Option<&str>, status: &mut i32) -> *mut CTokenizer {
+    let path = CString::new(path.to_str().unwrap()).unwrap();
+    let pattern = pattern.map(|value| CString::new(value).unwrap());
+    unsafe {
+        tokenizers_from_tiktoken(
+            path.as_ptr(),
+            preset,
+            pattern.as_ref().map_or(ptr::null(), |value| value.as_ptr()),
+            ptr::null(),
+            status as *mut i32,
+        )
+    }
+}
+
+fn encode(tokenizer: *const CTokenizer, text: &str) -> Vec<u32> {
+    let text = CString::new(text).unwrap();
+    let mut ids = [0u32; 64];
+    let mut length = 0usize;
+    let mut status = -1;
+    unsafe {
+        tokenizers_encode_ids(
+            tokenizer,
+            text.as_ptr(),
+            ptr::null(),
+            true,
+            ids.as_mut_ptr(),
+            ptr::null_mut(),
+            ids.len(),
+            ptr::addr_of_mut!(length),
+            ptr::addr_of_mut!(status),
+        );
+    }
+    assert_eq!(status, 0);
+    ids[..length].to_vec()
+}
+
+/// Rank file and expected ids under tests/fixtures/tiktoken. The ids were produced by tiktoken's
+/// `CoreBPE` (the copy vendored in tiktoken-rs 0.7.0) from the same rank file, with the
+/// cl100k_base and o200k_base split patterns and special tokens, and with `\S+|\s+` for the custom
+/// encoding.
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/tiktoken")
+        .join(name)
+}
+
+#[test]
+fn rank_file_encoding_matches_tiktoken_fixture() {
+    let expected: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(fixture("expected.json")).unwrap()).unwrap();
+
+    for (name, preset, pattern) in [
+        ("cl100k_base", 0, None),
+        ("o200k_base", 1, None),
+        ("custom", -1, Some(r"\S+|\s+")),
+    ] {
+        let mut status = -1;
+        let tokenizer = load(&fixture("ranks.tiktoken"), preset, pattern, &mut status);
+        assert_eq!(status, 0);
+
+        for case in expected[name].as_array().unwrap() {
+            let text = case["text"].as_str().unwrap();
+            let ids: Vec<u32> = case["ids"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|id| id.as_u64().unwrap() as u32)
+                .collect();
+            assert_eq!(encode(tokenizer, text), ids, "{name} mismatch for {text:?}");
+        }
+
+        unsafe { tokenizers_free(tokenizer) };
+    }
+}
+
+#[test]
+fn cl100k_preset_splits_and_keeps_special_tokens() {
+    let path = write_rank_file("cl100k");
+    let mut status = -1;
+    let tokenizer = load(&path, 0, None, &mut status);
+    assert_eq!(status, 0);
+
+    let ids = encode(tokenizer, "hello world<|endoftext|>");
+    assert_eq!(ids, vec![259, 32, 266, 100257]);
+
+    let text = unsafe {
+        tokenizers_decode(
+            tokenizer,
+            ids.as_ptr(),
+            ids.len(),
+            false,
+            ptr::addr_of_mut!(status),
+        )
+    };
+    assert_eq!(status, 0);
+    assert_eq!(take_string(text), "hello world<|endoftext|>");
+
+    unsafe { tokenizers_free(tokenizer) };
+}
+
+#[test]
+fn custom_encoding_requires_a_pattern() {
+    let path = write_rank_file("custom");
+    let mut status = -1;
+    let tokenizer = load(&path, -1, None, &mut status);
+    assert!(tokenizer.is_null());
+    assert_eq!(status, 4);
+}
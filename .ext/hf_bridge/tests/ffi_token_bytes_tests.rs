@@ -0,0 +1,126 @@
+use std::ffi::CString;
+use std::ptr;
+use tokenx_bridge::ffi::lifecycle::{tokenizers_create, tokenizers_free};
+use tokenx_bridge::ffi::test_helpers;
+use tokenx_bridge::ffi::token_bytes::{tokenizers_token_bytes, tokenizers_tokens_bytes};
+use tokenx_bridge::CTokenizer;
+
+const SPECIAL: u32 = 1;
+const INCOMPLETE: u32 = 2;
+
+fn create_llama_style_tokenizer() -> *mut CTokenizer {
+    let json = CString::new(
+        r#"{
+            "version": "1.0",
+            "truncation": null,
+            "padding": null,
+            "added_tokens": [],
+            "normalizer": null,
+            "pre_tokenizer": null,
+            "post_processor": null,
+            "decoder": {"type": "Sequence", "decoders": [
+                {"type": "Replace", "pattern": {"String": "▁"}, "content": " "},
+                {"type": "ByteFallback"},
+                {"type": "Fuse"},
+                {"type": "Strip", "content": " ", "start": 1, "stop": 0}
+            ]},
+            "model": {
+                "type": "WordLevel",
+                "vocab": {"<unk>": 0, "▁hello": 1, "<0xE2>": 2},
+                "unk_token": "<unk>"
+            }
+        }"#,
+    )
+    .unwrap();
+    let mut status = -1;
+    let tokenizer = unsafe { tokenizers_create(json.as_ptr(), ptr::addr_of_mut!(status)) };
+    assert_eq!(status, 0);
+    tokenizer
+}
+
+fn token_bytes(tokenizer: &CTokenizer, id: u32) -> (Vec<u8>, u32, i32) {
+    let mut buffer = [0u8; 16];
+    let mut length = 0usize;
+    let mut flags = 0u32;
+    let mut status = -1;
+
+    unsafe {
+        tokenizers_token_bytes(
+            tokenizer as *const CTokenizer,
+            id,
+            buffer.as_mut_ptr(),
+            buffer.len(),
+            ptr::addr_of_mut!(length),
+            ptr::addr_of_mut!(flags),
+            ptr::addr_of_mut!(status),
+        );
+    }
+
+    (buffer[..length].to_vec(), flags, status)
+}
+
+#[test]
+fn tokenizers_token_bytes_undoes_byte_level_mapping() {
+    let tokenizer = test_helpers::create_byte_level_tokenizer();
+
+    assert_eq!(token_bytes(&tokenizer, 3), (b" caf".to_vec(), 0, 0));
+    assert_eq!(token_bytes(&tokenizer, 1), (vec![0xC3], INCOMPLETE, 0));
+    assert_eq!(token_bytes(&tokenizer, 5), (b"</s>".to_vec(), SPECIAL, 0));
+}
+
+#[test]
+fn tokenizers_token_bytes_handles_byte_fallback_and_metaspace() {
+    let handle = create_llama_style_tokenizer();
+    let tokenizer = unsafe { &*handle };
+
+    assert_eq!(token_bytes(tokenizer, 1), (b" hello".to_vec(), 0, 0));
+    assert_eq!(token_bytes(tokenizer, 2), (vec![0xE2], INCOMPLETE, 0));
+    assert_eq!(token_bytes(tokenizer, 42).2, 2);
+
+    unsafe { tokenizers_free(handle) };
+}
+
+#[test]
+fn tokenizers_tokens_bytes_fills_flat_buffer_after_size_query() {
+    let tokenizer = test_helpers::create_byte_level_tokenizer();
+    let ids = [4u32, 3, 1, 2];
+    let mut offsets = [0usize; 5];
+    let mut flags = [9u32; 4];
+    let mut required = 0usize;
+    let mut status = -1;
+
+    let written = unsafe {
+        tokenizers_tokens_bytes(
+            &tokenizer as *const CTokenizer,
+            ids.as_ptr(),
+            ids.len(),
+            ptr::null_mut(),
+            0,
+            offsets.as_mut_ptr(),
+            flags.as_mut_ptr(),
+            ptr::addr_of_mut!(required),
+            ptr::addr_of_mut!(status),
+        )
+    };
+    assert_eq!((written, required, status), (0, 11, 0));
+
+    let mut buffer = vec![0u8; required];
+    let written = unsafe {
+        tokenizers_tokens_bytes(
+            &tokenizer as *const CTokenizer,
+            ids.as_ptr(),
+            ids.len(),
+            buffer.as_mut_ptr(),
+            buffer.len(),
+            offsets.as_mut_ptr(),
+            flags.as_mut_ptr(),
+            ptr::addr_of_mut!(required),
+            ptr::addr_of_mut!(status),
+        )
+    };
+
+    assert_eq!((written, status), (4, 0));
+    assert_eq!(std::str::from_utf8(&buffer).unwrap(), "hello caf\u{e9}");
+    assert_eq!(offsets, [0, 5, 9, 10, 11]);
+    assert_eq!(flags, [0, 0, INCOMPLETE, INCOMPLETE]);
+}
@@ -0,0 +1,183 @@
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+use tokenx_bridge::ffi::lifecycle::{tokenizers_create, tokenizers_free, tokenizers_free_string};
+use tokenx_bridge::ffi::test_helpers;
+use tokenx_bridge::ffi::trace::{
+    tokenizers_trace_model, tokenizers_trace_normalize, tokenizers_trace_post_process,
+    tokenizers_trace_pre_tokenize,
+};
+use tokenx_bridge::CTokenizer;
+
+const BPE_TOKENIZER: &str = r#"{
+    "version": "1.0",
+    "truncation": null,
+    "padding": null,
+    "added_tokens": [],
+    "normalizer": {"type": "Sequence", "normalizers": [{"type": "NFD"}, {"type": "StripAccents"}, {"type": "Lowercase"}]},
+    "pre_tokenizer": {"type": "Whitespace"},
+    "post_processor": {"type": "BertProcessing", "sep": ["[SEP]", 9], "cls": ["[CLS]", 8]},
+    "decoder": null,
+    "model": {
+        "type": "BPE",
+        "dropout": null,
+        "unk_token": null,
+        "continuing_subword_prefix": null,
+        "end_of_word_suffix": null,
+        "fuse_unk": false,
+        "byte_fallback": false,
+        "ignore_merges": false,
+        "vocab": {"h": 0, "e": 1, "l": 2, "o": 3, "he": 4, "ll": 5, "hell": 6, "hello": 7, "[CLS]": 8, "[SEP]": 9},
+        "merges": ["l l", "h e", "he ll", "hell o"]
+    }
+}"#;
+
+fn create_bpe_tokenizer() -> *mut CTokenizer {
+    let json = CString::new(BPE_TOKENIZER).unwrap();
+    let mut status = -1;
+    let tokenizer = unsafe { tokenizers_create(json.as_ptr(), ptr::addr_of_mut!(status)) };
+    assert_eq!(status, 0);
+    tokenizer
+}
+
+fn take_json(value: *mut c_char, status: i32) -> serde_json::Value {
+    assert_eq!(status, 0);
+    let json = unsafe { CStr::from_ptr(value) }
+        .to_str()
+        .unwrap()
+        .to_string();
+    unsafe { tokenizers_free_string(value) };
+    serde_json::from_str(&json).unwrap()
+}
+
+#[test]
+fn tokenizers_trace_normalize_aligns_to_original_chars() {
+    let tokenizer = create_bpe_tokenizer();
+    let text = CString::new("H\u{e9}llo").unwrap();
+    let mut status = -1;
+
+    let trace = take_json(
+        unsafe {
+            tokenizers_trace_normalize(tokenizer, text.as_ptr(), 1, ptr::addr_of_mut!(status))
+        },
+        status,
+    );
+
+    assert_eq!(trace["normalized"], "hello");
+    assert_eq!(
+        trace["alignments"][1],
+        serde_json::json!({"normalized": [1, 2], "original": [1, 2]})
+    );
+    assert_eq!(trace["alignments"].as_array().unwrap().len(), 5);
+
+    unsafe { tokenizers_free(tokenizer) };
+}
+
+#[test]
+fn tokenizers_trace_pre_tokenize_reports_splits() {
+    let tokenizer = test_helpers::create_tokenizer();
+    let text = CString::new("hello  world!").unwrap();
+    let mut status = -1;
+
+    let trace = take_json(
+        unsafe {
+            tokenizers_trace_pre_tokenize(
+                &tokenizer as *const CTokenizer,
+                text.as_ptr(),
+                0,
+                ptr::addr_of_mut!(status),
+            )
+        },
+        status,
+    );
+
+    assert_eq!(
+        trace["splits"],
+        serde_json::json!([
+            {"text": "hello", "offsets": [0, 5]},
+            {"text": "world", "offsets": [7, 12]},
+            {"text": "!", "offsets": [12, 13]}
+        ])
+    );
+}
+
+#[test]
+fn tokenizers_trace_model_replays_bpe_merges() {
+    let tokenizer = create_bpe_tokenizer();
+    let word = CString::new("hello").unwrap();
+    let mut status = -1;
+
+    let trace = take_json(
+        unsafe { tokenizers_trace_model(tokenizer, word.as_ptr(), 0, ptr::addr_of_mut!(status)) },
+        status,
+    );
+
+    assert_eq!(trace["model"], "BPE");
+    assert_eq!(
+        trace["tokens"],
+        serde_json::json!([{"id": 7, "value": "hello", "offsets": [0, 5]}])
+    );
+    let results: Vec<&str> = trace["merges"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|step| step["result"].as_str().unwrap())
+        .collect();
+    assert_eq!(results, ["ll", "he", "hell", "hello"]);
+
+    // The merge ranks are read once per tokenizer; later traces reuse them.
+    let word = CString::new("hell").unwrap();
+    let trace = take_json(
+        unsafe { tokenizers_trace_model(tokenizer, word.as_ptr(), 0, ptr::addr_of_mut!(status)) },
+        status,
+    );
+    let ranks: Vec<u64> = trace["merges"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|step| step["rank"].as_u64().unwrap())
+        .collect();
+    assert_eq!(ranks, [0, 1, 2]);
+
+    unsafe { tokenizers_free(tokenizer) };
+}
+
+#[test]
+fn tokenizers_trace_post_process_flags_inserted_tokens() {
+    let tokenizer = create_bpe_tokenizer();
+    let sequence = CString::new("Hello").unwrap();
+    let mut status = -1;
+
+    let trace = take_json(
+        unsafe {
+            tokenizers_trace_post_process(
+                tokenizer,
+                sequence.as_ptr(),
+                ptr::null(),
+                ptr::addr_of_mut!(status),
+            )
+        },
+        status,
+    );
+
+    let summary: Vec<(String, bool)> = trace["tokens"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|token| {
+            (
+                token["token"].as_str().unwrap().to_string(),
+                token["inserted"].as_bool().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("[CLS]".to_string(), true),
+            ("hello".to_string(), false),
+            ("[SEP]".to_string(), true)
+        ]
+    );
+
+    unsafe { tokenizers_free(tokenizer) };
+}
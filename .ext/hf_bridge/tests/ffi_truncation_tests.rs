@@ -0,0 +1,160 @@
+use std::ffi::{CStr, CString};
+use std::ptr;
+use tokenx_bridge::ffi::lifecycle::{tokenizers_create, tokenizers_free, tokenizers_free_string};
+use tokenx_bridge::ffi::test_helpers;
+use tokenx_bridge::ffi::truncation::tokenizers_truncate_text;
+use tokenx_bridge::CTokenizer;
+
+fn truncate(max_tokens: usize, strategy: i32, marker: Option<&str>) -> (String, usize, bool, i32) {
+    let tokenizer = test_helpers::create_tokenizer();
+    let text = CString::new("hello world hello world").unwrap();
+    let marker = marker.map(|value| CString::new(value).unwrap());
+    let mut token_count = 0usize;
+    let mut marker_dropped = true;
+    let mut status = -1;
+
+    let result = unsafe {
+        tokenizers_truncate_text(
+            &tokenizer as *const CTokenizer,
+            text.as_ptr(),
+            max_tokens,
+            strategy,
+            marker.as_ref().map_or(ptr::null(), |value| value.as_ptr()),
+            true,
+            ptr::addr_of_mut!(token_count),
+            ptr::addr_of_mut!(marker_dropped),
+            ptr::addr_of_mut!(status),
+        )
+    };
+
+    assert!(!result.is_null());
+    let value = unsafe { CStr::from_ptr(result) }
+        .to_str()
+        .unwrap()
+        .to_owned();
+    unsafe {
+        tokenizers_free_string(result);
+    }
+    (value, token_count, marker_dropped, status)
+}
+
+#[test]
+fn tokenizers_truncate_text_keeps_head_or_tail() {
+    assert_eq!(
+        truncate(2, 0, None),
+        ("hello world".to_string(), 2, false, 0)
+    );
+    assert_eq!(
+        truncate(3, 1, None),
+        ("world hello world".to_string(), 3, false, 0)
+    );
+}
+
+#[test]
+fn tokenizers_truncate_text_elides_middle_with_marker() {
+    assert_eq!(
+        truncate(3, 2, Some(" ... ")),
+        ("hello ... world".to_string(), 3, false, 0)
+    );
+}
+
+#[test]
+fn tokenizers_truncate_text_reports_marker_that_does_not_fit() {
+    assert_eq!(
+        truncate(1, 2, Some(" ... ... ")),
+        ("hello".to_string(), 1, true, 0)
+    );
+}
+
+#[test]
+fn tokenizers_truncate_text_returns_text_within_budget_unchanged() {
+    assert_eq!(
+        truncate(8, 0, Some("...")),
+        ("hello world hello world".to_string(), 4, false, 0)
+    );
+}
+
+#[test]
+fn tokenizers_truncate_text_rejects_unknown_strategy() {
+    let tokenizer = test_helpers::create_tokenizer();
+    let text = CString::new("hello").unwrap();
+    let mut status = -1;
+
+    let result = unsafe {
+        tokenizers_truncate_text(
+            &tokenizer as *const CTokenizer,
+            text.as_ptr(),
+            1,
+            7,
+            ptr::null(),
+            true,
+            ptr::null_mut(),
+            ptr::null_mut(),
+            ptr::addr_of_mut!(status),
+        )
+    };
+
+    assert!(result.is_null());
+    assert_ne!(status, 0);
+}
+
+#[test]
+fn tokenizers_truncate_text_rejects_budget_below_special_tokens() {
+    let json = CString::new(
+        r#"{
+            "version": "1.0",
+            "truncation": null,
+            "padding": null,
+            "added_tokens": [
+                {"id": 3, "content": "[CLS]", "single_word": false, "lstrip": false, "rstrip": false, "normalized": false, "special": true},
+                {"id": 4, "content": "[SEP]", "single_word": false, "lstrip": false, "rstrip": false, "normalized": false, "special": true}
+            ],
+            "normalizer": null,
+            "pre_tokenizer": {"type": "Whitespace"},
+            "post_processor": {
+                "type": "TemplateProcessing",
+                "single": [{"SpecialToken": {"id": "[CLS]", "type_id": 0}}, {"Sequence": {"id": "A", "type_id": 0}}, {"SpecialToken": {"id": "[SEP]", "type_id": 0}}],
+                "pair": [{"Sequence": {"id": "A", "type_id": 0}}, {"Sequence": {"id": "B", "type_id": 1}}],
+                "special_tokens": {
+                    "[CLS]": {"id": "[CLS]", "ids": [3], "tokens": ["[CLS]"]},
+                    "[SEP]": {"id": "[SEP]", "ids": [4], "tokens": ["[SEP]"]}
+                }
+            },
+            "decoder": null,
+            "model": {"type": "WordLevel", "vocab": {"[UNK]": 0, "hello": 1, "world": 2, "[CLS]": 3, "[SEP]": 4}, "unk_token": "[UNK]"}
+        }"#,
+    )
+    .unwrap();
+    let mut status = -1;
+    let tokenizer = unsafe { tokenizers_create(json.as_ptr(), ptr::addr_of_mut!(status)) };
+    assert_eq!(status, 0);
+
+    let text = CString::new("hello world hello world").unwrap();
+    let mut token_count = 0usize;
+    let truncate = |max_tokens: usize, status: &mut i32, token_count: &mut usize| unsafe {
+        tokenizers_truncate_text(
+            tokenizer,
+            text.as_ptr(),
+            max_tokens,
+            0,
+            ptr::null(),
+            true,
+            token_count as *mut usize,
+            ptr::null_mut(),
+            status as *mut i32,
+        )
+    };
+
+    let result = truncate(1, &mut status, &mut token_count);
+    assert!(result.is_null());
+    assert_eq!(status, 6);
+
+    let result = truncate(3, &mut status, &mut token_count);
+    assert_eq!(status, 0);
+    assert_eq!(token_count, 3);
+    assert_eq!(unsafe { CStr::from_ptr(result) }.to_str().unwrap(), "hello");
+    unsafe {
+        tokenizers_free_string(result);
+        tokenizers_free(tokenizer);
+    }
+}
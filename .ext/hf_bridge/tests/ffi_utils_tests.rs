@@ -1,7 +1,8 @@
 use std::ffi::CString;
 use std::ptr;
 use tokenx_bridge::ffi::utils_test_support::{
-    copy_slice, read_optional_utf8, read_required_utf8, set_length, set_status,
+    copy_slice, read_optional_utf16, read_optional_utf8, read_optional_utf8_array,
+    read_required_utf16, read_required_utf8, read_utf8_array, set_length, set_status,
 };
 
 #[test]
@@ -42,3 +43,31 @@ fn copy_slice_transfers_elements() {
     copy_slice(&source, destination.as_mut_ptr(), destination.len());
     assert_eq!(destination, source);
 }
+
+#[test]
+fn read_utf8_array_reads_every_entry() {
+    let first = CString::new("hello").unwrap();
+    let second = CString::new("world").unwrap();
+    let entries = [first.as_ptr(), second.as_ptr()];
+    let read = read_utf8_array(entries.as_ptr(), entries.len()).expect("read should succeed");
+    assert_eq!(read, vec!["hello".to_string(), "world".to_string()]);
+}
+
+#[test]
+fn read_optional_utf8_array_keeps_null_entries() {
+    let first = CString::new("hello").unwrap();
+    let entries = [first.as_ptr(), ptr::null()];
+    let read = read_optional_utf8_array(entries.as_ptr(), entries.len())
+        .expect("read should succeed")
+        .expect("array should be present");
+    assert_eq!(read, vec![Some("hello".to_string()), None]);
+    assert!(read_optional_utf8_array(ptr::null(), 2).unwrap().is_none());
+}
+
+#[test]
+fn read_required_utf16_decodes_surrogate_pairs() {
+    let units: Vec<u16> = "hi \u{1F600}".encode_utf16().collect();
+    let read = read_required_utf16(units.as_ptr(), units.len()).expect("read should succeed");
+    assert_eq!(read, "hi \u{1F600}");
+    assert!(read_optional_utf16(ptr::null(), 0).unwrap().is_none());
+}
@@ -0,0 +1,159 @@
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+use tokenx_bridge::ffi::lifecycle::tokenizers_free_string;
+use tokenx_bridge::ffi::test_helpers;
+use tokenx_bridge::ffi::vocab::{
+    tokenizers_get_added_tokens, tokenizers_get_vocab, tokenizers_get_vocab_flat,
+    tokenizers_get_vocab_size, tokenizers_ids_to_tokens, tokenizers_tokens_to_ids,
+};
+use tokenx_bridge::CTokenizer;
+
+fn take_json(value: *mut c_char) -> serde_json::Value {
+    assert!(!value.is_null());
+    let json = unsafe { CStr::from_ptr(value) }
+        .to_str()
+        .unwrap()
+        .to_string();
+    unsafe { tokenizers_free_string(value) };
+    serde_json::from_str(&json).unwrap()
+}
+
+#[test]
+fn tokenizers_get_vocab_reports_size_and_ordered_entries() {
+    let tokenizer = test_helpers::create_byte_level_tokenizer();
+    let handle = &tokenizer as *const CTokenizer;
+    let mut status = -1;
+
+    assert_eq!(
+        unsafe { tokenizers_get_vocab_size(handle, true, ptr::addr_of_mut!(status)) },
+        6
+    );
+    assert_eq!(status, 0);
+
+    let vocab =
+        take_json(unsafe { tokenizers_get_vocab(handle, false, ptr::addr_of_mut!(status)) });
+    let keys: Vec<&String> = vocab.as_object().unwrap().keys().collect();
+    assert_eq!(keys, ["[UNK]", "Ã", "©", "Ġcaf", "hello", "</s>"]);
+    assert_eq!(vocab["hello"], 4);
+}
+
+#[test]
+fn tokenizers_get_vocab_flat_writes_ids_and_tokens() {
+    let tokenizer = test_helpers::create_tokenizer();
+    let handle = &tokenizer as *const CTokenizer;
+    let mut count = 0usize;
+    let mut required = 0usize;
+    let mut status = -1;
+
+    unsafe {
+        tokenizers_get_vocab_flat(
+            handle,
+            true,
+            ptr::null_mut(),
+            0,
+            ptr::null_mut(),
+            0,
+            ptr::null_mut(),
+            ptr::addr_of_mut!(count),
+            ptr::addr_of_mut!(required),
+            ptr::addr_of_mut!(status),
+        );
+    }
+    assert_eq!((count, required, status), (3, 15, 0));
+
+    let mut ids = vec![0u32; count];
+    let mut buffer = vec![0u8; required];
+    let mut offsets = vec![0usize; count + 1];
+    let written = unsafe {
+        tokenizers_get_vocab_flat(
+            handle,
+            true,
+            ids.as_mut_ptr(),
+            ids.len(),
+            buffer.as_mut_ptr(),
+            buffer.len(),
+            offsets.as_mut_ptr(),
+            ptr::addr_of_mut!(count),
+            ptr::addr_of_mut!(required),
+            ptr::addr_of_mut!(status),
+        )
+    };
+
+    assert_eq!((written, status), (3, 0));
+    assert_eq!(ids, [0, 1, 2]);
+    assert_eq!(std::str::from_utf8(&buffer).unwrap(), "[UNK]helloworld");
+    assert_eq!(offsets, [0, 5, 10, 15]);
+}
+
+#[test]
+fn tokenizers_bulk_conversions_mark_unknown_entries() {
+    let tokenizer = test_helpers::create_tokenizer();
+    let handle = &tokenizer as *const CTokenizer;
+    let tokens: Vec<CString> = ["world", "missing", "hello"]
+        .iter()
+        .map(|token| CString::new(*token).unwrap())
+        .collect();
+    let token_ptrs: Vec<*const c_char> = tokens.iter().map(|token| token.as_ptr()).collect();
+    let mut ids = [0i32; 3];
+    let mut status = -1;
+
+    let found = unsafe {
+        tokenizers_tokens_to_ids(
+            handle,
+            token_ptrs.as_ptr(),
+            token_ptrs.len(),
+            ids.as_mut_ptr(),
+            ptr::addr_of_mut!(status),
+        )
+    };
+    assert_eq!((found, status), (2, 0));
+    assert_eq!(ids, [2, -1, 1]);
+
+    let lookup = [1u32, 7, 2];
+    let mut buffer = [0u8; 16];
+    let mut offsets = [0usize; 4];
+    let mut present = [true; 3];
+    let mut required = 0usize;
+    let written = unsafe {
+        tokenizers_ids_to_tokens(
+            handle,
+            lookup.as_ptr(),
+            lookup.len(),
+            buffer.as_mut_ptr(),
+            buffer.len(),
+            offsets.as_mut_ptr(),
+            present.as_mut_ptr(),
+            ptr::addr_of_mut!(required),
+            ptr::addr_of_mut!(status),
+        )
+    };
+
+    assert_eq!((written, required, status), (3, 10, 0));
+    assert_eq!(&buffer[..required], b"helloworld");
+    assert_eq!(offsets, [0, 5, 5, 10]);
+    assert_eq!(present, [true, false, true]);
+}
+
+#[test]
+fn tokenizers_get_added_tokens_lists_flags() {
+    let tokenizer = test_helpers::create_byte_level_tokenizer();
+    let mut status = -1;
+
+    let added = take_json(unsafe {
+        tokenizers_get_added_tokens(&tokenizer as *const CTokenizer, ptr::addr_of_mut!(status))
+    });
+
+    assert_eq!(status, 0);
+    assert_eq!(
+        added,
+        serde_json::json!([{
+            "id": 5,
+            "content": "</s>",
+            "special": true,
+            "lstrip": false,
+            "rstrip": false,
+            "normalized": false,
+            "single_word": false
+        }])
+    );
+}
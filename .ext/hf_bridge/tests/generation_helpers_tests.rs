@@ -1,6 +1,6 @@
 use serde_json::json;
 use tokenx_bridge::generation::test_support::{
-    collect_stop_sequences, convert_value_to_string, extract_numeric,
+    collect_eos_token_ids, collect_stop_sequences, convert_value_to_string, extract_numeric,
 };
 
 #[test]
@@ -30,3 +30,15 @@ fn convert_value_to_string_trims_whitespace() {
     );
     assert!(convert_value_to_string(&json!("   ")).is_none());
 }
+
+#[test]
+fn collect_eos_token_ids_accepts_single_and_list_values() {
+    let single = json!({ "eos_token_id": 2 }).as_object().cloned().unwrap();
+    assert_eq!(collect_eos_token_ids(&single), vec![2]);
+
+    let list = json!({ "eos_token_id": [7, "8", 7, -1] })
+        .as_object()
+        .cloned()
+        .unwrap();
+    assert_eq!(collect_eos_token_ids(&list), vec![7, 8]);
+}